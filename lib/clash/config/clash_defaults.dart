@@ -41,6 +41,8 @@ class ClashDefaults {
       'rule'; // 默认出站模式（rule/global/direct）
   static const int defaultKeepAliveInterval = 30; // TCP Keep-Alive 间隔（s）
   static const String defaultUserAgent = 'clash.meta'; // 默认 User-Agent
+  static const int providerModeRuleSetMinSize =
+      50; // 提供者模式下提取为 rule-provider 的最少连续规则数
 
   // ==================== TUN 配置默认值 ====================
   static const String defaultGeodataLoader = 'memconservative'; // GEO 数据加载模式
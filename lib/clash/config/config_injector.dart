@@ -1,6 +1,7 @@
 import 'dart:io';
 import 'package:path/path.dart' as path;
 import 'package:stelliberty/storage/clash_preferences.dart';
+import 'package:stelliberty/clash/config/clash_defaults.dart';
 import 'package:stelliberty/clash/services/dns_service.dart';
 import 'package:stelliberty/clash/services/geo_service.dart';
 import 'package:stelliberty/services/log_print_service.dart';
@@ -9,6 +10,24 @@ import 'package:stelliberty/src/bindings/signals/signals.dart';
 // Clash 配置注入器
 // 生成运行时配置文件（runtime_config.yaml），不修改订阅源文件
 class ConfigInjector {
+  // 覆写匹配条件上下文回调（运行时开关由 Rust 按运行时参数填充）
+  static OverrideMatchContext Function()? _getOverrideMatchContext;
+
+  static void setOverrideMatchContextGetter(
+    OverrideMatchContext Function() getter,
+  ) {
+    _getOverrideMatchContext = getter;
+  }
+
+  // 覆写追踪回调（开启追踪时每次生成配置后调用）
+  static void Function(List<OverrideTraceStep>)? _onOverrideTrace;
+
+  static void setOverrideTraceListener(
+    void Function(List<OverrideTraceStep>) listener,
+  ) {
+    _onOverrideTrace = listener;
+  }
+
   // 默认配置内容
   static String getDefaultConfigContent() {
     return 'proxies: []\nproxy-groups: []\nrules: []';
@@ -45,6 +64,7 @@ class ConfigInjector {
     try {
       // 1. 获取配置内容（优先级：configContent > configPath > 默认）
       String content;
+      var isSubscriptionContent = false;
 
       if (configContent != null && configContent.isNotEmpty) {
         content = configContent;
@@ -56,6 +76,7 @@ class ConfigInjector {
         } else {
           try {
             content = await configFile.readAsString();
+            isSubscriptionContent = true;
           } catch (e) {
             Logger.error('读取配置失败：$e');
             content = getDefaultConfigContent();
@@ -115,11 +136,29 @@ class ConfigInjector {
         dnsOverrideContent: dnsOverrideContent,
       );
 
+      // 提供者模式：仅在配置来自订阅文件时可用
+      final geoDataDir = await GeoService.getGeoDataDir();
+      ProviderModeParams? providerMode;
+      if (isSubscriptionContent &&
+          ClashPreferences.instance.getProviderModeEnabled()) {
+        providerMode = ProviderModeParams(
+          providerName: path.basenameWithoutExtension(configPath!),
+          subscriptionPath: configPath,
+          ruleProvidersDir: path.join(geoDataDir, 'rule_providers'),
+          ruleSetMinSize: ClashDefaults.providerModeRuleSetMinSize,
+        );
+      }
+
       // 3. 调用 Rust 处理
+      final isTraceEnabled = ClashPreferences.instance
+          .getOverrideTraceEnabled();
       final request = GenerateRuntimeConfigRequest(
         baseConfigContent: content,
         overrides: overrides,
         runtimeParams: params,
+        providerMode: providerMode,
+        isTraceEnabled: isTraceEnabled,
+        matchContext: _getOverrideMatchContext?.call(),
       );
 
       request.sendSignalToRust();
@@ -134,13 +173,22 @@ class ConfigInjector {
             },
           );
 
+      for (final line in response.message.overrideLogs) {
+        Logger.info('覆写脚本输出：$line');
+      }
+      for (final skipped in response.message.skippedOverrides) {
+        Logger.info('覆写已跳过：${skipped.overrideName} - ${skipped.reason}');
+      }
+      if (isTraceEnabled) {
+        _onOverrideTrace?.call(response.message.overrideTrace);
+      }
+
       if (!response.message.isSuccessful) {
         Logger.error('配置生成失败：${response.message.errorMessage}');
         return null;
       }
 
       // 4. 写入 runtime_config.yaml
-      final geoDataDir = await GeoService.getGeoDataDir();
       final runtimeConfigPath = path.join(geoDataDir, 'runtime_config.yaml');
       await File(
         runtimeConfigPath,
@@ -2,6 +2,7 @@ import 'dart:async';
 import 'package:stelliberty/clash/network/api_client.dart';
 import 'package:stelliberty/clash/services/process_service.dart';
 import 'package:stelliberty/clash/config/clash_defaults.dart';
+import 'package:stelliberty/clash/config/config_injector.dart';
 import 'package:stelliberty/clash/model/connection_model.dart';
 import 'package:stelliberty/clash/model/traffic_data_model.dart';
 import 'package:stelliberty/clash/model/log_message_model.dart';
@@ -49,6 +50,7 @@ class ClashManager {
   bool get isCoreRestarting => _lifecycleManager.isCoreRestarting;
   String? get currentConfigPath => _lifecycleManager.currentConfigPath;
   String get coreVersion => _lifecycleManager.coreVersion;
+  String? get lastKnownCoreVersion => _lifecycleManager.lastKnownCoreVersion;
 
   // TCP Keep-Alive 配置（启动参数，直接从持久化读取）
   bool get isKeepAliveEnabled =>
@@ -136,6 +138,20 @@ class ClashManager {
     Logger.debug('已设置覆写获取回调到 ClashManager');
   }
 
+  // 设置覆写匹配条件上下文回调（当前订阅与核心版本）
+  void setOverrideMatchContextGetter(OverrideMatchContext Function() getter) {
+    ConfigInjector.setOverrideMatchContextGetter(getter);
+    Logger.debug('已设置覆写匹配条件上下文回调');
+  }
+
+  // 设置覆写追踪回调（开启追踪时接收每次生成配置的追踪记录）
+  void setOverrideTraceListener(
+    void Function(List<OverrideTraceStep>) listener,
+  ) {
+    ConfigInjector.setOverrideTraceListener(listener);
+    Logger.debug('已设置覆写追踪回调');
+  }
+
   // 设置覆写失败回调（由 SubscriptionProvider 注入）
   void setOnOverridesFailed(Future<void> Function() handler) {
     _onOverridesFailed = handler;
@@ -92,6 +92,10 @@ class LifecycleManager {
   String _coreVersion = 'Unknown';
   String get coreVersion => _coreVersion;
 
+  // 最近一次获取到的核心版本（停止后保留，用于生成配置时判断覆写条件）
+  String? _lastKnownCoreVersion;
+  String? get lastKnownCoreVersion => _lastKnownCoreVersion;
+
   // 运行状态
   bool get isCoreRunning => _coreState.isRunning;
   bool get isCoreRestarting => _coreState == CoreState.restarting;
@@ -571,6 +575,7 @@ class LifecycleManager {
       final version = await _waitForIpcReady();
       if (version != null) {
         _coreVersion = version;
+        _lastKnownCoreVersion = version;
         _onCoreVersionChanged?.call(version);
       } else {
         Logger.warning('未能通过 IPC 获取版本号');
@@ -23,7 +23,10 @@ class OverrideManager {
 
   // 下载远程覆写
   // 根据 Clash 运行状态自动选择代理模式
-  Future<String> downloadRemoteOverride(OverrideConfig override) async {
+  Future<RemoteOverrideDownload> downloadRemoteOverride(
+    OverrideConfig override, {
+    String? etag,
+  }) async {
     final isClashRunning = _isCoreRunning();
 
     final effectiveProxyMode = isClashRunning
@@ -42,6 +45,7 @@ class OverrideManager {
       effectiveProxyMode,
       userAgent,
       mixedPort,
+      etag: etag,
     );
   }
 
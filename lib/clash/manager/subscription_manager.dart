@@ -2,7 +2,16 @@ import 'package:stelliberty/clash/model/subscription_model.dart';
 import 'package:stelliberty/clash/model/override_model.dart';
 import 'package:stelliberty/clash/services/subscription_service.dart';
 import 'package:stelliberty/clash/services/override_service.dart';
+import 'package:stelliberty/clash/config/clash_defaults.dart';
 import 'package:stelliberty/services/log_print_service.dart';
+import 'package:stelliberty/src/bindings/signals/signals.dart'
+    show
+        AggregateSubscriptionsResponse,
+        ConfigureSubscriptionScheduleRequest,
+        ExportProxyLinksResponse,
+        SetSubscriptionSchedulePaused,
+        SubscriptionInfoData,
+        SubscriptionSource;
 
 // 订阅管理器
 // 负责订阅相关的业务逻辑
@@ -71,7 +80,10 @@ class SubscriptionManager {
 
   // 下载订阅
   // 根据 Clash 运行状态自动选择代理模式
-  Future<Subscription> downloadSubscription(Subscription subscription) async {
+  Future<Subscription> downloadSubscription(
+    Subscription subscription, {
+    SubscriptionDownloadProgressHandler? onProgress,
+  }) async {
     final isClashRunning = _isCoreRunning();
 
     final effectiveProxyMode = isClashRunning
@@ -91,6 +103,47 @@ class SubscriptionManager {
       subscription,
       effectiveProxyMode,
       mixedPort,
+      onProgress: onProgress,
     );
   }
+
+  // 合并多个订阅内容
+  Future<AggregateSubscriptionsResponse> aggregateSubscriptions(
+    List<SubscriptionSource> sources,
+    int ruleSourceIndex,
+  ) async {
+    return await _service.aggregateSubscriptions(sources, ruleSourceIndex);
+  }
+
+  // 导出订阅节点的分享链接
+  Future<ExportProxyLinksResponse> exportProxyLinks(String content) async {
+    return await _service.exportProxyLinks(content);
+  }
+
+  // 将间隔更新的远程订阅同步到 Rust 侧调度器（整体替换计划）
+  void configureRefreshSchedule(List<Subscription> subscriptions) {
+    final mixedPort = _getMixedPort();
+    final scheduled = subscriptions
+        .where(
+          (s) => s.autoUpdateMode == AutoUpdateMode.interval && !s.isLocalFile,
+        )
+        .map((s) => _service.buildScheduledSubscription(s, mixedPort))
+        .toList();
+
+    ConfigureSubscriptionScheduleRequest(
+      subscriptions: scheduled,
+      maxConcurrency: ClashDefaults.subscriptionUpdateConcurrency,
+    ).sendSignalToRust();
+    Logger.info('已同步订阅自动更新计划：${scheduled.length} 个订阅');
+  }
+
+  // 暂停或恢复 Rust 侧调度器的自动更新
+  void setRefreshSchedulePaused(bool isPaused) {
+    SetSubscriptionSchedulePaused(isPaused: isPaused).sendSignalToRust();
+  }
+
+  // 转换订阅信息（Rust → Dart）
+  SubscriptionInfo? convertSubscriptionInfo(SubscriptionInfoData? rustInfo) {
+    return _service.convertSubscriptionInfo(rustInfo);
+  }
 }
@@ -21,13 +21,22 @@ enum OverrideType {
 // 覆写格式
 enum OverrideFormat {
   yaml('yaml', 'Yaml'),
-  js('js', 'JavaScript');
+  js('js', 'JavaScript'),
+  jsonPatch('json-patch', 'JSON Patch'),
+  mergePatch('merge-patch', 'Merge Patch');
 
   const OverrideFormat(this.value, this.displayName);
 
   final String value;
   final String displayName;
 
+  // 覆写文件扩展名
+  String get fileExtension => switch (this) {
+    OverrideFormat.yaml => 'yaml',
+    OverrideFormat.js => 'js',
+    OverrideFormat.jsonPatch || OverrideFormat.mergePatch => 'json',
+  };
+
   static OverrideFormat fromString(String value) {
     return values.firstWhere(
       (format) => format.value == value,
@@ -36,6 +45,78 @@ enum OverrideFormat {
   }
 }
 
+// 运行时开关（用于覆写匹配条件）
+enum OverrideRuntimeFlag {
+  tun('tun', 'TUN 模式'),
+  ipv6('ipv6', 'IPv6'),
+  allowLan('allow-lan', '局域网连接'),
+  dnsOverride('dns-override', 'DNS 覆写'),
+  keepAlive('keep-alive', 'TCP Keep-Alive'),
+  tcpConcurrent('tcp-concurrent', 'TCP 并发'),
+  unifiedDelay('unified-delay', '统一延迟');
+
+  const OverrideRuntimeFlag(this.value, this.displayName);
+
+  final String value;
+  final String displayName;
+
+  static OverrideRuntimeFlag? fromString(String value) {
+    for (final flag in values) {
+      if (flag.value == value) return flag;
+    }
+    return null;
+  }
+}
+
+// 覆写匹配条件（各项同时满足时应用覆写，未设置的项不参与判断）
+class OverrideConditions {
+  final String? subscriptionPattern; // 匹配订阅 ID 或订阅链接的正则表达式
+  final List<String> platforms; // 操作系统：windows / linux / macos / android
+  final Map<OverrideRuntimeFlag, bool> runtimeFlags; // 要求的运行时开关状态
+  final String? minCoreVersion; // 最低核心版本，如 v1.19.0
+
+  const OverrideConditions({
+    this.subscriptionPattern,
+    this.platforms = const [],
+    this.runtimeFlags = const {},
+    this.minCoreVersion,
+  });
+
+  // 是否未设置任何条件
+  bool get isEmpty =>
+      subscriptionPattern == null &&
+      platforms.isEmpty &&
+      runtimeFlags.isEmpty &&
+      minCoreVersion == null;
+
+  Map<String, dynamic> toJson() => {
+    'subscriptionPattern': subscriptionPattern,
+    'platforms': platforms,
+    'runtimeFlags': {
+      for (final entry in runtimeFlags.entries) entry.key.value: entry.value,
+    },
+    'minCoreVersion': minCoreVersion,
+  };
+
+  factory OverrideConditions.fromJson(Map<String, dynamic> json) {
+    final runtimeFlags = <OverrideRuntimeFlag, bool>{};
+    final rawFlags = json['runtimeFlags'] as Map<String, dynamic>? ?? {};
+    for (final entry in rawFlags.entries) {
+      final flag = OverrideRuntimeFlag.fromString(entry.key);
+      if (flag != null && entry.value is bool) {
+        runtimeFlags[flag] = entry.value as bool;
+      }
+    }
+
+    return OverrideConditions(
+      subscriptionPattern: json['subscriptionPattern'],
+      platforms: List<String>.from(json['platforms'] ?? const []),
+      runtimeFlags: runtimeFlags,
+      minCoreVersion: json['minCoreVersion'],
+    );
+  }
+}
+
 // 覆写配置
 class OverrideConfig {
   final String id;
@@ -47,6 +128,16 @@ class OverrideConfig {
   final String? content; // 缓存的内容
   final DateTime? lastUpdate; // 最后更新时间
   final SubscriptionProxyMode proxyMode; // 代理模式（仅远程覆写）
+  final String? arguments; // 脚本参数（JSON 对象，仅 JavaScript 覆写）
+  final OverrideConditions? conditions; // 匹配条件，不满足时跳过该覆写
+  final String? sha256Pin; // 固定的内容 SHA-256，下载内容不符时拒绝（仅远程覆写）
+  final String? minisignPublicKey; // minisign 公钥，下载内容需通过签名校验（仅远程覆写）
+  final String? signatureUrl; // 签名文件地址，未设置时为 url + .minisig
+  final int refreshIntervalMinutes; // 自动刷新间隔（分钟，0 为不自动刷新，仅远程覆写）
+  final String? etag; // 上次下载的 ETag
+  final String? approvedSha256; // 用户已批准的内容哈希（仅远程 JavaScript 覆写）
+  final String? pendingSha256; // 待用户批准的新内容哈希
+  final DownloadHttpSettings httpSettings; // 请求头、客户端证书与额外 CA（仅远程覆写）
 
   const OverrideConfig({
     required this.id,
@@ -58,8 +149,38 @@ class OverrideConfig {
     this.content,
     this.lastUpdate,
     this.proxyMode = SubscriptionProxyMode.direct,
+    this.arguments,
+    this.conditions,
+    this.sha256Pin,
+    this.minisignPublicKey,
+    this.signatureUrl,
+    this.refreshIntervalMinutes = 0,
+    this.etag,
+    this.approvedSha256,
+    this.pendingSha256,
+    this.httpSettings = const DownloadHttpSettings(),
   });
 
+  // 是否有待批准的远程内容更新
+  bool get hasPendingUpdate => pendingSha256 != null;
+
+  // 远程 JavaScript 覆写的内容变化需用户批准后才会生效
+  bool get requiresApproval =>
+      type == OverrideType.remote && format == OverrideFormat.js;
+
+  // 是否需要自动刷新
+  bool get shouldRefresh {
+    if (type != OverrideType.remote || refreshIntervalMinutes <= 0) {
+      return false;
+    }
+    if (lastUpdate == null) return true;
+
+    final nextRefreshTime = lastUpdate!.add(
+      Duration(minutes: refreshIntervalMinutes),
+    );
+    return DateTime.now().isAfter(nextRefreshTime);
+  }
+
   // 创建新覆写
   factory OverrideConfig.create({
     required String name,
@@ -69,6 +190,12 @@ class OverrideConfig {
     String? localPath,
     String? content,
     SubscriptionProxyMode proxyMode = SubscriptionProxyMode.direct,
+    String? arguments,
+    OverrideConditions? conditions,
+    String? sha256Pin,
+    String? minisignPublicKey,
+    int refreshIntervalMinutes = 0,
+    DownloadHttpSettings httpSettings = const DownloadHttpSettings(),
   }) {
     return OverrideConfig(
       id: DateTime.now().millisecondsSinceEpoch.toString(),
@@ -80,6 +207,12 @@ class OverrideConfig {
       content: content,
       lastUpdate: DateTime.now(),
       proxyMode: proxyMode,
+      arguments: arguments,
+      conditions: conditions,
+      sha256Pin: sha256Pin,
+      minisignPublicKey: minisignPublicKey,
+      refreshIntervalMinutes: refreshIntervalMinutes,
+      httpSettings: httpSettings,
     );
   }
 
@@ -92,6 +225,16 @@ class OverrideConfig {
     String? content,
     DateTime? lastUpdate,
     SubscriptionProxyMode? proxyMode,
+    String? arguments,
+    bool clearArguments = false,
+    OverrideConditions? conditions,
+    bool clearConditions = false,
+    int? refreshIntervalMinutes,
+    String? etag,
+    String? approvedSha256,
+    String? pendingSha256,
+    bool clearPendingSha256 = false,
+    DownloadHttpSettings? httpSettings,
   }) {
     return OverrideConfig(
       id: id,
@@ -103,6 +246,47 @@ class OverrideConfig {
       content: content ?? this.content,
       lastUpdate: lastUpdate ?? this.lastUpdate,
       proxyMode: proxyMode ?? this.proxyMode,
+      arguments: clearArguments ? null : (arguments ?? this.arguments),
+      conditions: clearConditions ? null : (conditions ?? this.conditions),
+      sha256Pin: sha256Pin,
+      minisignPublicKey: minisignPublicKey,
+      signatureUrl: signatureUrl,
+      refreshIntervalMinutes:
+          refreshIntervalMinutes ?? this.refreshIntervalMinutes,
+      etag: etag ?? this.etag,
+      approvedSha256: approvedSha256 ?? this.approvedSha256,
+      pendingSha256: clearPendingSha256
+          ? null
+          : (pendingSha256 ?? this.pendingSha256),
+      httpSettings: httpSettings ?? this.httpSettings,
+    );
+  }
+
+  // 修改完整性校验设置（清除 ETag，下次更新时重新下载并校验）
+  OverrideConfig copyWithIntegrity({
+    String? sha256Pin,
+    String? minisignPublicKey,
+  }) {
+    return OverrideConfig(
+      id: id,
+      name: name,
+      type: type,
+      format: format,
+      url: url,
+      localPath: localPath,
+      content: content,
+      lastUpdate: lastUpdate,
+      proxyMode: proxyMode,
+      arguments: arguments,
+      conditions: conditions,
+      sha256Pin: sha256Pin,
+      minisignPublicKey: minisignPublicKey,
+      signatureUrl: signatureUrl,
+      refreshIntervalMinutes: refreshIntervalMinutes,
+      etag: null,
+      approvedSha256: approvedSha256,
+      pendingSha256: pendingSha256,
+      httpSettings: httpSettings,
     );
   }
 
@@ -116,6 +300,16 @@ class OverrideConfig {
     // content 不序列化到 JSON，仅用于内存缓存
     'lastUpdate': lastUpdate?.toIso8601String(),
     'proxyMode': proxyMode.value,
+    'arguments': arguments,
+    'conditions': conditions?.toJson(),
+    'sha256Pin': sha256Pin,
+    'minisignPublicKey': minisignPublicKey,
+    'signatureUrl': signatureUrl,
+    'refreshIntervalMinutes': refreshIntervalMinutes,
+    'etag': etag,
+    'approvedSha256': approvedSha256,
+    'pendingSha256': pendingSha256,
+    'httpSettings': httpSettings.toJson(),
   };
 
   factory OverrideConfig.fromJson(Map<String, dynamic> json) {
@@ -133,6 +327,20 @@ class OverrideConfig {
       proxyMode: SubscriptionProxyMode.fromString(
         json['proxyMode'] ?? 'direct',
       ),
+      arguments: json['arguments'],
+      conditions: json['conditions'] != null
+          ? OverrideConditions.fromJson(json['conditions'])
+          : null,
+      sha256Pin: json['sha256Pin'],
+      minisignPublicKey: json['minisignPublicKey'],
+      signatureUrl: json['signatureUrl'],
+      refreshIntervalMinutes: json['refreshIntervalMinutes'] as int? ?? 0,
+      etag: json['etag'],
+      approvedSha256: json['approvedSha256'],
+      pendingSha256: json['pendingSha256'],
+      httpSettings: json['httpSettings'] != null
+          ? DownloadHttpSettings.fromJson(json['httpSettings'])
+          : const DownloadHttpSettings(),
     );
   }
 
@@ -1,4 +1,5 @@
 import '../config/clash_defaults.dart';
+import 'package:stelliberty/src/bindings/signals/signals.dart' as signals;
 
 // 订阅更新代理模式
 enum SubscriptionProxyMode {
@@ -46,6 +47,234 @@ enum AutoUpdateMode {
   }
 }
 
+// 客户端证书格式
+enum ClientCertificateType {
+  // PEM 证书链（可同时包含私钥）
+  pem('pem'),
+
+  // PKCS#12（.p12 / .pfx）
+  pkcs12('pkcs12');
+
+  const ClientCertificateType(this.value);
+
+  final String value;
+
+  static ClientCertificateType fromString(String value) {
+    return ClientCertificateType.values.firstWhere(
+      (type) => type.value == value,
+      orElse: () => ClientCertificateType.pem,
+    );
+  }
+}
+
+// 下载请求设置：自定义请求头、客户端证书与额外 CA（订阅与远程覆写共用）
+class DownloadHttpSettings {
+  final Map<String, String> headers; // 自定义请求头，例如 Authorization、Cookie
+  final ClientCertificateType certificateType;
+  final String? certificatePath; // PEM 证书或 PKCS#12 文件
+  final String? keyPath; // PEM 私钥（与证书分开存放时）
+  final String? certificatePassword; // PKCS#12 密码
+  final String? caBundlePath; // 额外信任的 CA 证书（PEM）
+
+  const DownloadHttpSettings({
+    this.headers = const {},
+    this.certificateType = ClientCertificateType.pem,
+    this.certificatePath,
+    this.keyPath,
+    this.certificatePassword,
+    this.caBundlePath,
+  });
+
+  // 是否未设置任何项
+  bool get isEmpty =>
+      headers.isEmpty && certificatePath == null && caBundlePath == null;
+
+  // 请求头文本（每行一个「名称: 值」）
+  String get headersText =>
+      headers.entries.map((e) => '${e.key}: ${e.value}').join('\n');
+
+  // 解析请求头文本，忽略空行，格式错误时抛出 FormatException
+  static Map<String, String> parseHeaders(String text) {
+    final headers = <String, String>{};
+    for (final line in text.split('\n')) {
+      final trimmed = line.trim();
+      if (trimmed.isEmpty) continue;
+
+      final separator = trimmed.indexOf(':');
+      if (separator <= 0) {
+        throw FormatException(trimmed);
+      }
+      headers[trimmed.substring(0, separator).trim()] = trimmed
+          .substring(separator + 1)
+          .trim();
+    }
+    return headers;
+  }
+
+  // 转换为下载请求参数，未设置任何项时为 null
+  signals.HttpRequestOptions? toRequestOptions() {
+    if (isEmpty) return null;
+
+    return signals.HttpRequestOptions(
+      headers: headers.entries
+          .map((e) => signals.HttpHeader(name: e.key, value: e.value))
+          .toList(),
+      clientCertificate: certificatePath == null
+          ? null
+          : signals.ClientCertificate(
+              format: certificateType == ClientCertificateType.pkcs12
+                  ? signals.ClientCertificateFormat.pkcs12
+                  : signals.ClientCertificateFormat.pem,
+              certificatePath: certificatePath!,
+              keyPath: keyPath,
+              password: certificatePassword,
+            ),
+      caBundlePath: caBundlePath,
+    );
+  }
+
+  Map<String, dynamic> toJson() => {
+    'headers': headers,
+    'certificateType': certificateType.value,
+    'certificatePath': certificatePath,
+    'keyPath': keyPath,
+    'certificatePassword': certificatePassword,
+    'caBundlePath': caBundlePath,
+  };
+
+  factory DownloadHttpSettings.fromJson(Map<String, dynamic> json) {
+    return DownloadHttpSettings(
+      headers: json['headers'] != null
+          ? Map<String, String>.from(json['headers'] as Map)
+          : const {},
+      certificateType: ClientCertificateType.fromString(
+        json['certificateType'] as String? ?? 'pem',
+      ),
+      certificatePath: json['certificatePath'] as String?,
+      keyPath: json['keyPath'] as String?,
+      certificatePassword: json['certificatePassword'] as String?,
+      caBundlePath: json['caBundlePath'] as String?,
+    );
+  }
+}
+
+// 订阅版本差异摘要：最近一次更新替换配置时的节点、代理组与规则变化
+class SubscriptionDiffSummary {
+  final List<String> addedNodes;
+  final List<String> removedNodes;
+  final List<String> renamedNodes; // 「旧名称 → 新名称」
+  final List<String> modifiedNodes;
+  final List<String> addedGroups;
+  final List<String> removedGroups;
+  final List<String> modifiedGroups;
+  final int addedRuleCount;
+  final int removedRuleCount;
+
+  const SubscriptionDiffSummary({
+    this.addedNodes = const [],
+    this.removedNodes = const [],
+    this.renamedNodes = const [],
+    this.modifiedNodes = const [],
+    this.addedGroups = const [],
+    this.removedGroups = const [],
+    this.modifiedGroups = const [],
+    this.addedRuleCount = 0,
+    this.removedRuleCount = 0,
+  });
+
+  factory SubscriptionDiffSummary.fromSignal(signals.ProfileDiff diff) {
+    return SubscriptionDiffSummary(
+      addedNodes: diff.addedNodes,
+      removedNodes: diff.removedNodes,
+      renamedNodes: diff.renamedNodes
+          .map((node) => '${node.oldName} → ${node.newName}')
+          .toList(),
+      modifiedNodes: diff.modifiedNodes.map((node) => node.name).toList(),
+      addedGroups: diff.addedGroups,
+      removedGroups: diff.removedGroups,
+      modifiedGroups: diff.modifiedGroups.map((group) => group.name).toList(),
+      addedRuleCount: diff.addedRuleCount,
+      removedRuleCount: diff.removedRuleCount,
+    );
+  }
+
+  // 是否有任何变化
+  bool get hasChanges =>
+      addedNodes.isNotEmpty ||
+      removedNodes.isNotEmpty ||
+      renamedNodes.isNotEmpty ||
+      modifiedNodes.isNotEmpty ||
+      addedGroups.isNotEmpty ||
+      removedGroups.isNotEmpty ||
+      modifiedGroups.isNotEmpty ||
+      addedRuleCount > 0 ||
+      removedRuleCount > 0;
+
+  Map<String, dynamic> toJson() => {
+    'addedNodes': addedNodes,
+    'removedNodes': removedNodes,
+    'renamedNodes': renamedNodes,
+    'modifiedNodes': modifiedNodes,
+    'addedGroups': addedGroups,
+    'removedGroups': removedGroups,
+    'modifiedGroups': modifiedGroups,
+    'addedRuleCount': addedRuleCount,
+    'removedRuleCount': removedRuleCount,
+  };
+
+  factory SubscriptionDiffSummary.fromJson(Map<String, dynamic> json) {
+    List<String> list(String key) =>
+        json[key] != null ? List<String>.from(json[key] as List) : const [];
+
+    return SubscriptionDiffSummary(
+      addedNodes: list('addedNodes'),
+      removedNodes: list('removedNodes'),
+      renamedNodes: list('renamedNodes'),
+      modifiedNodes: list('modifiedNodes'),
+      addedGroups: list('addedGroups'),
+      removedGroups: list('removedGroups'),
+      modifiedGroups: list('modifiedGroups'),
+      addedRuleCount: json['addedRuleCount'] as int? ?? 0,
+      removedRuleCount: json['removedRuleCount'] as int? ?? 0,
+    );
+  }
+}
+
+// 最近一次解析订阅内容的结果摘要
+class SubscriptionParseSummary {
+  final int acceptedCount; // 解析成功的节点数
+  final int skippedCount; // 无法解析而跳过的条目数
+  final int excludedCount; // 被排除规则过滤掉的节点数
+
+  const SubscriptionParseSummary({
+    this.acceptedCount = 0,
+    this.skippedCount = 0,
+    this.excludedCount = 0,
+  });
+
+  factory SubscriptionParseSummary.fromSignal(signals.ParseReport report) {
+    return SubscriptionParseSummary(
+      acceptedCount: report.acceptedCount,
+      skippedCount: report.rejectedEntries.length,
+      excludedCount: report.excludedCount,
+    );
+  }
+
+  Map<String, dynamic> toJson() => {
+    'acceptedCount': acceptedCount,
+    'skippedCount': skippedCount,
+    'excludedCount': excludedCount,
+  };
+
+  factory SubscriptionParseSummary.fromJson(Map<String, dynamic> json) {
+    return SubscriptionParseSummary(
+      acceptedCount: json['acceptedCount'] as int? ?? 0,
+      skippedCount: json['skippedCount'] as int? ?? 0,
+      excludedCount: json['excludedCount'] as int? ?? 0,
+    );
+  }
+}
+
 // 订阅信息（流量统计）
 class SubscriptionInfo {
   final int upload; // 已上传（字节）
@@ -136,6 +365,9 @@ class Subscription {
   final List<String> failedOverrideIds; // 失败的覆写 ID 列表(启动失败时记录)
   final String userAgent; // User-Agent（仅远程订阅有效，默认为 clash.meta）
   final bool hasConfigLoadFailed; // 配置加载失败标记（用于 UI 显示警告）
+  final DownloadHttpSettings httpSettings; // 请求头、客户端证书与额外 CA（仅远程订阅）
+  final SubscriptionDiffSummary? lastDiff; // 最近一次更新与旧版本的差异
+  final SubscriptionParseSummary? lastParseSummary; // 最近一次解析的节点统计
 
   const Subscription({
     required this.id,
@@ -155,6 +387,9 @@ class Subscription {
     this.failedOverrideIds = const [],
     this.userAgent = ClashDefaults.defaultUserAgent,
     this.hasConfigLoadFailed = false,
+    this.httpSettings = const DownloadHttpSettings(),
+    this.lastDiff,
+    this.lastParseSummary,
   });
 
   // 创建新订阅
@@ -215,6 +450,9 @@ class Subscription {
     List<String>? failedOverrideIds,
     String? userAgent,
     bool? hasConfigLoadFailed,
+    DownloadHttpSettings? httpSettings,
+    SubscriptionDiffSummary? lastDiff,
+    SubscriptionParseSummary? lastParseSummary,
   }) {
     return Subscription(
       id: id ?? this.id,
@@ -236,6 +474,9 @@ class Subscription {
       failedOverrideIds: failedOverrideIds ?? this.failedOverrideIds,
       userAgent: userAgent ?? this.userAgent,
       hasConfigLoadFailed: hasConfigLoadFailed ?? this.hasConfigLoadFailed,
+      httpSettings: httpSettings ?? this.httpSettings,
+      lastDiff: lastDiff ?? this.lastDiff,
+      lastParseSummary: lastParseSummary ?? this.lastParseSummary,
     );
   }
 
@@ -256,6 +497,9 @@ class Subscription {
     'failedOverrideIds': failedOverrideIds,
     'userAgent': userAgent,
     'hasConfigLoadFailed': hasConfigLoadFailed,
+    'httpSettings': httpSettings.toJson(),
+    'lastDiff': lastDiff?.toJson(),
+    'lastParseSummary': lastParseSummary?.toJson(),
   };
 
   factory Subscription.fromJson(Map<String, dynamic> json) {
@@ -290,6 +534,21 @@ class Subscription {
           : const [],
       userAgent: json['userAgent'] as String? ?? ClashDefaults.defaultUserAgent,
       hasConfigLoadFailed: json['hasConfigLoadFailed'] as bool? ?? false,
+      httpSettings: json['httpSettings'] != null
+          ? DownloadHttpSettings.fromJson(
+              json['httpSettings'] as Map<String, dynamic>,
+            )
+          : const DownloadHttpSettings(),
+      lastDiff: json['lastDiff'] != null
+          ? SubscriptionDiffSummary.fromJson(
+              json['lastDiff'] as Map<String, dynamic>,
+            )
+          : null,
+      lastParseSummary: json['lastParseSummary'] != null
+          ? SubscriptionParseSummary.fromJson(
+              json['lastParseSummary'] as Map<String, dynamic>,
+            )
+          : null,
     );
   }
 
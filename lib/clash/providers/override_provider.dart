@@ -1,3 +1,4 @@
+import 'dart:async';
 import 'dart:convert';
 import 'dart:io';
 import 'package:flutter/foundation.dart';
@@ -9,6 +10,8 @@ import 'package:stelliberty/clash/manager/override_manager.dart';
 import 'package:stelliberty/storage/clash_preferences.dart';
 import 'package:stelliberty/services/path_service.dart';
 import 'package:stelliberty/services/log_print_service.dart';
+import 'package:stelliberty/src/bindings/signals/signals.dart'
+    show OverrideTraceStep;
 
 // 全局覆写管理 Provider
 class OverrideProvider extends ChangeNotifier {
@@ -34,6 +37,18 @@ class OverrideProvider extends ChangeNotifier {
   // 覆写内容更新回调（通知订阅系统重载配置）
   Future<void> Function(String overrideId)? _onOverrideContentUpdated;
 
+  // 覆写追踪：开启后记录最近一次生成配置时各覆写的执行情况
+  bool _isTraceEnabled = false;
+  bool get isTraceEnabled => _isTraceEnabled;
+  List<OverrideTraceStep> _lastTrace = const [];
+  List<OverrideTraceStep> get lastTrace => _lastTrace;
+  DateTime? _lastTraceAt;
+  DateTime? get lastTraceAt => _lastTraceAt;
+
+  // 远程覆写自动刷新定时器（固定 1 分钟检查间隔）
+  Timer? _refreshTimer;
+  bool _isRefreshInProgress = false;
+
   // 设置覆写删除回调
   void setOnOverrideDeleted(Future<void> Function(String) handler) {
     _onOverrideDeleted = handler;
@@ -77,9 +92,15 @@ class OverrideProvider extends ChangeNotifier {
     try {
       // 加载覆写列表
       _overrides = await _loadOverrideList();
+      _isTraceEnabled = ClashPreferences.instance.getOverrideTraceEnabled();
 
       Logger.info('覆写 Provider 初始化成功，共 ${_overrides.length} 个覆写');
       _updateState(OverrideState.idle()); // '初始化完成');
+
+      _refreshTimer?.cancel();
+      _refreshTimer = Timer.periodic(const Duration(minutes: 1), (_) {
+        _checkAndRefresh();
+      });
     } catch (e) {
       final errorMsg = '初始化覆写失败: $e';
       Logger.error(errorMsg);
@@ -95,6 +116,24 @@ class OverrideProvider extends ChangeNotifier {
     }
   }
 
+  // 开启或关闭覆写追踪，下次生成配置时生效
+  Future<void> setTraceEnabled(bool enabled) async {
+    _isTraceEnabled = enabled;
+    if (!enabled) {
+      _lastTrace = const [];
+      _lastTraceAt = null;
+    }
+    await ClashPreferences.instance.setOverrideTraceEnabled(enabled);
+    notifyListeners();
+  }
+
+  // 接收生成配置时的覆写追踪记录
+  void updateTrace(List<OverrideTraceStep> trace) {
+    _lastTrace = trace;
+    _lastTraceAt = DateTime.now();
+    notifyListeners();
+  }
+
   // 添加覆写
   Future<bool> addOverride(OverrideConfig override) async {
     Logger.info('开始添加覆写');
@@ -110,13 +149,17 @@ class OverrideProvider extends ChangeNotifier {
         Logger.info('URL：${override.url}');
 
         try {
-          final content = await _manager.downloadRemoteOverride(override);
+          final download = await _manager.downloadRemoteOverride(override);
+          final content = download.content!;
           Logger.info('远程覆写下载成功，内容长度：${content.length}');
 
-          // 下载成功，更新覆写配置
+          // 下载成功，保存内容并更新覆写配置（添加即批准当前内容）
+          await _manager.saveOverrideContent(override, content);
           final updatedOverride = override.copyWith(
             content: content,
             lastUpdate: DateTime.now(),
+            etag: download.etag,
+            approvedSha256: override.requiresApproval ? download.sha256 : null,
           );
 
           _overrides.add(updatedOverride);
@@ -236,15 +279,32 @@ class OverrideProvider extends ChangeNotifier {
     try {
       Logger.info('开始更新远程覆写：${override.name}');
 
-      // 下载远程覆写
-      final content = await _manager.downloadRemoteOverride(override);
-
-      // 更新覆写配置
-      _overrides[index] = override.copyWith(
-        content: content,
-        lastUpdate: DateTime.now(),
+      // 已有内容时附带 ETag，内容未变化时不重新下载
+      final hasContent =
+          override.content != null && override.content!.isNotEmpty;
+      final download = await _manager.downloadRemoteOverride(
+        override,
+        etag: hasContent ? override.etag : null,
       );
 
+      if (download.isNotModified) {
+        Logger.info('远程覆写未变化：${override.name}');
+        _overrides[index] = override.copyWith(lastUpdate: DateTime.now());
+      } else if (override.requiresApproval &&
+          (hasContent || override.approvedSha256 != null) &&
+          download.sha256 != override.approvedSha256) {
+        // 远程脚本内容变化：保留当前内容，等待用户批准新哈希
+        Logger.warning(
+          '远程脚本内容已变化，等待批准：${override.name}（${download.sha256}）',
+        );
+        _overrides[index] = override.copyWith(
+          lastUpdate: DateTime.now(),
+          pendingSha256: download.sha256,
+        );
+      } else {
+        await _acceptDownload(index, override, download);
+      }
+
       await _saveOverrideList();
       Logger.info('更新远程覆写成功：${override.name}');
       return true;
@@ -269,6 +329,110 @@ class OverrideProvider extends ChangeNotifier {
     }
   }
 
+  // 批准远程脚本的新内容：重新下载，仅当哈希与待批准哈希一致时接受
+  Future<bool> approvePendingUpdate(String overrideId) async {
+    final index = _overrides.indexWhere((o) => o.id == overrideId);
+    if (index == -1) {
+      Logger.error('批准失败：覆写不存在 (ID：$overrideId)');
+      return false;
+    }
+
+    final override = _overrides[index];
+    final pendingSha256 = override.pendingSha256;
+    if (pendingSha256 == null) {
+      Logger.info('覆写没有待批准的更新：${override.name}');
+      return true;
+    }
+
+    _updateState(
+      _state.copyWith(updatingIds: {..._state.updatingIds, overrideId}),
+    ); // '开始批准远程覆写更新');
+    notifyListeners();
+
+    try {
+      final download = await _manager.downloadRemoteOverride(override);
+
+      if (download.sha256 != pendingSha256) {
+        Logger.warning('待批准的内容已再次变化：${override.name}（${download.sha256}）');
+        _overrides[index] = override.copyWith(pendingSha256: download.sha256);
+        await _saveOverrideList();
+        return false;
+      }
+
+      await _acceptDownload(index, override, download);
+      await _saveOverrideList();
+      Logger.info('已批准远程脚本新内容：${override.name}（$pendingSha256）');
+
+      if (_onOverrideContentUpdated != null) {
+        await _onOverrideContentUpdated!(overrideId);
+      }
+      return true;
+    } catch (e) {
+      Logger.error('批准远程覆写更新失败：${override.name} - $e');
+      return false;
+    } finally {
+      _updateState(
+        _state.copyWith(
+          updatingIds: _state.updatingIds
+              .where((id) => id != overrideId)
+              .toSet(),
+        ),
+      ); // '批准完成');
+      notifyListeners();
+    }
+  }
+
+  // 接受下载的新内容：保存文件并记录 ETag 与已批准哈希
+  Future<void> _acceptDownload(
+    int index,
+    OverrideConfig override,
+    RemoteOverrideDownload download,
+  ) async {
+    final content = download.content!;
+    await _manager.saveOverrideContent(override, content);
+
+    _overrides[index] = override.copyWith(
+      content: content,
+      lastUpdate: DateTime.now(),
+      etag: download.etag,
+      approvedSha256: override.requiresApproval ? download.sha256 : null,
+      clearPendingSha256: true,
+    );
+  }
+
+  // 检查并刷新到期的远程覆写
+  Future<void> _checkAndRefresh() async {
+    if (_isRefreshInProgress || _state.isBatchUpdating) {
+      return;
+    }
+
+    final dueOverrides = _overrides.where((o) => o.shouldRefresh).toList();
+    if (dueOverrides.isEmpty) {
+      return;
+    }
+
+    _isRefreshInProgress = true;
+    try {
+      Logger.info('定时检查：${dueOverrides.length} 个远程覆写需要刷新');
+
+      for (final override in dueOverrides) {
+        final previousContent = override.content;
+        final success = await updateRemoteOverride(override.id);
+        final updated = getOverrideById(override.id);
+
+        // 内容变化时通知订阅系统重载配置
+        if (success &&
+            updated != null &&
+            updated.content != previousContent &&
+            _onOverrideContentUpdated != null) {
+          await _onOverrideContentUpdated!(override.id);
+        }
+      }
+    } finally {
+      _isRefreshInProgress = false;
+    }
+  }
+
   // 删除覆写
   Future<bool> deleteOverride(String overrideId) async {
     final index = _overrides.indexWhere((o) => o.id == overrideId);
@@ -512,6 +676,8 @@ class OverrideProvider extends ChangeNotifier {
   @override
   void dispose() {
     // 清理回调，避免内存泄漏
+    _refreshTimer?.cancel();
+    _refreshTimer = null;
     _onOverrideDeleted = null;
     _onOverrideContentUpdated = null;
     super.dispose();
@@ -29,19 +29,28 @@ class SubscriptionProvider extends ChangeNotifier {
   // ClashProvider 引用（用于配置切换时重新加载代理信息）
   ClashProvider? _clashProvider;
 
-  // 自动更新定时器
-  Timer? _autoUpdateTimer;
+  // Rust 侧自动更新的进度与结果监听
+  StreamSubscription? _refreshProgressListener;
+  StreamSubscription? _refreshResultListener;
 
   // 启动时更新是否已完成
   bool _isStartupUpdateDone = false;
 
-  // 自动更新并发保护标记
-  bool _isAutoUpdateInProgress = false;
-
   // 订阅列表
   List<Subscription> _subscriptions = [];
   List<Subscription> get subscriptions => List.unmodifiable(_subscriptions);
 
+  // 更新失败并回退到缓存的订阅：订阅 ID → 缓存内容最近一次确认有效的时间
+  final Map<String, DateTime> _cacheFallbackTimes = {};
+
+  // 正在下载的订阅：订阅 ID → 下载进度（total 未知时为 0）
+  final Map<String, ({double progress, int downloaded, int total})>
+  _downloadProgress = {};
+
+  // 自动更新是否已被用户暂停（持久化，启动时同步到 Rust 侧调度器）
+  bool _isAutoUpdatePaused = false;
+  bool get isAutoUpdatePaused => _isAutoUpdatePaused;
+
   // 当前选中的订阅 ID
   String? _currentSubscriptionId;
   String? get currentSubscriptionId => _currentSubscriptionId;
@@ -70,6 +79,18 @@ class SubscriptionProvider extends ChangeNotifier {
     return _state.isSubscriptionUpdating(subscriptionId);
   }
 
+  // 最近一次更新失败并回退到缓存时，缓存内容的时间；更新成功后清除
+  DateTime? getCacheFallbackTime(String subscriptionId) {
+    return _cacheFallbackTimes[subscriptionId];
+  }
+
+  // 手动更新时的下载进度，未在下载时返回 null
+  ({double progress, int downloaded, int total})? getDownloadProgress(
+    String subscriptionId,
+  ) {
+    return _downloadProgress[subscriptionId];
+  }
+
   // 构造函数（接收共享的 OverrideService 实例）
   SubscriptionProvider(OverrideService overrideService) {
     final service = SubscriptionService();
@@ -236,8 +257,14 @@ class SubscriptionProvider extends ChangeNotifier {
 
       Logger.info('订阅 Provider 初始化成功，共 ${_subscriptions.length} 个订阅');
 
-      // 启动动态自动更新定时器
-      _restartAutoUpdateTimer();
+      // 自动更新由 Rust 侧调度器执行，这里只同步计划并接收结果
+      _listenRefreshSignals();
+      _syncRefreshSchedule();
+      _isAutoUpdatePaused = ClashPreferences.instance
+          .getSubscriptionAutoUpdatePaused();
+      if (_isAutoUpdatePaused) {
+        _manager.setRefreshSchedulePaused(true);
+      }
 
       _updateState(SubscriptionState.idle());
     } catch (e) {
@@ -264,6 +291,7 @@ class SubscriptionProvider extends ChangeNotifier {
     bool downloadNow = true,
     SubscriptionProxyMode proxyMode = SubscriptionProxyMode.direct,
     String? userAgent,
+    DownloadHttpSettings httpSettings = const DownloadHttpSettings(),
   }) async {
     // 不清除全局错误，单个订阅操作不影响全局状态
 
@@ -289,6 +317,7 @@ class SubscriptionProvider extends ChangeNotifier {
         shouldUpdateOnStartup: shouldUpdateOnStartup,
         proxyMode: proxyMode,
         userAgent: effectiveUserAgent,
+        httpSettings: httpSettings,
       );
 
       // 如果需要立即下载
@@ -341,8 +370,8 @@ class SubscriptionProvider extends ChangeNotifier {
 
       notifyListeners();
 
-      // 重新计算定时器间隔（新订阅可能启用了自动更新）
-      _restartAutoUpdateTimer();
+      // 同步自动更新计划（新订阅可能启用了自动更新）
+      _syncRefreshSchedule();
 
       Logger.info('添加订阅成功：$name');
       return true;
@@ -439,9 +468,18 @@ class SubscriptionProvider extends ChangeNotifier {
       // 下载订阅
       final updatedSubscription = await _manager.downloadSubscription(
         subscription,
+        onProgress: (progress, downloaded, total) {
+          _downloadProgress[subscriptionId] = (
+            progress: progress,
+            downloaded: downloaded,
+            total: total,
+          );
+          notifyListeners();
+        },
       );
 
       // 更新列表（确保清除错误信息和配置失败标记）
+      _cacheFallbackTimes.remove(subscriptionId);
       _subscriptions[index] = updatedSubscription.copyWith(
         lastError: null,
         hasConfigLoadFailed: false, // 更新成功后清除配置失败标记
@@ -461,9 +499,6 @@ class SubscriptionProvider extends ChangeNotifier {
       }
       Logger.info('更新订阅成功：${subscription.name}');
 
-      // 注意：不在这里重启定时器，避免批量更新时频繁重启
-      // 定时器会在 autoUpdateSubscriptions() 完成后统一重启
-
       return true;
     } catch (e) {
       final rawError = e.toString();
@@ -473,6 +508,12 @@ class SubscriptionProvider extends ChangeNotifier {
       final errorType = _classifyError(rawError);
       Logger.info('错误类型：$errorType');
 
+      // 回退到缓存：配置仍是上次成功的内容，保留上次更新时间
+      final isCacheFallback = e is SubscriptionCacheFallbackException;
+      if (e is SubscriptionCacheFallbackException) {
+        _cacheFallbackTimes[subscriptionId] = e.cachedAt;
+      }
+
       // 判断是否为永久性错误（需要禁用自动更新）
       final isPermanentError =
           errorType == SubscriptionErrorState.notFound ||
@@ -493,7 +534,7 @@ class SubscriptionProvider extends ChangeNotifier {
         _subscriptions[index] = subscription.copyWith(
           isUpdating: false,
           lastError: errorType.name,
-          lastUpdatedAt: DateTime.now(),
+          lastUpdatedAt: isCacheFallback ? null : DateTime.now(),
         );
       }
       await _manager.saveSubscriptionList(_subscriptions);
@@ -501,6 +542,7 @@ class SubscriptionProvider extends ChangeNotifier {
       return false;
     } finally {
       // 从更新中列表移除
+      _downloadProgress.remove(subscriptionId);
       _updateState(
         _state.copyWith(
           updatingIds: _state.updatingIds
@@ -604,124 +646,138 @@ class SubscriptionProvider extends ChangeNotifier {
     return errors;
   }
 
-  // 检查是否有启用间隔更新的订阅
-  bool _hasIntervalUpdateSubscriptions() {
-    return _subscriptions.any(
-      (s) => s.autoUpdateMode == AutoUpdateMode.interval && !s.isLocalFile,
-    );
+  // 将间隔更新的订阅同步到 Rust 侧调度器
+  void _syncRefreshSchedule() {
+    _manager.configureRefreshSchedule(_subscriptions);
   }
 
-  // 启动/重启自动更新定时器（固定 1 分钟检查间隔）
-  void _restartAutoUpdateTimer() {
-    // 取消现有定时器
-    _autoUpdateTimer?.cancel();
-    _autoUpdateTimer = null;
-
-    // 如果没有需要自动更新的订阅，停止定时器
-    if (!_hasIntervalUpdateSubscriptions()) {
-      Logger.info('无启用间隔更新的订阅，定时器已停止');
-      return;
-    }
+  // 暂停或恢复自动更新（手动更新不受影响）
+  Future<void> setAutoUpdatePaused(bool isPaused) async {
+    if (_isAutoUpdatePaused == isPaused) return;
+    _isAutoUpdatePaused = isPaused;
+    _manager.setRefreshSchedulePaused(isPaused);
+    await ClashPreferences.instance.setSubscriptionAutoUpdatePaused(isPaused);
+    notifyListeners();
+  }
 
-    // 固定每分钟检查一次（简单高效）
-    _autoUpdateTimer = Timer.periodic(const Duration(minutes: 1), (_) {
-      _checkAndAutoUpdate();
-    });
+  // 监听 Rust 侧自动更新的进度与结果
+  void _listenRefreshSignals() {
+    _refreshProgressListener?.cancel();
+    _refreshProgressListener = SubscriptionRefreshProgress.rustSignalStream
+        .listen((signal) {
+          _handleRefreshProgress(signal.message);
+        });
 
-    // 2 秒后立即检查一次，避免错过已过期的订阅
-    Future.delayed(const Duration(seconds: 2), () {
-      if (_autoUpdateTimer != null) {
-        _checkAndAutoUpdate();
-      }
+    _refreshResultListener?.cancel();
+    _refreshResultListener = SubscriptionRefreshResult.rustSignalStream.listen((
+      signal,
+    ) {
+      _handleRefreshResult(signal.message);
     });
-
-    Logger.info('自动更新定时器已启动（固定检查间隔：1 分钟）');
   }
 
-  // 检查并执行自动更新
-  void _checkAndAutoUpdate() async {
-    // 防止并发执行
-    if (_isAutoUpdateInProgress) {
-      Logger.debug('自动更新正在处理中，跳过本次检查');
-      return;
-    }
+  // 自动更新开始下载时标记为更新中
+  void _handleRefreshProgress(SubscriptionRefreshProgress progress) {
+    if (progress.stage != RefreshStage.downloading) return;
 
-    // 防止重复执行
-    if (_state.operationState.isAutoUpdating) {
-      Logger.debug('自动更新正在执行中，跳过本次检查');
-      return;
-    }
+    final index = _subscriptions.indexWhere(
+      (s) => s.id == progress.subscriptionId,
+    );
+    if (index == -1) return;
 
-    _isAutoUpdateInProgress = true;
-    try {
-      // 过滤出需要更新的订阅
-      final needUpdateSubscriptions = _subscriptions
-          .where((s) => s.shouldUpdate)
-          .toList();
+    _subscriptions[index] = _subscriptions[index].copyWith(
+      isUpdating: true,
+      lastError: _subscriptions[index].lastError,
+    );
+    _updateState(
+      _state.copyWith(
+        updatingIds: {..._state.updatingIds, progress.subscriptionId},
+      ),
+    );
+  }
 
-      if (needUpdateSubscriptions.isEmpty) {
-        Logger.debug('定时检查：没有订阅需要更新');
-        return;
-      }
+  // 处理自动更新结果：配置文件已由 Rust 写入，这里更新订阅状态并按需重载配置
+  Future<void> _handleRefreshResult(SubscriptionRefreshResult result) async {
+    final subscriptionId = result.subscriptionId;
+    _updateState(
+      _state.copyWith(
+        updatingIds: _state.updatingIds
+            .where((id) => id != subscriptionId)
+            .toSet(),
+      ),
+    );
 
-      Logger.info('定时检查：发现 ${needUpdateSubscriptions.length} 个订阅需要更新');
+    final index = _subscriptions.indexWhere((s) => s.id == subscriptionId);
+    if (index == -1) return;
+    final subscription = _subscriptions[index];
 
-      _updateState(
-        _state.copyWith(
-          operationState: SubscriptionOperationState.autoUpdating,
-        ),
+    final isFresh =
+        result.outcome == DownloadOutcome.downloaded ||
+        result.outcome == DownloadOutcome.notModified;
+
+    if (isFresh) {
+      _cacheFallbackTimes.remove(subscriptionId);
+      Logger.info('自动更新订阅成功：${subscription.name}（${result.outcome.name}）');
+      final diff = result.diff;
+      final parseReport = result.parseReport;
+      _subscriptions[index] = subscription.copyWith(
+        lastUpdatedAt: DateTime.now(),
+        info: _manager.convertSubscriptionInfo(result.subscriptionInfo),
+        isUpdating: false,
+        lastError: null,
+        hasConfigLoadFailed: result.isConfigUpdated
+            ? false
+            : subscription.hasConfigLoadFailed,
+        lastDiff: diff != null
+            ? SubscriptionDiffSummary.fromSignal(diff)
+            : null,
+        lastParseSummary: parseReport != null
+            ? SubscriptionParseSummary.fromSignal(parseReport)
+            : null,
       );
-      try {
-        await autoUpdateSubscriptions();
-      } catch (e) {
-        Logger.error('自动更新订阅失败：$e');
-        _updateState(
-          _state.copyWith(
-            errorState: SubscriptionErrorState.unknown,
-            errorMessage: '自动更新失败: $e',
-          ),
+    } else {
+      final cacheAgeSeconds = result.cacheAgeSeconds;
+      if (result.outcome == DownloadOutcome.cachedFallback &&
+          cacheAgeSeconds != null) {
+        _cacheFallbackTimes[subscriptionId] = DateTime.now().subtract(
+          Duration(seconds: cacheAgeSeconds.toInt()),
         );
-      } finally {
-        _updateState(SubscriptionState.idle());
       }
-    } finally {
-      _isAutoUpdateInProgress = false;
-    }
-  }
-
-  // 自动更新需要更新的订阅
-  // 使用并发更新以提高效率
-  Future<void> autoUpdateSubscriptions() async {
-    Logger.info('开始自动更新订阅...');
-
-    // 过滤出需要更新的订阅
-    final needUpdateSubscriptions = _subscriptions
-        .where((s) => s.shouldUpdate)
-        .toList();
-
-    if (needUpdateSubscriptions.isEmpty) {
-      Logger.info('没有需要更新的订阅');
-      return;
-    }
-
-    Logger.info('发现 ${needUpdateSubscriptions.length} 个订阅需要更新');
-
-    // 使用并发更新，限制并发数
-    const concurrency = ClashDefaults.subscriptionUpdateConcurrency;
-
-    for (int i = 0; i < needUpdateSubscriptions.length; i += concurrency) {
-      final batch = needUpdateSubscriptions.skip(i).take(concurrency).toList();
+      final rawError = result.errorMessage ?? '';
+      final errorType = _classifyError(rawError);
+      Logger.error(
+        '自动更新订阅失败：${subscription.name}（连续 ${result.consecutiveFailures} 次）- $rawError',
+      );
 
-      // 并发更新一批订阅
-      await Future.wait(
-        batch.map((subscription) => updateSubscription(subscription.id)),
+      // 永久性错误：禁用自动更新（需要用户手动修复），并从计划中移除
+      final isPermanentError =
+          errorType == SubscriptionErrorState.notFound ||
+          errorType == SubscriptionErrorState.forbidden ||
+          errorType == SubscriptionErrorState.formatError;
+      _subscriptions[index] = subscription.copyWith(
+        isUpdating: false,
+        lastError: errorType.name,
+        autoUpdateMode: isPermanentError ? AutoUpdateMode.disabled : null,
       );
+      if (isPermanentError) {
+        Logger.warning('检测到永久性错误，已禁用自动更新：${errorType.name}');
+        _syncRefreshSchedule();
+      }
     }
 
-    Logger.info('自动更新订阅完成');
+    await _manager.saveSubscriptionList(_subscriptions);
+    notifyListeners();
 
-    // 批量更新完成后重新计算定时器（避免单个更新时频繁重启）
-    _restartAutoUpdateTimer();
+    // 当前订阅的配置文件已更新时重新加载
+    if (result.isConfigUpdated && subscriptionId == _currentSubscriptionId) {
+      Logger.info('当前订阅已自动更新，开始重新加载配置...');
+      _clashProvider?.pauseConfigWatcher();
+      try {
+        await _reloadCurrentSubscriptionConfig(reason: '订阅自动更新');
+      } finally {
+        await _clashProvider?.resumeConfigWatcher();
+      }
+    }
   }
 
   // 执行启动时更新（确保只执行一次）
@@ -763,6 +819,65 @@ class SubscriptionProvider extends ChangeNotifier {
     Logger.info('启动时更新完成');
   }
 
+  // 合并多个订阅为新的本地订阅，返回各来源的合并结果。
+  // 规则取自 ruleSourceId 对应的订阅，合并失败时抛出异常。
+  Future<List<AggregatedSourceReport>> aggregateSubscriptions({
+    required String name,
+    required List<String> sourceIds,
+    required String ruleSourceId,
+  }) async {
+    final sources = <SubscriptionSource>[];
+    for (final id in sourceIds) {
+      final subscription = _subscriptions.firstWhere((s) => s.id == id);
+      sources.add(
+        SubscriptionSource(
+          label: subscription.name,
+          content: await _manager.readSubscriptionConfig(subscription),
+        ),
+      );
+    }
+
+    final response = await _manager.aggregateSubscriptions(
+      sources,
+      sourceIds.indexOf(ruleSourceId),
+    );
+    if (!response.isSuccessful) {
+      throw Exception(response.errorMessage ?? '订阅合并失败');
+    }
+
+    final isAdded = await addLocalSubscription(
+      name: name,
+      filePath: '',
+      content: response.resultConfig,
+    );
+    if (!isAdded) {
+      throw Exception('保存合并后的订阅失败');
+    }
+
+    Logger.info('已合并 ${sources.length} 个订阅为：$name');
+    return response.sourceReports;
+  }
+
+  // 导出订阅中全部节点的分享链接，导出失败时抛出异常
+  Future<ExportProxyLinksResponse> exportProxyLinks(
+    String subscriptionId,
+  ) async {
+    final subscription = _subscriptions.firstWhere(
+      (s) => s.id == subscriptionId,
+    );
+    final content = await _manager.readSubscriptionConfig(subscription);
+    final response = await _manager.exportProxyLinks(content);
+    if (!response.isSuccessful) {
+      throw Exception(response.errorMessage ?? '导出分享链接失败');
+    }
+
+    Logger.info(
+      '已导出分享链接：${subscription.name}，'
+      '${response.links.length} 个，跳过 ${response.skippedProxies.length} 个',
+    );
+    return response;
+  }
+
   // 添加本地订阅
   Future<bool> addLocalSubscription({
     required String name,
@@ -826,9 +941,6 @@ class SubscriptionProvider extends ChangeNotifier {
 
       notifyListeners();
 
-      // 本地订阅不支持自动更新，但仍需重新计算定时器
-      _restartAutoUpdateTimer();
-
       Logger.info('添加本地订阅成功：$name');
       return true;
     } catch (e) {
@@ -857,6 +969,7 @@ class SubscriptionProvider extends ChangeNotifier {
 
       // 从列表中移除
       _subscriptions.removeWhere((s) => s.id == subscriptionId);
+      _cacheFallbackTimes.remove(subscriptionId);
 
       // 如果删除的是当前选中的订阅
       if (isDeletingCurrentSubscription) {
@@ -901,8 +1014,8 @@ class SubscriptionProvider extends ChangeNotifier {
 
       notifyListeners();
 
-      // 重新计算定时器间隔（订阅减少可能影响最短间隔）
-      _restartAutoUpdateTimer();
+      // 从自动更新计划中移除
+      _syncRefreshSchedule();
 
       Logger.info('删除订阅成功：${subscription.name}');
       return true;
@@ -997,6 +1110,7 @@ class SubscriptionProvider extends ChangeNotifier {
     bool? shouldUpdateOnStartup,
     SubscriptionProxyMode? proxyMode,
     String? userAgent,
+    DownloadHttpSettings? httpSettings,
   }) async {
     // 不清除全局错误，单个操作不影响全局状态
     final index = _subscriptions.indexWhere((s) => s.id == subscriptionId);
@@ -1017,17 +1131,14 @@ class SubscriptionProvider extends ChangeNotifier {
             shouldUpdateOnStartup ?? subscription.shouldUpdateOnStartup,
         proxyMode: proxyMode ?? subscription.proxyMode,
         userAgent: userAgent ?? subscription.userAgent,
+        httpSettings: httpSettings ?? subscription.httpSettings,
       );
 
       await _manager.saveSubscriptionList(_subscriptions);
       notifyListeners();
 
-      // 如果修改了自动更新配置，重新计算定时器
-      if (autoUpdateMode != null ||
-          intervalMinutes != null ||
-          shouldUpdateOnStartup != null) {
-        _restartAutoUpdateTimer();
-      }
+      // 链接、代理模式等也属于下载参数，修改后整体同步自动更新计划
+      _syncRefreshSchedule();
 
       Logger.info('修改订阅信息成功：${_subscriptions[index].name}');
       return true;
@@ -1284,10 +1395,19 @@ class SubscriptionProvider extends ChangeNotifier {
               return OverrideConfig(
                 id: appOverride.id,
                 name: appOverride.name,
-                format: appOverride.format == app_override.OverrideFormat.yaml
-                    ? OverrideFormat.yaml
-                    : OverrideFormat.javascript,
+                format: switch (appOverride.format) {
+                  app_override.OverrideFormat.yaml => OverrideFormat.yaml,
+                  app_override.OverrideFormat.js => OverrideFormat.javascript,
+                  app_override.OverrideFormat.jsonPatch =>
+                    OverrideFormat.jsonPatch,
+                  app_override.OverrideFormat.mergePatch =>
+                    OverrideFormat.mergePatch,
+                },
                 content: appOverride.content!,
+                arguments: appOverride.arguments,
+                conditions: OverrideService.convertConditions(
+                  appOverride.conditions,
+                ),
               );
             })
             .toList();
@@ -1566,9 +1686,9 @@ class SubscriptionProvider extends ChangeNotifier {
 
   @override
   void dispose() {
-    // 取消自动更新定时器
-    _autoUpdateTimer?.cancel();
-    Logger.debug('自动更新定时器已取消');
+    // 停止监听自动更新信号
+    _refreshProgressListener?.cancel();
+    _refreshResultListener?.cancel();
 
     super.dispose();
   }
@@ -6,6 +6,18 @@ import 'package:stelliberty/services/path_service.dart';
 import 'package:stelliberty/services/log_print_service.dart';
 import 'package:stelliberty/src/bindings/signals/signals.dart' as signals;
 import 'package:stelliberty/clash/config/clash_defaults.dart';
+import 'package:stelliberty/storage/clash_preferences.dart';
+
+// 远程覆写下载结果
+class RemoteOverrideDownload {
+  final String? content; // 新内容，服务器返回 304 时为 null
+  final String? etag;
+  final String sha256; // 新内容的 SHA-256（十六进制）
+
+  const RemoteOverrideDownload({this.content, this.etag, this.sha256 = ''});
+
+  bool get isNotModified => content == null;
+}
 
 // 覆写服务
 // 纯技术实现：文件操作、网络下载、Rust 调用
@@ -13,6 +25,42 @@ class OverrideService {
   // 覆写目录路径（从 PathService 获取）
   String get overridesDir => PathService.instance.overridesDir;
 
+  // 核心版本获取回调（从 ClashManager 注入，核心停止后保留最近一次的版本）
+  static String? Function()? _getCoreVersion;
+
+  static void setCoreVersionGetter(String? Function() getter) {
+    _getCoreVersion = getter;
+  }
+
+  // 构建覆写匹配条件上下文，核心版本与运行时开关取当前状态
+  static signals.OverrideMatchContext buildMatchContext({
+    String? subscriptionId,
+    String? subscriptionUrl,
+  }) {
+    return signals.OverrideMatchContext(
+      subscriptionId: subscriptionId,
+      subscriptionUrl: subscriptionUrl,
+      coreVersion: _getCoreVersion?.call(),
+      runtimeFlags: _enabledRuntimeFlags(),
+    );
+  }
+
+  // 已开启的运行时开关（与 Rust 端 RuntimeConfigParams::enabled_flags 一致）
+  static List<signals.RuntimeFlag> _enabledRuntimeFlags() {
+    final prefs = ClashPreferences.instance;
+    return [
+      if (prefs.getTunEnable()) signals.RuntimeFlag.tunEnabled,
+      if (prefs.getIpv6()) signals.RuntimeFlag.ipv6Enabled,
+      if (prefs.getAllowLan()) signals.RuntimeFlag.allowLanEnabled,
+      if (prefs.getDnsOverrideEnabled())
+        signals.RuntimeFlag.dnsOverrideEnabled,
+      if (prefs.getKeepAliveEnabled()) signals.RuntimeFlag.keepAliveEnabled,
+      if (prefs.getTcpConcurrent()) signals.RuntimeFlag.tcpConcurrentEnabled,
+      if (prefs.getUnifiedDelayEnabled())
+        signals.RuntimeFlag.unifiedDelayEnabled,
+    ];
+  }
+
   // 初始化覆写目录
   Future<void> initialize() async {
     // 目录创建已由 PathService 统一管理
@@ -64,14 +112,16 @@ class OverrideService {
     }
   }
 
-  // 下载远程覆写
+  // 下载远程覆写（不保存，由调用者决定是否接受新内容）
   // proxyMode: 由调用者决定使用的代理模式
-  Future<String> downloadRemoteOverride(
+  // etag: 上次下载的 ETag，内容未变化时返回 isNotModified
+  Future<RemoteOverrideDownload> downloadRemoteOverride(
     data.OverrideConfig config,
     SubscriptionProxyMode proxyMode,
     String userAgent,
-    int mixedPort,
-  ) async {
+    int mixedPort, {
+    String? etag,
+  }) async {
     if (config.url == null || config.url!.isEmpty) {
       throw Exception('远程 URL 为空');
     }
@@ -109,6 +159,18 @@ class OverrideService {
             BigInt.from(ClashDefaults.overrideDownloadTimeout),
           ),
           mixedPort: mixedPort,
+          retryPolicy: null,
+          httpOptions: config.httpSettings.toRequestOptions(),
+          format: _convertFormat(config.format),
+          integrity:
+              config.sha256Pin == null && config.minisignPublicKey == null
+              ? null
+              : signals.OverrideIntegrity(
+                  sha256: config.sha256Pin,
+                  minisignPublicKey: config.minisignPublicKey,
+                  signatureUrl: config.signatureUrl,
+                ),
+          etag: etag,
         ).sendSignalToRust();
 
         // 等待响应
@@ -123,18 +185,21 @@ class OverrideService {
           throw Exception(response.errorMessage ?? '下载失败');
         }
 
+        if (response.isNotModified) {
+          Logger.debug('覆写内容未变化：${config.name}');
+          return RemoteOverrideDownload(etag: response.etag);
+        }
+
         final content = response.content;
         if (content.isEmpty) {
           throw Exception('下载的内容为空');
         }
 
-        // 保存到本地
-        final targetPath = _getOverridePath(config.id, config.format);
-        final targetFile = File(targetPath);
-        await targetFile.writeAsString(content);
-
-        Logger.debug('覆写已保存至：$targetPath');
-        return content;
+        return RemoteOverrideDownload(
+          content: content,
+          etag: response.etag,
+          sha256: response.contentSha256,
+        );
       } finally {
         await subscription?.cancel();
       }
@@ -198,16 +263,16 @@ class OverrideService {
 
   // 获取覆写文件路径
   String _getOverridePath(String id, data.OverrideFormat format) {
-    final ext = format == data.OverrideFormat.yaml ? 'yaml' : 'js';
-    return PathService.instance.getOverridePath(id, ext);
+    return PathService.instance.getOverridePath(id, format.fileExtension);
   }
 
   // 应用覆写列表到订阅配置
   // 返回应用覆写后的配置内容
   Future<String> applyOverrides(
     String baseConfigContent,
-    List<data.OverrideConfig> overrides,
-  ) async {
+    List<data.OverrideConfig> overrides, {
+    signals.OverrideMatchContext? matchContext,
+  }) async {
     Logger.debug('applyOverrides');
     Logger.debug('基础配置长度：${baseConfigContent.length} 字符');
     Logger.debug('覆写数量：${overrides.length}');
@@ -241,6 +306,8 @@ class OverrideService {
             name: override.name,
             format: _convertFormat(override.format),
             content: overrideContent,
+            arguments: override.arguments,
+            conditions: convertConditions(override.conditions),
           ),
         );
       } catch (e) {
@@ -261,6 +328,8 @@ class OverrideService {
       final request = signals.ApplyOverridesRequest(
         baseConfigContent: baseConfigContent,
         overrides: overrideConfigs,
+        isTraceEnabled: false,
+        matchContext: matchContext,
       );
 
       // 发送请求到 Rust
@@ -271,6 +340,13 @@ class OverrideService {
           await signals.ApplyOverridesResponse.rustSignalStream.first;
       final result = response.message;
 
+      for (final line in result.logs) {
+        Logger.info('覆写脚本输出：$line');
+      }
+      for (final skipped in result.skippedOverrides) {
+        Logger.info('覆写已跳过：${skipped.overrideName} - ${skipped.reason}');
+      }
+
       if (!result.isSuccessful) {
         Logger.error('Rust 覆写处理失败：${result.errorMessage}');
         throw Exception('Rust 覆写处理失败：${result.errorMessage}');
@@ -294,9 +370,46 @@ class OverrideService {
         return signals.OverrideFormat.yaml;
       case data.OverrideFormat.js:
         return signals.OverrideFormat.javascript;
+      case data.OverrideFormat.jsonPatch:
+        return signals.OverrideFormat.jsonPatch;
+      case data.OverrideFormat.mergePatch:
+        return signals.OverrideFormat.mergePatch;
     }
   }
 
+  // 转换 Dart 覆写匹配条件到 Rinf OverrideConditions
+  static signals.OverrideConditions? convertConditions(
+    data.OverrideConditions? conditions,
+  ) {
+    if (conditions == null) return null;
+
+    return signals.OverrideConditions(
+      subscriptionPattern: conditions.subscriptionPattern,
+      platforms: conditions.platforms,
+      runtimeFlags: [
+        for (final entry in conditions.runtimeFlags.entries)
+          signals.RuntimeFlagCondition(
+            flag: switch (entry.key) {
+              data.OverrideRuntimeFlag.tun => signals.RuntimeFlag.tunEnabled,
+              data.OverrideRuntimeFlag.ipv6 => signals.RuntimeFlag.ipv6Enabled,
+              data.OverrideRuntimeFlag.allowLan =>
+                signals.RuntimeFlag.allowLanEnabled,
+              data.OverrideRuntimeFlag.dnsOverride =>
+                signals.RuntimeFlag.dnsOverrideEnabled,
+              data.OverrideRuntimeFlag.keepAlive =>
+                signals.RuntimeFlag.keepAliveEnabled,
+              data.OverrideRuntimeFlag.tcpConcurrent =>
+                signals.RuntimeFlag.tcpConcurrentEnabled,
+              data.OverrideRuntimeFlag.unifiedDelay =>
+                signals.RuntimeFlag.unifiedDelayEnabled,
+            },
+            isEnabled: entry.value,
+          ),
+      ],
+      minCoreVersion: conditions.minCoreVersion,
+    );
+  }
+
   // 应用 YAML 覆写（从 Map）
   // 用于 DNS 覆写等场景，将 Map 直接合并到配置中
   Future<String> applyYamlOverride(
@@ -317,6 +430,8 @@ class OverrideService {
       name: 'Map Override',
       format: signals.OverrideFormat.yaml,
       content: yamlContent,
+      arguments: null,
+      conditions: null,
     );
 
     // 调用 Rust 处理
@@ -324,6 +439,8 @@ class OverrideService {
       final request = signals.ApplyOverridesRequest(
         baseConfigContent: baseContent,
         overrides: [tempOverride],
+        isTraceEnabled: false,
+        matchContext: null,
       );
 
       // 发送请求到 Rust
@@ -11,6 +11,21 @@ import 'package:stelliberty/services/log_print_service.dart';
 import 'package:stelliberty/services/path_service.dart';
 import 'package:stelliberty/src/bindings/signals/signals.dart';
 
+// 下载失败并回退到缓存内容：订阅配置保持上次成功的版本
+class SubscriptionCacheFallbackException implements Exception {
+  final String message;
+  final DateTime cachedAt; // 缓存内容最近一次确认有效的时间
+
+  const SubscriptionCacheFallbackException(this.message, this.cachedAt);
+
+  @override
+  String toString() => message;
+}
+
+// 订阅下载进度回调：progress 为 0.0 - 1.0，total 未知时为 0
+typedef SubscriptionDownloadProgressHandler =
+    void Function(double progress, int downloaded, int total);
+
 // 订阅服务
 // 负责订阅的下载、保存、验证等操作
 class SubscriptionService {
@@ -58,16 +73,32 @@ class SubscriptionService {
   Future<Subscription> downloadSubscription(
     Subscription subscription,
     SubscriptionProxyMode proxyMode,
-    int mixedPort,
-  ) async {
+    int mixedPort, {
+    SubscriptionDownloadProgressHandler? onProgress,
+  }) async {
     // 使用订阅 ID 作为请求标识符
     final requestId = subscription.id;
 
     // 使用 Rust 层下载订阅
     final completer = Completer<DownloadSubscriptionResponse>();
     StreamSubscription? downloadSubscription;
+    StreamSubscription? progressSubscription;
 
     try {
+      // 订阅下载进度，只接收匹配的 request_id
+      if (onProgress != null) {
+        progressSubscription = DownloadSubscriptionProgress.rustSignalStream
+            .listen((result) {
+              final progress = result.message;
+              if (progress.requestId != requestId) return;
+              onProgress(
+                progress.progress,
+                progress.downloaded.toInt(),
+                progress.total.toInt(),
+              );
+            });
+      }
+
       // 订阅 Rust 下载响应流，只接收匹配的 request_id
       downloadSubscription = DownloadSubscriptionResponse.rustSignalStream
           .listen((result) {
@@ -78,19 +109,12 @@ class SubscriptionService {
             }
           });
 
-      // 转换代理模式枚举
-      final rustProxyMode = _convertProxyMode(proxyMode);
-
       // 发送下载请求到 Rust
-      final downloadRequest = DownloadSubscriptionRequest(
-        requestId: requestId,
-        url: subscription.url,
-        proxyMode: rustProxyMode,
-        userAgent: subscription.userAgent,
-        timeoutSeconds: Uint64(
-          BigInt.from(ClashDefaults.subscriptionDownloadTimeout),
-        ),
-        mixedPort: mixedPort,
+      final downloadRequest = _buildDownloadRequest(
+        subscription,
+        _convertProxyMode(proxyMode),
+        mixedPort,
+        retryPolicy: null,
       );
       downloadRequest.sendSignalToRust();
 
@@ -102,12 +126,41 @@ class SubscriptionService {
         },
       );
 
-      if (!downloadResult.isSuccessful) {
-        throw Exception(downloadResult.errorMessage ?? '下载失败');
+      // 下载失败时 Rust 可能回退到上次成功的缓存内容，此时配置文件已是该内容，
+      // 不重新写入，按失败处理并告知缓存时间
+      switch (downloadResult.outcome) {
+        case DownloadOutcome.failed:
+          throw Exception(downloadResult.errorMessage ?? '下载失败');
+        case DownloadOutcome.cachedFallback:
+          final cacheAge = Duration(
+            seconds: downloadResult.cacheAgeSeconds?.toInt() ?? 0,
+          );
+          throw SubscriptionCacheFallbackException(
+            downloadResult.errorMessage ?? '下载失败',
+            DateTime.now().subtract(cacheAge),
+          );
+        case DownloadOutcome.notModified:
+        case DownloadOutcome.downloaded:
+          break;
       }
 
       // 解析订阅信息
-      final info = _convertSubscriptionInfo(downloadResult.subscriptionInfo);
+      final info = convertSubscriptionInfo(downloadResult.subscriptionInfo);
+
+      // 服务器返回 304 且配置文件存在：内容未变化，不重新解析与写入
+      final existingConfigFile = File(
+        PathService.instance.getSubscriptionConfigPath(subscription.id),
+      );
+      if (downloadResult.outcome == DownloadOutcome.notModified &&
+          await existingConfigFile.exists()) {
+        Logger.info('订阅内容未变化，跳过解析：${subscription.name}');
+        return subscription.copyWith(
+          lastUpdatedAt: DateTime.now(),
+          info: info,
+          isUpdating: false,
+          lastDiff: const SubscriptionDiffSummary(),
+        );
+      }
 
       // 获取配置内容
       String configContent = downloadResult.content;
@@ -116,6 +169,7 @@ class SubscriptionService {
       // 创建 Completer 等待解析结果
       final parseCompleter = Completer<String>();
       StreamSubscription? parseSubscription;
+      ParseReport? parseReport;
 
       try {
         // 订阅 Rust 信号流，只接收匹配的 request_id
@@ -125,6 +179,7 @@ class SubscriptionService {
           if (!parseCompleter.isCompleted &&
               result.message.requestId == requestId) {
             if (result.message.isSuccessful) {
+              parseReport = result.message.report;
               parseCompleter.complete(result.message.parsedConfig);
             } else {
               parseCompleter.completeError(
@@ -139,6 +194,7 @@ class SubscriptionService {
         final parseRequest = ParseSubscriptionRequest(
           requestId: requestId,
           content: configContent,
+          options: _parseOptions,
         );
         parseRequest.sendSignalToRust();
 
@@ -159,17 +215,34 @@ class SubscriptionService {
           subscription.id,
         );
         final configFile = File(configPath);
+        final oldConfigContent = await configFile.exists()
+            ? await configFile.readAsString()
+            : null;
         // 确保父目录存在
         await configFile.parent.create(recursive: true);
         await configFile.writeAsString(parsedConfigContent);
 
         Logger.debug('订阅已保存至：$configPath');
 
+        // 替换已有配置时比较新旧版本
+        final diff = oldConfigContent == null
+            ? null
+            : await _diffConfig(
+                requestId,
+                oldConfigContent,
+                parsedConfigContent,
+              );
+
         // 返回更新后的订阅
+        final report = parseReport;
         return subscription.copyWith(
           lastUpdatedAt: DateTime.now(),
           info: info,
           isUpdating: false,
+          lastDiff: diff,
+          lastParseSummary: report == null
+              ? null
+              : SubscriptionParseSummary.fromSignal(report),
         );
       } finally {
         // 停止监听信号流（即使发生异常）
@@ -179,11 +252,178 @@ class SubscriptionService {
       Logger.error('下载订阅失败：${subscription.name} - $e');
       rethrow;
     } finally {
-      // 停止监听下载响应流
+      // 停止监听下载响应与进度流
       await downloadSubscription?.cancel();
+      await progressSubscription?.cancel();
+    }
+  }
+
+  // 比较订阅新旧版本，差异仅用于提示，失败时返回 null
+  Future<SubscriptionDiffSummary?> _diffConfig(
+    String requestId,
+    String oldContent,
+    String newContent,
+  ) async {
+    final completer = Completer<DiffSubscriptionResponse>();
+    final diffSubscription = DiffSubscriptionResponse.rustSignalStream.listen((
+      result,
+    ) {
+      if (!completer.isCompleted && result.message.requestId == requestId) {
+        completer.complete(result.message);
+      }
+    });
+
+    try {
+      DiffSubscriptionRequest(
+        requestId: requestId,
+        oldContent: oldContent,
+        newContent: newContent,
+      ).sendSignalToRust();
+
+      final response = await completer.future.timeout(
+        const Duration(seconds: 10),
+      );
+      final diff = response.diff;
+      if (!response.isSuccessful || diff == null) {
+        Logger.warning('订阅版本差异比较失败：${response.errorMessage}');
+        return null;
+      }
+      return SubscriptionDiffSummary.fromSignal(diff);
+    } catch (e) {
+      Logger.warning('订阅版本差异比较失败：$e');
+      return null;
+    } finally {
+      await diffSubscription.cancel();
     }
   }
 
+  // 将多个订阅内容合并为一份配置，每个来源一个代理组，规则取自 ruleSourceIndex 对应的来源
+  Future<AggregateSubscriptionsResponse> aggregateSubscriptions(
+    List<SubscriptionSource> sources,
+    int ruleSourceIndex,
+  ) async {
+    final requestId = 'aggregate-${DateTime.now().millisecondsSinceEpoch}';
+    final completer = Completer<AggregateSubscriptionsResponse>();
+    final listener = AggregateSubscriptionsResponse.rustSignalStream.listen((
+      result,
+    ) {
+      if (!completer.isCompleted && result.message.requestId == requestId) {
+        completer.complete(result.message);
+      }
+    });
+
+    try {
+      AggregateSubscriptionsRequest(
+        requestId: requestId,
+        sources: sources,
+        ruleSourceIndex: ruleSourceIndex,
+        options: _parseOptions,
+      ).sendSignalToRust();
+
+      return await completer.future.timeout(
+        const Duration(seconds: 60),
+        onTimeout: () {
+          throw Exception('订阅合并超时');
+        },
+      );
+    } finally {
+      await listener.cancel();
+    }
+  }
+
+  // 将订阅中的节点导出为分享链接（换行拼接），无法导出的节点列在 skippedProxies
+  Future<ExportProxyLinksResponse> exportProxyLinks(String content) async {
+    final requestId = 'export-${DateTime.now().millisecondsSinceEpoch}';
+    final completer = Completer<ExportProxyLinksResponse>();
+    final listener = ExportProxyLinksResponse.rustSignalStream.listen((result) {
+      if (!completer.isCompleted && result.message.requestId == requestId) {
+        completer.complete(result.message);
+      }
+    });
+
+    try {
+      ExportProxyLinksRequest(
+        requestId: requestId,
+        content: content,
+        proxyNames: const [],
+        isBase64Encoded: false,
+      ).sendSignalToRust();
+
+      return await completer.future.timeout(
+        const Duration(seconds: 10),
+        onTimeout: () {
+          throw Exception('导出分享链接超时');
+        },
+      );
+    } finally {
+      await listener.cancel();
+    }
+  }
+
+  // 构建 Rust 侧自动更新计划条目。
+  // 核心未运行时代理模式不可用，因此非直连模式失败后回退到直连。
+  ScheduledSubscription buildScheduledSubscription(
+    Subscription subscription,
+    int mixedPort,
+  ) {
+    final proxyMode = _convertProxyMode(subscription.proxyMode);
+    final retryPolicy = proxyMode == ProxyMode.direct
+        ? null
+        : DownloadRetryPolicy(
+            fallbackModes: [ProxyMode.direct],
+            attemptTimeoutSeconds: null,
+            maxRetries: 0,
+            retryDelayMs: Uint64(BigInt.zero),
+          );
+
+    return ScheduledSubscription(
+      subscriptionId: subscription.id,
+      request: _buildDownloadRequest(
+        subscription,
+        proxyMode,
+        mixedPort,
+        retryPolicy: retryPolicy,
+      ),
+      intervalMinutes: subscription.intervalMinutes,
+      lastRefreshedAt: subscription.lastUpdatedAt == null
+          ? null
+          : subscription.lastUpdatedAt!.millisecondsSinceEpoch ~/ 1000,
+      configPath: PathService.instance.getSubscriptionConfigPath(
+        subscription.id,
+      ),
+      parseOptions: _parseOptions,
+    );
+  }
+
+  // 构建下载请求（手动更新与自动更新共用）
+  DownloadSubscriptionRequest _buildDownloadRequest(
+    Subscription subscription,
+    ProxyMode proxyMode,
+    int mixedPort, {
+    required DownloadRetryPolicy? retryPolicy,
+  }) {
+    return DownloadSubscriptionRequest(
+      requestId: subscription.id,
+      url: subscription.url,
+      proxyMode: proxyMode,
+      userAgent: subscription.userAgent,
+      timeoutSeconds: Uint64(
+        BigInt.from(ClashDefaults.subscriptionDownloadTimeout),
+      ),
+      mixedPort: mixedPort,
+      retryPolicy: retryPolicy,
+      maxSizeBytes: null,
+      httpOptions: subscription.httpSettings.toRequestOptions(),
+    );
+  }
+
+  // 订阅解析选项（手动更新与自动更新一致）
+  SubscriptionParseOptions get _parseOptions => SubscriptionParseOptions(
+    isRegionGroupingEnabled: false,
+    isInfoNodeExcluded: false,
+    excludePatterns: [],
+  );
+
   // 转换代理模式枚举（Dart → Rust）
   ProxyMode _convertProxyMode(SubscriptionProxyMode mode) {
     switch (mode) {
@@ -197,7 +437,7 @@ class SubscriptionService {
   }
 
   // 转换订阅信息（Rust → Dart）
-  SubscriptionInfo? _convertSubscriptionInfo(SubscriptionInfoData? rustInfo) {
+  SubscriptionInfo? convertSubscriptionInfo(SubscriptionInfoData? rustInfo) {
     if (rustInfo == null) return null;
 
     return SubscriptionInfo(
@@ -299,6 +539,11 @@ class SubscriptionService {
       Logger.info('已删除订阅配置：${subscription.name}');
     }
 
+    // 清除 Rust 侧的下载缓存（以 URL 摘要为键）
+    if (!subscription.isLocalFile && subscription.url.isNotEmpty) {
+      RemoveSubscriptionCacheRequest(url: subscription.url).sendSignalToRust();
+    }
+
     // 清理该订阅相关的所有节点选择持久化数据
     await ClashPreferences.instance.clearProxySelectionsForSubscription(
       subscription.id,
@@ -509,7 +754,14 @@ class SubscriptionService {
             );
           }
 
-          result = await _overrideService!.applyOverrides(result, overrides);
+          result = await _overrideService!.applyOverrides(
+            result,
+            overrides,
+            matchContext: OverrideService.buildMatchContext(
+              subscriptionId: subscription.id,
+              subscriptionUrl: subscription.url,
+            ),
+          );
           Logger.info('规则覆写应用成功：${overrides.length} 个覆写');
         } else {
           Logger.warning('overrideIds 非空，但未获取到任何覆写配置');
@@ -94,6 +94,11 @@ class OverrideTest {
     final request = ParseSubscriptionRequest(
       requestId: 'test-parse-${DateTime.now().millisecondsSinceEpoch}',
       content: content,
+      options: SubscriptionParseOptions(
+        isRegionGroupingEnabled: false,
+        isInfoNodeExcluded: false,
+        excludePatterns: [],
+      ),
     );
     request.sendSignalToRust();
 
@@ -163,6 +168,8 @@ class OverrideTest {
               ? OverrideFormat.javascript
               : OverrideFormat.yaml,
           content: content,
+          arguments: null,
+          conditions: null,
         ),
       );
     }
@@ -173,6 +180,8 @@ class OverrideTest {
     final request = ApplyOverridesRequest(
       baseConfigContent: baseConfig,
       overrides: overrideConfigs,
+      isTraceEnabled: true,
+      matchContext: null,
     );
 
     // 发送请求到 Rust
@@ -189,6 +198,18 @@ class OverrideTest {
 
     final result = response.message;
 
+    for (final step in result.trace) {
+      final status = step.isSkipped
+          ? '跳过'
+          : (step.isSuccessful ? '成功' : '失败');
+      Logger.info(
+        '   ${step.overrideName}: $status，耗时 ${step.elapsedMicros.toInt()} 微秒，'
+        '节点 ${step.countsBefore.proxyCount} → ${step.countsAfter.proxyCount}，'
+        '规则 ${step.countsBefore.ruleCount} → ${step.countsAfter.ruleCount}，'
+        '新增 ${step.addedKeys.length} / 删除 ${step.removedKeys.length} / 修改 ${step.changedKeys.length} 个键',
+      );
+    }
+
     if (!result.isSuccessful) {
       throw Exception('Rust 覆写处理失败: ${result.errorMessage}');
     }
@@ -265,10 +265,19 @@ class ProviderSetup {
             OverrideConfig(
               id: override.id,
               name: override.name,
-              format: override.format == app_override.OverrideFormat.yaml
-                  ? OverrideFormat.yaml
-                  : OverrideFormat.javascript,
+              format: switch (override.format) {
+                app_override.OverrideFormat.yaml => OverrideFormat.yaml,
+                app_override.OverrideFormat.js => OverrideFormat.javascript,
+                app_override.OverrideFormat.jsonPatch =>
+                  OverrideFormat.jsonPatch,
+                app_override.OverrideFormat.mergePatch =>
+                  OverrideFormat.mergePatch,
+              },
               content: override.content!,
+              arguments: override.arguments,
+              conditions: OverrideService.convertConditions(
+                override.conditions,
+              ),
             ),
           );
         }
@@ -276,6 +285,23 @@ class ProviderSetup {
       return overrides;
     });
 
+    // 覆写匹配条件上下文回调
+    OverrideService.setCoreVersionGetter(
+      () => ClashManager.instance.lastKnownCoreVersion,
+    );
+    ClashManager.instance.setOverrideMatchContextGetter(() {
+      final currentSub = providers.subscriptionProvider.currentSubscription;
+      return OverrideService.buildMatchContext(
+        subscriptionId: currentSub?.id,
+        subscriptionUrl: currentSub?.url,
+      );
+    });
+
+    // 覆写追踪回调
+    ClashManager.instance.setOverrideTraceListener(
+      providers.overrideProvider.updateTrace,
+    );
+
     // 设置覆写失败回调
     final currentSub = providers.subscriptionProvider.currentSubscription;
     if (currentSub != null && currentSub.overrideIds.isNotEmpty) {
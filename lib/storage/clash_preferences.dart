@@ -151,6 +151,12 @@ class ClashPreferences {
   // DNS 配置键
   static const String _kDnsOverrideEnabled = 'clash_dns_override_enabled';
 
+  // 提供者模式配置键
+  static const String _kProviderModeEnabled = 'clash_provider_mode_enabled';
+
+  // 覆写追踪配置键
+  static const String _kOverrideTraceEnabled = 'clash_override_trace_enabled';
+
   // 系统代理配置键
   static const String _kProxyHost = 'clash_proxy_host';
   static const String _kSystemProxyBypass = 'clash_system_proxy_bypass';
@@ -159,6 +165,8 @@ class ClashPreferences {
   static const String _kSystemProxyPacScript = 'clash_system_proxy_pac_script';
 
   // 订阅配置键
+  static const String _kSubscriptionAutoUpdatePaused =
+      'clash_subscription_auto_update_paused';
   static const String _kCurrentSubscriptionId = 'clash_current_subscription_id';
 
   // 节点选择配置键前缀（格式：clash_proxy_selection_{订阅 ID}_{代理组名}）
@@ -405,6 +413,24 @@ class ClashPreferences {
   Future<void> setDnsOverrideEnabled(bool enabled) =>
       _setBool(_kDnsOverrideEnabled, enabled);
 
+  // ==================== 提供者模式 ====================
+
+  // 获取提供者模式是否启用
+  bool getProviderModeEnabled() => _getBool(_kProviderModeEnabled, false);
+
+  // 保存提供者模式启用状态
+  Future<void> setProviderModeEnabled(bool enabled) =>
+      _setBool(_kProviderModeEnabled, enabled);
+
+  // ==================== 覆写追踪 ====================
+
+  // 获取覆写追踪是否启用
+  bool getOverrideTraceEnabled() => _getBool(_kOverrideTraceEnabled, false);
+
+  // 保存覆写追踪启用状态
+  Future<void> setOverrideTraceEnabled(bool enabled) =>
+      _setBool(_kOverrideTraceEnabled, enabled);
+
   // ==================== 系统代理配置 ====================
 
   // 获取代理主机（默认 127.0.0.1）
@@ -495,6 +521,8 @@ class ClashPreferences {
       _kTunDisableIcmpForwarding,
       _kTunMtu,
       _kDnsOverrideEnabled,
+      _kProviderModeEnabled,
+      _kOverrideTraceEnabled,
       _kOutboundMode,
       _kProxyNodeSortMode,
       _kLazyMode,
@@ -541,6 +569,8 @@ class ClashPreferences {
       _kTunDisableIcmpForwarding,
       _kTunMtu,
       _kDnsOverrideEnabled,
+      _kProviderModeEnabled,
+      _kOverrideTraceEnabled,
       _kOutboundMode,
       _kProxyNodeSortMode,
       _kProxyHost,
@@ -549,6 +579,7 @@ class ClashPreferences {
       _kSystemProxyPacMode,
       _kSystemProxyPacScript,
       _kCurrentSubscriptionId,
+      _kSubscriptionAutoUpdatePaused,
       _kLazyMode,
     ];
 
@@ -567,6 +598,14 @@ class ClashPreferences {
   Future<void> setCurrentSubscriptionId(String? subscriptionId) =>
       _setStringNullable(_kCurrentSubscriptionId, subscriptionId);
 
+  // 获取订阅自动更新是否已暂停
+  bool getSubscriptionAutoUpdatePaused() =>
+      _getBool(_kSubscriptionAutoUpdatePaused, false);
+
+  // 保存订阅自动更新暂停状态
+  Future<void> setSubscriptionAutoUpdatePaused(bool isPaused) =>
+      _setBool(_kSubscriptionAutoUpdatePaused, isPaused);
+
   // ==================== 出站模式 ====================
 
   // 获取出站模式
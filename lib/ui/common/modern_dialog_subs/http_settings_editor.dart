@@ -0,0 +1,181 @@
+import 'dart:convert';
+
+import 'package:flutter/material.dart';
+import 'package:stelliberty/clash/model/subscription_model.dart';
+import 'package:stelliberty/ui/common/modern_dialog_subs/option_selector.dart';
+import 'package:stelliberty/ui/common/modern_dialog_subs/text_input_field.dart';
+import 'package:stelliberty/i18n/i18n.dart';
+
+// 编辑项间距
+const double _editorItemSpacing = 16.0;
+
+// 下载请求设置的输入状态
+// 由对话框持有，负责创建与释放文本控制器
+class HttpSettingsController {
+  final TextEditingController headersController;
+  final TextEditingController certificatePathController;
+  final TextEditingController keyPathController;
+  final TextEditingController passwordController;
+  final TextEditingController caBundlePathController;
+  ClientCertificateType certificateType;
+
+  HttpSettingsController(DownloadHttpSettings initial)
+    : headersController = TextEditingController(text: initial.headersText),
+      certificatePathController = TextEditingController(
+        text: initial.certificatePath ?? '',
+      ),
+      keyPathController = TextEditingController(text: initial.keyPath ?? ''),
+      passwordController = TextEditingController(
+        text: initial.certificatePassword ?? '',
+      ),
+      caBundlePathController = TextEditingController(
+        text: initial.caBundlePath ?? '',
+      ),
+      certificateType = initial.certificateType;
+
+  List<TextEditingController> get _controllers => [
+    headersController,
+    certificatePathController,
+    keyPathController,
+    passwordController,
+    caBundlePathController,
+  ];
+
+  // 当前输入对应的设置，请求头格式错误时抛出 FormatException
+  DownloadHttpSettings get settings {
+    final isPkcs12 = certificateType == ClientCertificateType.pkcs12;
+    return DownloadHttpSettings(
+      headers: DownloadHttpSettings.parseHeaders(headersController.text),
+      certificateType: certificateType,
+      certificatePath: _optionalText(certificatePathController),
+      keyPath: isPkcs12 ? null : _optionalText(keyPathController),
+      certificatePassword: isPkcs12 ? _optionalText(passwordController) : null,
+      caBundlePath: _optionalText(caBundlePathController),
+    );
+  }
+
+  // 是否与初始设置不同，请求头格式错误时视为已修改
+  bool isChangedFrom(DownloadHttpSettings initial) {
+    try {
+      return jsonEncode(settings.toJson()) != jsonEncode(initial.toJson());
+    } on FormatException {
+      return true;
+    }
+  }
+
+  void addListener(VoidCallback listener) {
+    for (final controller in _controllers) {
+      controller.addListener(listener);
+    }
+  }
+
+  void removeListener(VoidCallback listener) {
+    for (final controller in _controllers) {
+      controller.removeListener(listener);
+    }
+  }
+
+  void dispose() {
+    for (final controller in _controllers) {
+      controller.dispose();
+    }
+  }
+
+  // 读取可选文本，空白时为 null
+  static String? _optionalText(TextEditingController controller) {
+    final text = controller.text.trim();
+    return text.isEmpty ? null : text;
+  }
+}
+
+// 下载请求设置编辑器：自定义请求头、客户端证书与额外 CA
+// 用于订阅对话框和覆写对话框的远程下载设置
+class HttpSettingsEditor extends StatelessWidget {
+  final HttpSettingsController controller;
+  final ValueChanged<ClientCertificateType> onCertificateTypeChanged;
+
+  const HttpSettingsEditor({
+    super.key,
+    required this.controller,
+    required this.onCertificateTypeChanged,
+  });
+
+  @override
+  Widget build(BuildContext context) {
+    final dialogTrans = context.translate.subscription_dialog;
+    final isPkcs12 = controller.certificateType == ClientCertificateType.pkcs12;
+
+    return Column(
+      crossAxisAlignment: CrossAxisAlignment.start,
+      mainAxisSize: MainAxisSize.min,
+      children: [
+        TextInputField(
+          controller: controller.headersController,
+          label: dialogTrans.http_headers_label,
+          hint: dialogTrans.http_headers_hint,
+          icon: Icons.http,
+          minLines: 1,
+          maxLines: null,
+          validator: (value) {
+            try {
+              DownloadHttpSettings.parseHeaders(value ?? '');
+              return null;
+            } on FormatException {
+              return dialogTrans.http_headers_error;
+            }
+          },
+        ),
+        const SizedBox(height: _editorItemSpacing),
+        OptionSelectorWidget<ClientCertificateType>(
+          title: dialogTrans.client_cert_title,
+          titleIcon: Icons.badge_outlined,
+          isHorizontal: true,
+          options: [
+            OptionItem(
+              value: ClientCertificateType.pem,
+              title: dialogTrans.client_cert_pem,
+              subtitle: dialogTrans.client_cert_pem_desc,
+            ),
+            OptionItem(
+              value: ClientCertificateType.pkcs12,
+              title: dialogTrans.client_cert_pkcs12,
+              subtitle: dialogTrans.client_cert_pkcs12_desc,
+            ),
+          ],
+          selectedValue: controller.certificateType,
+          onChanged: onCertificateTypeChanged,
+        ),
+        const SizedBox(height: _editorItemSpacing),
+        TextInputField(
+          controller: controller.certificatePathController,
+          label: dialogTrans.client_cert_path_label,
+          hint: dialogTrans.client_cert_path_hint,
+          icon: Icons.description_outlined,
+        ),
+        const SizedBox(height: _editorItemSpacing),
+        if (isPkcs12)
+          TextInputField(
+            controller: controller.passwordController,
+            label: dialogTrans.client_cert_password_label,
+            hint: dialogTrans.client_cert_password_hint,
+            icon: Icons.password,
+            obscureText: true,
+          )
+        else
+          TextInputField(
+            controller: controller.keyPathController,
+            label: dialogTrans.client_key_path_label,
+            hint: dialogTrans.client_key_path_hint,
+            icon: Icons.key,
+          ),
+        const SizedBox(height: _editorItemSpacing),
+        TextInputField(
+          controller: controller.caBundlePathController,
+          label: dialogTrans.ca_bundle_label,
+          hint: dialogTrans.ca_bundle_hint,
+          icon: Icons.verified_outlined,
+        ),
+      ],
+    );
+  }
+}
@@ -27,6 +27,9 @@ class TextInputField extends StatelessWidget {
   // 是否启用
   final bool enabled;
 
+  // 是否隐藏输入内容（用于密码）
+  final bool obscureText;
+
   const TextInputField({
     super.key,
     required this.controller,
@@ -37,6 +40,7 @@ class TextInputField extends StatelessWidget {
     this.maxLines = 1,
     this.validator,
     this.enabled = true,
+    this.obscureText = false,
   });
 
   @override
@@ -58,6 +62,7 @@ class TextInputField extends StatelessWidget {
         child: TextFormField(
           controller: controller,
           enabled: enabled,
+          obscureText: obscureText,
           minLines: minLines,
           maxLines: maxLines,
           validator: validator,
@@ -7,6 +7,7 @@ import 'package:stelliberty/i18n/i18n.dart';
 import 'package:stelliberty/ui/widgets/file_editor_dialog.dart';
 import 'package:stelliberty/ui/widgets/override/override_dialog.dart';
 import 'package:stelliberty/ui/widgets/override/override_card.dart';
+import 'package:stelliberty/ui/widgets/override/override_trace_dialog.dart';
 import 'package:stelliberty/ui/widgets/modern_toast.dart';
 import 'package:stelliberty/ui/widgets/confirm_dialog.dart';
 import 'package:stelliberty/services/log_print_service.dart';
@@ -71,6 +72,55 @@ class _OverridePageState extends State<OverridePage> {
                 },
               ),
               const Spacer(),
+              if (provider.isTraceEnabled) ...[
+                OutlinedButton.icon(
+                  onPressed: () => OverrideTraceDialog.show(
+                    context,
+                    trace: provider.lastTrace,
+                    recordedAt: provider.lastTraceAt,
+                  ),
+                  icon: const Icon(Icons.manage_search, size: 18),
+                  label: Text(translate.kOverride.view_trace),
+                  style: OutlinedButton.styleFrom(
+                    padding: const EdgeInsets.symmetric(
+                      horizontal: 16,
+                      vertical: 10,
+                    ),
+                    shape: modernTopToolbarButtonShape(),
+                    textStyle: const TextStyle(
+                      fontSize: 13,
+                      fontWeight: FontWeight.w600,
+                    ),
+                  ),
+                ),
+                const SizedBox(width: 8),
+              ],
+              OutlinedButton.icon(
+                onPressed: () => _toggleTrace(provider),
+                icon: Icon(
+                  provider.isTraceEnabled
+                      ? Icons.timeline
+                      : Icons.timeline_outlined,
+                  size: 18,
+                ),
+                label: Text(
+                  provider.isTraceEnabled
+                      ? translate.kOverride.trace_off
+                      : translate.kOverride.trace_on,
+                ),
+                style: OutlinedButton.styleFrom(
+                  padding: const EdgeInsets.symmetric(
+                    horizontal: 16,
+                    vertical: 10,
+                  ),
+                  shape: modernTopToolbarButtonShape(),
+                  textStyle: const TextStyle(
+                    fontSize: 13,
+                    fontWeight: FontWeight.w600,
+                  ),
+                ),
+              ),
+              const SizedBox(width: 8),
               FilledButton.icon(
                 onPressed: _showAddOverrideDialog,
                 icon: const Icon(Icons.add_circle, size: 18),
@@ -129,6 +179,15 @@ class _OverridePageState extends State<OverridePage> {
     );
   }
 
+  // 切换覆写追踪，开启后在下次生成配置时记录每个覆写的结果
+  Future<void> _toggleTrace(OverrideProvider provider) async {
+    final isEnabled = !provider.isTraceEnabled;
+    await provider.setTraceEnabled(isEnabled);
+    if (isEnabled) {
+      ModernToast.success(translate.kOverride.trace_enabled_hint);
+    }
+  }
+
   Widget _buildContent() {
     return Padding(
       padding: SpacingConstants.scrollbarPadding,
@@ -181,6 +240,7 @@ class _OverridePageState extends State<OverridePage> {
                   isUpdating: isUpdating,
                   isDragging: false,
                   onUpdate: () => provider.updateRemoteOverride(override.id),
+                  onApproveUpdate: () => _approvePendingUpdate(override),
                   onEditConfig: () => _editOverride(override),
                   onEditFile: () => _editOverrideFile(override),
                   onDelete: () => _deleteOverride(override),
@@ -244,6 +304,34 @@ class _OverridePageState extends State<OverridePage> {
     );
   }
 
+  Future<void> _approvePendingUpdate(OverrideConfig override) async {
+    final isConfirmed = await showConfirmDialog(
+      context: context,
+      title: translate.kOverride.approve_update_title,
+      message: translate.kOverride.approve_update_message
+          .replaceAll('{name}', override.name)
+          .replaceAll('{hash}', override.pendingSha256 ?? ''),
+      confirmText: translate.kOverride.approve_update,
+    );
+
+    if (isConfirmed != true || !mounted) return;
+
+    final isApproved = await context
+        .read<OverrideProvider>()
+        .approvePendingUpdate(override.id);
+
+    if (isApproved) {
+      ModernToast.success(
+        translate.kOverride.approve_update_success.replaceAll(
+          '{name}',
+          override.name,
+        ),
+      );
+    } else {
+      ModernToast.warning(translate.kOverride.approve_update_failed);
+    }
+  }
+
   Future<void> _deleteOverride(OverrideConfig override) async {
     final trans = context.translate;
     final isConfirmed = await showConfirmDialog(
@@ -3,11 +3,13 @@ import 'package:flutter/material.dart';
 import 'package:provider/provider.dart';
 import 'package:stelliberty/providers/content_provider.dart';
 import 'package:stelliberty/i18n/i18n.dart';
+import 'package:stelliberty/clash/manager/clash_manager.dart';
 import 'package:stelliberty/clash/providers/clash_provider.dart';
 import 'package:stelliberty/storage/clash_preferences.dart';
 import 'package:stelliberty/ui/common/modern_feature_card.dart';
 import 'package:stelliberty/ui/common/modern_dropdown_menu.dart';
 import 'package:stelliberty/ui/common/modern_dropdown_button.dart';
+import 'package:stelliberty/ui/common/modern_switch.dart';
 import 'package:stelliberty/ui/widgets/setting/keep_alive_card.dart';
 import 'package:stelliberty/services/log_print_service.dart';
 
@@ -22,6 +24,7 @@ class _PerformancePageState extends State<PerformancePage> {
   final _scrollController = ScrollController();
   late String _geodataLoader;
   late String _findProcessMode;
+  late bool _isProviderModeEnabled;
   bool _isHoveringOnGeodataMenu = false;
   bool _isHoveringOnProcessMenu = false;
 
@@ -36,6 +39,25 @@ class _PerformancePageState extends State<PerformancePage> {
     final prefs = ClashPreferences.instance;
     _geodataLoader = prefs.getGeodataLoader();
     _findProcessMode = prefs.getFindProcessMode();
+    _isProviderModeEnabled = prefs.getProviderModeEnabled();
+  }
+
+  // 切换提供者模式，核心运行中时重新生成并重载配置
+  Future<void> _setProviderMode(
+    ClashProvider clashProvider,
+    bool enabled,
+  ) async {
+    setState(() => _isProviderModeEnabled = enabled);
+    await ClashPreferences.instance.setProviderModeEnabled(enabled);
+    Logger.info('提供者模式：${enabled ? "启用" : "禁用"}');
+
+    final currentConfigPath = clashProvider.currentConfigPath;
+    if (ClashManager.instance.isCoreRunning && currentConfigPath != null) {
+      await ClashManager.instance.reloadConfig(
+        configPath: currentConfigPath,
+        overrides: ClashManager.instance.getOverrides(),
+      );
+    }
   }
 
   @override
@@ -280,6 +302,64 @@ class _PerformancePageState extends State<PerformancePage> {
                   ),
                   const SizedBox(height: 16),
 
+                  // 提供者模式
+                  ModernFeatureCard(
+                    isSelected: false,
+                    onTap: () {},
+                    isHoverEnabled: true,
+                    isTapEnabled: false,
+                    child: Row(
+                      mainAxisAlignment: MainAxisAlignment.spaceBetween,
+                      children: [
+                        Expanded(
+                          child: Row(
+                            children: [
+                              const Icon(Icons.inventory_2_outlined),
+                              const SizedBox(
+                                width: ModernFeatureCardSpacing
+                                    .featureIconToTextSpacing,
+                              ),
+                              Expanded(
+                                child: Column(
+                                  crossAxisAlignment: CrossAxisAlignment.start,
+                                  children: [
+                                    Text(
+                                      trans
+                                          .clash_features
+                                          .performance
+                                          .provider_mode
+                                          .title,
+                                      style: theme.textTheme.titleMedium,
+                                    ),
+                                    Text(
+                                      trans
+                                          .clash_features
+                                          .performance
+                                          .provider_mode
+                                          .subtitle,
+                                      style: theme.textTheme.bodySmall
+                                          ?.copyWith(
+                                            color: theme.colorScheme.onSurface
+                                                .withAlpha(153),
+                                          ),
+                                    ),
+                                  ],
+                                ),
+                              ),
+                            ],
+                          ),
+                        ),
+                        const SizedBox(width: 16),
+                        ModernSwitch(
+                          value: _isProviderModeEnabled,
+                          onChanged: (value) =>
+                              _setProviderMode(clashProvider, value),
+                        ),
+                      ],
+                    ),
+                  ),
+                  const SizedBox(height: 16),
+
                   // TCP 保持活动
                   const KeepAliveCard(),
                 ],
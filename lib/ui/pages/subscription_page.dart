@@ -1,6 +1,7 @@
 import 'dart:io';
 import 'dart:async';
 import 'package:flutter/material.dart';
+import 'package:flutter/services.dart';
 import 'package:path/path.dart' as path;
 import 'package:provider/provider.dart';
 import 'package:reorderable_grid_view/reorderable_grid_view.dart';
@@ -9,6 +10,8 @@ import 'package:stelliberty/clash/model/subscription_model.dart';
 import 'package:stelliberty/clash/services/geo_service.dart';
 import 'package:stelliberty/ui/widgets/subscription/subscription_card.dart';
 import 'package:stelliberty/ui/widgets/subscription/subscription_dialog.dart';
+import 'package:stelliberty/ui/widgets/subscription/subscription_aggregate_dialog.dart';
+import 'package:stelliberty/ui/widgets/subscription/subscription_diff_dialog.dart';
 import 'package:stelliberty/ui/widgets/override/override_selector_dialog.dart';
 import 'package:stelliberty/ui/widgets/subscription/provider_viewer_dialog.dart';
 import 'package:stelliberty/ui/widgets/file_editor_dialog.dart';
@@ -75,6 +78,7 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
             selector: (_, provider) => _SubscriptionControlBarState(
               subscriptionCount: provider.subscriptions.length,
               isLoading: provider.isLoading,
+              isAutoUpdatePaused: provider.isAutoUpdatePaused,
             ),
             builder: (context, data, child) {
               final provider = context.read<SubscriptionProvider>();
@@ -144,6 +148,23 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
                       ),
                     ),
                   ),
+                  if (data.subscriptionCount >= 2)
+                    OutlinedButton.icon(
+                      onPressed: () => _showAggregateDialog(context, provider),
+                      icon: const Icon(Icons.merge_type, size: 18),
+                      label: Text(trans.subscription_aggregate.button),
+                      style: OutlinedButton.styleFrom(
+                        padding: const EdgeInsets.symmetric(
+                          horizontal: 16,
+                          vertical: 10,
+                        ),
+                        shape: modernTopToolbarButtonShape(),
+                        textStyle: const TextStyle(
+                          fontSize: 13,
+                          fontWeight: FontWeight.w600,
+                        ),
+                      ),
+                    ),
                   FilledButton.icon(
                     onPressed: () =>
                         _showAddSubscriptionDialog(context, provider),
@@ -161,6 +182,34 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
                       ),
                     ),
                   ),
+                  if (data.subscriptionCount > 0)
+                    OutlinedButton.icon(
+                      onPressed: () => provider.setAutoUpdatePaused(
+                        !data.isAutoUpdatePaused,
+                      ),
+                      icon: Icon(
+                        data.isAutoUpdatePaused
+                            ? Icons.play_circle_outline
+                            : Icons.pause_circle_outline,
+                        size: 18,
+                      ),
+                      label: Text(
+                        data.isAutoUpdatePaused
+                            ? trans.subscription.resume_auto_update
+                            : trans.subscription.pause_auto_update,
+                      ),
+                      style: OutlinedButton.styleFrom(
+                        padding: const EdgeInsets.symmetric(
+                          horizontal: 16,
+                          vertical: 10,
+                        ),
+                        shape: modernTopToolbarButtonShape(),
+                        textStyle: const TextStyle(
+                          fontSize: 13,
+                          fontWeight: FontWeight.w600,
+                        ),
+                      ),
+                    ),
                   if (data.subscriptionCount > 0)
                     FilledButton.tonalIcon(
                       onPressed: data.isLoading
@@ -339,6 +388,7 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
                 onDelete: null,
                 onManageOverride: null,
                 onViewProvider: null,
+                onExportLinks: null,
               ),
             );
           },
@@ -371,6 +421,8 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
                   subscription,
                 ),
                 onViewProvider: () => _showProviderViewerDialog(context),
+                onExportLinks: () =>
+                    _exportProxyLinks(context, provider, subscription),
               ),
             );
           },
@@ -397,7 +449,7 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
         // 链接导入逻辑
         if (result.url == null) return false;
 
-        return await provider.addSubscription(
+        final isAdded = await provider.addSubscription(
           name: result.name,
           url: result.url!,
           autoUpdateMode: result.autoUpdateMode,
@@ -405,7 +457,24 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
           shouldUpdateOnStartup: result.shouldUpdateOnStartup,
           proxyMode: result.proxyMode,
           userAgent: result.userAgent,
+          httpSettings: result.httpSettings,
         );
+
+        // 导入成功后提示解析出的节点数与跳过的条目数
+        final parseSummary = isAdded
+            ? provider.subscriptions.last.lastParseSummary
+            : null;
+        if (parseSummary != null && context.mounted) {
+          ModernToast.success(
+            context.translate.subscription.import_success
+                .replaceAll('{name}', result.name)
+                .replaceAll(
+                  '{summary}',
+                  _parseSummaryText(context, parseSummary),
+                ),
+          );
+        }
+        return isAdded;
       },
     );
   }
@@ -450,10 +519,43 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
         shouldUpdateOnStartup: result.shouldUpdateOnStartup,
         proxyMode: result.proxyMode,
         userAgent: result.userAgent,
+        httpSettings: result.httpSettings,
       );
     }
   }
 
+  // 显示合并订阅对话框
+  Future<void> _showAggregateDialog(
+    BuildContext context,
+    SubscriptionProvider provider,
+  ) async {
+    final aggregateTrans = context.translate.subscription_aggregate;
+
+    await SubscriptionAggregateDialog.show(
+      context,
+      subscriptions: provider.subscriptions,
+      onConfirm: (result) async {
+        final reports = await provider.aggregateSubscriptions(
+          name: result.name,
+          sourceIds: result.sourceIds,
+          ruleSourceId: result.ruleSourceId,
+        );
+        final successCount = reports.where((r) => r.isSuccessful).length;
+        for (final report in reports.where((r) => !r.isSuccessful)) {
+          Logger.warning('合并来源失败：${report.label} - ${report.errorMessage}');
+        }
+
+        ModernToast.success(
+          aggregateTrans.success
+              .replaceAll('{name}', result.name)
+              .replaceAll('{success}', successCount.toString())
+              .replaceAll('{total}', reports.length.toString()),
+        );
+        return true;
+      },
+    );
+  }
+
   // 更新订阅
   Future<void> _updateSubscription(
     BuildContext context,
@@ -466,12 +568,29 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
     if (!context.mounted) return;
 
     if (isSuccess) {
-      ModernToast.success(
-        trans.subscription.update_success.replaceAll(
-          '{name}',
-          subscription.name,
-        ),
+      final updatedSubscription = provider.subscriptions.firstWhere(
+        (s) => s.id == subscription.id,
+        orElse: () => subscription,
       );
+      final lastDiff = updatedSubscription.lastDiff;
+      final parseSummary = updatedSubscription.lastParseSummary;
+      final parseNote = parseSummary == null
+          ? ''
+          : ' (${_parseSummaryText(context, parseSummary)})';
+
+      // 节点有变化时在提示中附带变更摘要
+      final message = lastDiff != null && lastDiff.hasChanges
+          ? trans.subscription.diff.update_summary
+                .replaceAll('{name}', subscription.name)
+                .replaceAll(
+                  '{summary}',
+                  SubscriptionDiffDialog.summaryText(context, lastDiff),
+                )
+          : trans.subscription.update_success.replaceAll(
+              '{name}',
+              subscription.name,
+            );
+      ModernToast.success('$message$parseNote');
     } else {
       // 从订阅对象获取错误信息
       final updatedSubscription = provider.subscriptions.firstWhere(
@@ -480,10 +599,26 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
       );
       final errorMsg = _getErrorMessage(context, updatedSubscription.lastError);
 
-      ModernToast.error('${subscription.name}: $errorMsg');
+      // 回退到缓存时说明当前使用的缓存时间
+      final fallbackTime = provider.getCacheFallbackTime(subscription.id);
+      final cacheNote = fallbackTime == null
+          ? ''
+          : ' (${SubscriptionCard.cacheFallbackText(context, fallbackTime)})';
+
+      ModernToast.error('${subscription.name}: $errorMsg$cacheNote');
     }
   }
 
+  // 解析摘要文本，例如「120 个节点，跳过 3 个」
+  String _parseSummaryText(
+    BuildContext context,
+    SubscriptionParseSummary summary,
+  ) {
+    return context.translate.subscription.parse_summary
+        .replaceAll('{accepted}', summary.acceptedCount.toString())
+        .replaceAll('{skipped}', summary.skippedCount.toString());
+  }
+
   // 获取错误消息
   String _getErrorMessage(BuildContext context, String? errorTypeName) {
     final trans = context.translate;
@@ -602,6 +737,43 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
     );
   }
 
+  // 导出订阅节点的分享链接并复制到剪贴板
+  Future<void> _exportProxyLinks(
+    BuildContext context,
+    SubscriptionProvider provider,
+    Subscription subscription,
+  ) async {
+    final trans = context.translate;
+
+    try {
+      final response = await provider.exportProxyLinks(subscription.id);
+      if (response.links.isEmpty) {
+        ModernToast.warning(trans.subscription.links_export_empty);
+        return;
+      }
+
+      await Clipboard.setData(ClipboardData(text: response.bundle));
+
+      final count = response.links.length.toString();
+      final skipped = response.skippedProxies.length;
+      ModernToast.success(
+        skipped == 0
+            ? trans.subscription.links_exported.replaceAll('{count}', count)
+            : trans.subscription.links_exported_with_skipped
+                  .replaceAll('{count}', count)
+                  .replaceAll('{skipped}', skipped.toString()),
+      );
+    } catch (e) {
+      Logger.error('导出分享链接失败：${subscription.name} - $e');
+      ModernToast.error(
+        trans.subscription.links_export_failed.replaceAll(
+          '{error}',
+          e.toString(),
+        ),
+      );
+    }
+  }
+
   // 显示提供者查看对话框
   Future<void> _showProviderViewerDialog(BuildContext context) async {
     await WidgetsBinding.instance.endOfFrame;
@@ -758,6 +930,11 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
       final parseRequest = ParseSubscriptionRequest(
         requestId: requestId,
         content: content,
+        options: SubscriptionParseOptions(
+          isRegionGroupingEnabled: false,
+          isInfoNodeExcluded: false,
+          excludePatterns: [],
+        ),
       );
       parseRequest.sendSignalToRust();
 
@@ -791,10 +968,12 @@ class _SubscriptionPageState extends State<SubscriptionPage> {
 class _SubscriptionControlBarState {
   final int subscriptionCount;
   final bool isLoading;
+  final bool isAutoUpdatePaused;
 
   const _SubscriptionControlBarState({
     required this.subscriptionCount,
     required this.isLoading,
+    required this.isAutoUpdatePaused,
   });
 
   @override
@@ -803,10 +982,12 @@ class _SubscriptionControlBarState {
       other is _SubscriptionControlBarState &&
           runtimeType == other.runtimeType &&
           subscriptionCount == other.subscriptionCount &&
-          isLoading == other.isLoading;
+          isLoading == other.isLoading &&
+          isAutoUpdatePaused == other.isAutoUpdatePaused;
 
   @override
-  int get hashCode => Object.hash(subscriptionCount, isLoading);
+  int get hashCode =>
+      Object.hash(subscriptionCount, isLoading, isAutoUpdatePaused);
 }
 
 // 订阅列表状态 - 用于 Selector 精确控制重建
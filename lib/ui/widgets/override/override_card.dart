@@ -11,6 +11,7 @@ class OverrideCard extends StatelessWidget {
   final bool isDragging;
   final bool isDragTarget;
   final VoidCallback? onUpdate;
+  final VoidCallback? onApproveUpdate;
   final VoidCallback? onEditConfig;
   final VoidCallback? onEditFile;
   final VoidCallback? onDelete;
@@ -22,6 +23,7 @@ class OverrideCard extends StatelessWidget {
     this.isDragging = false,
     this.isDragTarget = false,
     this.onUpdate,
+    this.onApproveUpdate,
     this.onEditConfig,
     this.onEditFile,
     this.onDelete,
@@ -147,6 +149,28 @@ class OverrideCard extends StatelessWidget {
                             ),
                           ),
                         ],
+                        // 远程脚本内容变化，等待批准
+                        if (config.hasPendingUpdate) ...[
+                          const SizedBox(width: 6),
+                          Container(
+                            padding: const EdgeInsets.symmetric(
+                              horizontal: 6,
+                              vertical: 2,
+                            ),
+                            decoration: BoxDecoration(
+                              color: Colors.red.withValues(alpha: 0.1),
+                              borderRadius: BorderRadius.circular(4),
+                            ),
+                            child: Text(
+                              trans.kOverride.pending_update_label,
+                              style: TextStyle(
+                                fontSize: 10,
+                                color: Colors.red[700],
+                                fontWeight: FontWeight.w500,
+                              ),
+                            ),
+                          ),
+                        ],
                         if (isUpdating) ...[
                           const SizedBox(width: 8),
                           const SizedBox(
@@ -198,6 +222,12 @@ class OverrideCard extends StatelessWidget {
                 ),
                 popup: ModernPopupMenu(
                   items: [
+                    if (config.hasPendingUpdate)
+                      PopupMenuItemData(
+                        icon: Icons.verified_outlined,
+                        label: trans.kOverride.approve_update,
+                        onPressed: onApproveUpdate,
+                      ),
                     PopupMenuItemData(
                       icon: Icons.settings,
                       label: trans.kOverride.edit_config,
@@ -0,0 +1,288 @@
+import 'dart:convert';
+
+import 'package:flutter/material.dart';
+import 'package:stelliberty/clash/model/override_model.dart';
+import 'package:stelliberty/ui/common/modern_dialog_subs/text_input_field.dart';
+import 'package:stelliberty/ui/common/modern_dropdown_button.dart';
+import 'package:stelliberty/ui/common/modern_dropdown_menu.dart';
+import 'package:stelliberty/ui/common/modern_switch.dart';
+import 'package:stelliberty/i18n/i18n.dart';
+
+// 编辑项间距
+const double _editorItemSpacing = 16.0;
+
+// 可选的操作系统（与 Rust 端 std::env::consts::OS 取值一致）
+const List<(String, String)> _kPlatforms = [
+  ('windows', 'Windows'),
+  ('linux', 'Linux'),
+  ('macos', 'macOS'),
+  ('android', 'Android'),
+];
+
+// 覆写匹配条件的输入状态
+// 由对话框持有，负责创建与释放文本控制器
+class OverrideConditionsController {
+  final TextEditingController subscriptionPatternController;
+  final TextEditingController minCoreVersionController;
+  final Set<String> platforms;
+  final Map<OverrideRuntimeFlag, bool> runtimeFlags;
+
+  OverrideConditionsController(OverrideConditions? initial)
+    : subscriptionPatternController = TextEditingController(
+        text: initial?.subscriptionPattern ?? '',
+      ),
+      minCoreVersionController = TextEditingController(
+        text: initial?.minCoreVersion ?? '',
+      ),
+      platforms = {...?initial?.platforms},
+      runtimeFlags = {...?initial?.runtimeFlags};
+
+  // 当前输入对应的条件，未设置任何条件时为 null
+  OverrideConditions? get conditions {
+    final conditions = OverrideConditions(
+      subscriptionPattern: _optionalText(subscriptionPatternController),
+      platforms: [
+        for (final (platform, _) in _kPlatforms)
+          if (platforms.contains(platform)) platform,
+      ],
+      runtimeFlags: Map.of(runtimeFlags),
+      minCoreVersion: _optionalText(minCoreVersionController),
+    );
+    return conditions.isEmpty ? null : conditions;
+  }
+
+  // 是否与初始条件不同（空条件与未设置视为相同）
+  bool isChangedFrom(OverrideConditions? initial) {
+    final normalized = (initial == null || initial.isEmpty) ? null : initial;
+    return jsonEncode(conditions?.toJson()) !=
+        jsonEncode(normalized?.toJson());
+  }
+
+  void addListener(VoidCallback listener) {
+    subscriptionPatternController.addListener(listener);
+    minCoreVersionController.addListener(listener);
+  }
+
+  void removeListener(VoidCallback listener) {
+    subscriptionPatternController.removeListener(listener);
+    minCoreVersionController.removeListener(listener);
+  }
+
+  void dispose() {
+    subscriptionPatternController.dispose();
+    minCoreVersionController.dispose();
+  }
+
+  // 读取可选文本，空白时为 null
+  static String? _optionalText(TextEditingController controller) {
+    final text = controller.text.trim();
+    return text.isEmpty ? null : text;
+  }
+}
+
+// 覆写匹配条件编辑器：订阅匹配、操作系统、运行时开关与最低核心版本
+// 开关与下拉选择直接修改控制器，再通过 onChanged 通知对话框重建
+class OverrideConditionsEditor extends StatelessWidget {
+  final OverrideConditionsController controller;
+  final VoidCallback onChanged;
+
+  const OverrideConditionsEditor({
+    super.key,
+    required this.controller,
+    required this.onChanged,
+  });
+
+  @override
+  Widget build(BuildContext context) {
+    final conditionsTrans = context.translate.override_conditions;
+
+    return Column(
+      crossAxisAlignment: CrossAxisAlignment.start,
+      mainAxisSize: MainAxisSize.min,
+      children: [
+        TextInputField(
+          controller: controller.subscriptionPatternController,
+          label: conditionsTrans.subscription_pattern_label,
+          hint: conditionsTrans.subscription_pattern_hint,
+          icon: Icons.filter_alt_outlined,
+          validator: (value) {
+            final text = value?.trim() ?? '';
+            if (text.isEmpty) return null;
+            try {
+              RegExp(text);
+              return null;
+            } on FormatException {
+              return conditionsTrans.subscription_pattern_error;
+            }
+          },
+        ),
+        const SizedBox(height: _editorItemSpacing),
+        TextInputField(
+          controller: controller.minCoreVersionController,
+          label: conditionsTrans.min_core_version_label,
+          hint: conditionsTrans.min_core_version_hint,
+          icon: Icons.memory,
+          validator: (value) {
+            final text = value?.trim() ?? '';
+            if (text.isEmpty || RegExp(r'\d').hasMatch(text)) return null;
+            return conditionsTrans.min_core_version_error;
+          },
+        ),
+        const SizedBox(height: _editorItemSpacing),
+        _buildSection(
+          context,
+          title: conditionsTrans.platforms_title,
+          hint: conditionsTrans.platforms_hint,
+          icon: Icons.devices_outlined,
+          children: [
+            for (final (platform, displayName) in _kPlatforms)
+              _buildRow(
+                context,
+                displayName,
+                ModernSwitch(
+                  value: controller.platforms.contains(platform),
+                  onChanged: (enabled) {
+                    if (enabled) {
+                      controller.platforms.add(platform);
+                    } else {
+                      controller.platforms.remove(platform);
+                    }
+                    onChanged();
+                  },
+                ),
+              ),
+          ],
+        ),
+        const SizedBox(height: _editorItemSpacing),
+        _buildSection(
+          context,
+          title: conditionsTrans.runtime_flags_title,
+          hint: conditionsTrans.runtime_flags_hint,
+          icon: Icons.toggle_on_outlined,
+          children: [
+            for (final flag in OverrideRuntimeFlag.values)
+              _buildRow(
+                context,
+                _flagName(context, flag),
+                _buildFlagSelector(context, flag),
+              ),
+          ],
+        ),
+      ],
+    );
+  }
+
+  // 构建运行时开关要求选择器：不限 / 开启 / 关闭
+  Widget _buildFlagSelector(BuildContext context, OverrideRuntimeFlag flag) {
+    final conditionsTrans = context.translate.override_conditions;
+    String stateName(bool? isEnabled) => switch (isEnabled) {
+      null => conditionsTrans.flag_any,
+      true => conditionsTrans.flag_on,
+      false => conditionsTrans.flag_off,
+    };
+
+    final selected = controller.runtimeFlags[flag];
+    return ModernDropdownMenu<bool?>(
+      items: const [null, true, false],
+      selectedItem: selected,
+      itemToString: stateName,
+      onSelected: (isEnabled) {
+        if (isEnabled == null) {
+          controller.runtimeFlags.remove(flag);
+        } else {
+          controller.runtimeFlags[flag] = isEnabled;
+        }
+        onChanged();
+      },
+      child: CustomDropdownButton(
+        text: stateName(selected),
+        isHovering: false,
+      ),
+    );
+  }
+
+  // 构建分组卡片
+  Widget _buildSection(
+    BuildContext context, {
+    required String title,
+    required String hint,
+    required IconData icon,
+    required List<Widget> children,
+  }) {
+    final theme = Theme.of(context);
+    final isDark = theme.brightness == Brightness.dark;
+
+    return Container(
+      padding: const EdgeInsets.all(16),
+      decoration: BoxDecoration(
+        color: Colors.white.withValues(alpha: isDark ? 0.04 : 0.5),
+        borderRadius: BorderRadius.circular(12),
+        border: Border.all(
+          color: Colors.white.withValues(alpha: isDark ? 0.1 : 0.2),
+        ),
+      ),
+      child: Column(
+        crossAxisAlignment: CrossAxisAlignment.start,
+        mainAxisSize: MainAxisSize.min,
+        children: [
+          Row(
+            children: [
+              Icon(icon, size: 18, color: theme.colorScheme.primary),
+              const SizedBox(width: 8),
+              Text(
+                title,
+                style: theme.textTheme.titleSmall?.copyWith(
+                  fontWeight: FontWeight.bold,
+                ),
+              ),
+            ],
+          ),
+          const SizedBox(height: 4),
+          Text(
+            hint,
+            style: TextStyle(
+              fontSize: 12,
+              color: theme.colorScheme.onSurface.withValues(alpha: 0.6),
+            ),
+          ),
+          const SizedBox(height: 8),
+          ...children,
+        ],
+      ),
+    );
+  }
+
+  // 构建分组内的一行：名称与控件
+  Widget _buildRow(BuildContext context, String label, Widget trailing) {
+    return Padding(
+      padding: const EdgeInsets.symmetric(vertical: 4),
+      child: Row(
+        children: [
+          Expanded(
+            child: Text(
+              label,
+              style: TextStyle(
+                fontSize: 14,
+                color: Theme.of(context).colorScheme.onSurface,
+              ),
+            ),
+          ),
+          trailing,
+        ],
+      ),
+    );
+  }
+
+  String _flagName(BuildContext context, OverrideRuntimeFlag flag) {
+    final conditionsTrans = context.translate.override_conditions;
+    return switch (flag) {
+      OverrideRuntimeFlag.tun => conditionsTrans.flag_tun,
+      OverrideRuntimeFlag.ipv6 => conditionsTrans.flag_ipv6,
+      OverrideRuntimeFlag.allowLan => conditionsTrans.flag_allow_lan,
+      OverrideRuntimeFlag.dnsOverride => conditionsTrans.flag_dns_override,
+      OverrideRuntimeFlag.keepAlive => conditionsTrans.flag_keep_alive,
+      OverrideRuntimeFlag.tcpConcurrent => conditionsTrans.flag_tcp_concurrent,
+      OverrideRuntimeFlag.unifiedDelay => conditionsTrans.flag_unified_delay,
+    };
+  }
+}
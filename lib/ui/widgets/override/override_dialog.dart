@@ -1,3 +1,5 @@
+import 'dart:convert';
+
 import 'package:flutter/material.dart';
 import 'package:stelliberty/clash/model/override_model.dart';
 import 'package:stelliberty/clash/model/subscription_model.dart';
@@ -9,6 +11,8 @@ import 'package:stelliberty/ui/common/modern_dialog_subs/option_selector.dart';
 import 'package:stelliberty/ui/common/modern_dialog_subs/text_input_field.dart';
 import 'package:stelliberty/ui/common/modern_dialog_subs/file_selector.dart';
 import 'package:stelliberty/ui/common/modern_dialog_subs/proxy_mode_selector.dart';
+import 'package:stelliberty/ui/common/modern_dialog_subs/http_settings_editor.dart';
+import 'package:stelliberty/ui/widgets/override/override_conditions_editor.dart';
 
 // 对话框间距常量
 const double _dialogContentPadding = 20.0;
@@ -56,6 +60,12 @@ class OverrideDialog extends StatefulWidget {
 class _OverrideDialogState extends State<OverrideDialog> {
   late final TextEditingController _nameController;
   late final TextEditingController _urlController;
+  late final TextEditingController _sha256PinController;
+  late final TextEditingController _minisignKeyController;
+  late final TextEditingController _refreshIntervalController;
+  late final HttpSettingsController _httpSettingsController;
+  late final TextEditingController _argumentsController;
+  late final OverrideConditionsController _conditionsController;
   late OverrideFormat _format;
   late SubscriptionProxyMode _proxyMode;
 
@@ -81,6 +91,22 @@ class _OverrideDialogState extends State<OverrideDialog> {
     _urlController = TextEditingController(
       text: widget.editingOverride?.url ?? '',
     );
+    _sha256PinController = TextEditingController(
+      text: widget.editingOverride?.sha256Pin ?? '',
+    );
+    _minisignKeyController = TextEditingController(
+      text: widget.editingOverride?.minisignPublicKey ?? '',
+    );
+    _refreshIntervalController = TextEditingController(
+      text: '${widget.editingOverride?.refreshIntervalMinutes ?? 0}',
+    );
+    _httpSettingsController = HttpSettingsController(_initialHttpSettings);
+    _argumentsController = TextEditingController(
+      text: widget.editingOverride?.arguments ?? '',
+    );
+    _conditionsController = OverrideConditionsController(
+      widget.editingOverride?.conditions,
+    );
     _format = widget.editingOverride?.format ?? OverrideFormat.yaml;
     _proxyMode =
         widget.editingOverride?.proxyMode ?? SubscriptionProxyMode.direct;
@@ -94,8 +120,17 @@ class _OverrideDialogState extends State<OverrideDialog> {
     // 添加监听器以检测内容变化
     _nameController.addListener(_checkForChanges);
     _urlController.addListener(_checkForChanges);
+    _sha256PinController.addListener(_checkForChanges);
+    _minisignKeyController.addListener(_checkForChanges);
+    _refreshIntervalController.addListener(_checkForChanges);
+    _httpSettingsController.addListener(_checkForChanges);
+    _argumentsController.addListener(_checkForChanges);
+    _conditionsController.addListener(_checkForChanges);
   }
 
+  DownloadHttpSettings get _initialHttpSettings =>
+      widget.editingOverride?.httpSettings ?? const DownloadHttpSettings();
+
   // 检查内容是否发生变化
   bool get _hasChanges {
     // 添加模式：总是允许保存
@@ -112,7 +147,39 @@ class _OverrideDialogState extends State<OverrideDialog> {
         _proxyMode !=
             (widget.editingOverride?.proxyMode ?? SubscriptionProxyMode.direct);
 
-    return nameChanged || urlChanged || proxyModeChanged;
+    final remoteSettingsChanged =
+        widget.editingOverride?.type == OverrideType.remote &&
+        (_optionalText(_sha256PinController) !=
+                widget.editingOverride?.sha256Pin ||
+            _optionalText(_minisignKeyController) !=
+                widget.editingOverride?.minisignPublicKey ||
+            _refreshIntervalController.text.trim() !=
+                '${widget.editingOverride?.refreshIntervalMinutes ?? 0}' ||
+            _httpSettingsController.isChangedFrom(_initialHttpSettings));
+
+    final argumentsChanged =
+        _optionalText(_argumentsController) !=
+        widget.editingOverride?.arguments;
+    final conditionsChanged = _conditionsController.isChangedFrom(
+      widget.editingOverride?.conditions,
+    );
+
+    return nameChanged ||
+        urlChanged ||
+        proxyModeChanged ||
+        remoteSettingsChanged ||
+        argumentsChanged ||
+        conditionsChanged;
+  }
+
+  // 当前格式是否为 JavaScript（编辑模式沿用原格式）
+  bool get _isJavaScript =>
+      (widget.editingOverride?.format ?? _format) == OverrideFormat.js;
+
+  // 读取可选文本，空白时为 null
+  String? _optionalText(TextEditingController controller) {
+    final text = controller.text.trim();
+    return text.isEmpty ? null : text;
   }
 
   // 延迟重建，合并同一帧内的多次变更
@@ -134,9 +201,21 @@ class _OverrideDialogState extends State<OverrideDialog> {
     // 移除监听器
     _nameController.removeListener(_checkForChanges);
     _urlController.removeListener(_checkForChanges);
+    _sha256PinController.removeListener(_checkForChanges);
+    _minisignKeyController.removeListener(_checkForChanges);
+    _refreshIntervalController.removeListener(_checkForChanges);
+    _httpSettingsController.removeListener(_checkForChanges);
+    _argumentsController.removeListener(_checkForChanges);
+    _conditionsController.removeListener(_checkForChanges);
     // 释放控制器
     _nameController.dispose();
     _urlController.dispose();
+    _sha256PinController.dispose();
+    _minisignKeyController.dispose();
+    _refreshIntervalController.dispose();
+    _httpSettingsController.dispose();
+    _argumentsController.dispose();
+    _conditionsController.dispose();
     super.dispose();
   }
 
@@ -228,10 +307,31 @@ class _OverrideDialogState extends State<OverrideDialog> {
               _buildFormatSelector(),
             ],
 
+            // JavaScript 覆写可设置脚本参数
+            if (_isJavaScript) ...[
+              const SizedBox(height: _dialogItemSpacing),
+              _buildArgumentsField(),
+            ],
+
+            // 匹配条件，不满足时生成配置会跳过该覆写
+            const SizedBox(height: _dialogItemSpacing),
+            _buildConditionsSection(),
+
             // 远程模式显示代理模式选择，导入模式显示文件选择器
             if (_addMethod == OverrideAddMethod.remote) ...[
               const SizedBox(height: _dialogItemSpacing),
               _buildProxyModeSection(),
+              const SizedBox(height: _dialogItemSpacing),
+              _buildRemotePolicySection(),
+              const SizedBox(height: _dialogItemSpacing),
+              HttpSettingsEditor(
+                controller: _httpSettingsController,
+                onCertificateTypeChanged: (value) {
+                  setState(
+                    () => _httpSettingsController.certificateType = value,
+                  );
+                },
+              ),
             ] else if (_addMethod == OverrideAddMethod.import &&
                 !isEditing) ...[
               const SizedBox(height: _dialogItemSpacing),
@@ -282,14 +382,8 @@ class _OverrideDialogState extends State<OverrideDialog> {
       titleIcon: Icons.code,
       isHorizontal: true,
       options: [
-        OptionItem(
-          value: OverrideFormat.yaml,
-          title: OverrideFormat.yaml.displayName,
-        ),
-        OptionItem(
-          value: OverrideFormat.js,
-          title: OverrideFormat.js.displayName,
-        ),
+        for (final format in OverrideFormat.values)
+          OptionItem(value: format, title: format.displayName),
       ],
       selectedValue: _format,
       onChanged: (value) {
@@ -308,6 +402,133 @@ class _OverrideDialogState extends State<OverrideDialog> {
     );
   }
 
+  // 构建完整性校验与自动刷新设置
+  Widget _buildRemotePolicySection() {
+    final trans = context.translate;
+    return Column(
+      crossAxisAlignment: CrossAxisAlignment.start,
+      mainAxisSize: MainAxisSize.min,
+      children: [
+        TextInputField(
+          controller: _sha256PinController,
+          label: trans.kOverride.sha256_pin_label,
+          hint: trans.kOverride.sha256_pin_hint,
+          icon: Icons.fingerprint,
+          validator: (value) {
+            final text = value?.trim() ?? '';
+            if (text.isEmpty) return null;
+            if (!RegExp(r'^(sha256:)?[0-9a-fA-F]{64}$').hasMatch(text)) {
+              return trans.kOverride.sha256_pin_error;
+            }
+            return null;
+          },
+        ),
+        const SizedBox(height: _dialogItemSpacing),
+        TextInputField(
+          controller: _minisignKeyController,
+          label: trans.kOverride.minisign_key_label,
+          hint: trans.kOverride.minisign_key_hint,
+          icon: Icons.verified_user_outlined,
+          minLines: 1,
+          maxLines: null,
+        ),
+        const SizedBox(height: _dialogItemSpacing),
+        TextInputField(
+          controller: _refreshIntervalController,
+          label: trans.kOverride.refresh_interval_label,
+          hint: trans.kOverride.refresh_interval_hint,
+          icon: Icons.schedule,
+          validator: (value) {
+            final minutes = int.tryParse(value?.trim() ?? '');
+            if (minutes == null || minutes < 0) {
+              return trans.kOverride.refresh_interval_error;
+            }
+            return null;
+          },
+        ),
+      ],
+    );
+  }
+
+  // 应用远程覆写的完整性校验与自动刷新设置
+  OverrideConfig _applyRemotePolicy(OverrideConfig override) {
+    if (override.type != OverrideType.remote) return override;
+
+    final sha256Pin = _optionalText(_sha256PinController);
+    final minisignPublicKey = _optionalText(_minisignKeyController);
+    final isIntegrityChanged =
+        sha256Pin != override.sha256Pin ||
+        minisignPublicKey != override.minisignPublicKey;
+
+    final updated = isIntegrityChanged
+        ? override.copyWithIntegrity(
+            sha256Pin: sha256Pin,
+            minisignPublicKey: minisignPublicKey,
+          )
+        : override;
+    return updated.copyWith(
+      refreshIntervalMinutes:
+          int.tryParse(_refreshIntervalController.text.trim()) ?? 0,
+      httpSettings: _httpSettingsController.settings,
+    );
+  }
+
+  // 构建脚本参数输入框，内容须为 JSON 对象
+  Widget _buildArgumentsField() {
+    final trans = context.translate;
+    return TextInputField(
+      controller: _argumentsController,
+      label: trans.kOverride.arguments_label,
+      hint: trans.kOverride.arguments_hint,
+      icon: Icons.data_object,
+      minLines: 2,
+      maxLines: null,
+      validator: (value) {
+        final text = value?.trim() ?? '';
+        if (text.isEmpty) return null;
+        try {
+          return jsonDecode(text) is Map
+              ? null
+              : trans.kOverride.arguments_error;
+        } on FormatException {
+          return trans.kOverride.arguments_error;
+        }
+      },
+    );
+  }
+
+  // 构建匹配条件设置
+  Widget _buildConditionsSection() {
+    final conditionsTrans = context.translate.override_conditions;
+    return Column(
+      crossAxisAlignment: CrossAxisAlignment.start,
+      mainAxisSize: MainAxisSize.min,
+      children: [
+        Text(
+          conditionsTrans.title,
+          style: Theme.of(
+            context,
+          ).textTheme.titleSmall?.copyWith(fontWeight: FontWeight.bold),
+        ),
+        const SizedBox(height: 4),
+        Text(
+          conditionsTrans.hint,
+          style: TextStyle(
+            fontSize: 12,
+            color: Theme.of(
+              context,
+            ).colorScheme.onSurface.withValues(alpha: 0.6),
+          ),
+        ),
+        const SizedBox(height: 12),
+        OverrideConditionsEditor(
+          controller: _conditionsController,
+          onChanged: () => setState(() {}),
+        ),
+      ],
+    );
+  }
+
   // 构建文件选择器
   Widget _buildFileSelector() {
     final trans = context.translate;
@@ -346,7 +567,7 @@ class _OverrideDialogState extends State<OverrideDialog> {
 
     Logger.info('表单验证通过，继续处理...');
 
-    final override = widget.editingOverride != null
+    final baseOverride = widget.editingOverride != null
         ? widget.editingOverride!.copyWith(name: _nameController.text.trim())
         : OverrideConfig(
             id: DateTime.now().millisecondsSinceEpoch.toString(),
@@ -366,6 +587,18 @@ class _OverrideDialogState extends State<OverrideDialog> {
                 ? _proxyMode
                 : SubscriptionProxyMode.direct,
           );
+    final arguments = _isJavaScript
+        ? _optionalText(_argumentsController)
+        : null;
+    final conditions = _conditionsController.conditions;
+    final override = _applyRemotePolicy(
+      baseOverride.copyWith(
+        arguments: arguments,
+        clearArguments: arguments == null,
+        conditions: conditions,
+        clearConditions: conditions == null,
+      ),
+    );
 
     Logger.info('创建的覆写对象: ${override.name}, ID: ${override.id}');
 
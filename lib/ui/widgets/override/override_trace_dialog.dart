@@ -0,0 +1,237 @@
+import 'package:flutter/material.dart';
+import 'package:intl/intl.dart';
+import 'package:stelliberty/i18n/i18n.dart';
+import 'package:stelliberty/ui/common/modern_dialog.dart';
+import 'package:stelliberty/src/bindings/signals/signals.dart'
+    show ConfigCounts, OverrideTraceStep;
+
+// 覆写追踪对话框
+// 显示最近一次生成配置时每个覆写的结果、耗时与配置变化
+class OverrideTraceDialog extends StatelessWidget {
+  final List<OverrideTraceStep> trace;
+  final DateTime? recordedAt;
+
+  const OverrideTraceDialog({
+    super.key,
+    required this.trace,
+    required this.recordedAt,
+  });
+
+  static Future<void> show(
+    BuildContext context, {
+    required List<OverrideTraceStep> trace,
+    required DateTime? recordedAt,
+  }) {
+    return showDialog<void>(
+      context: context,
+      builder: (context) =>
+          OverrideTraceDialog(trace: trace, recordedAt: recordedAt),
+    );
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    final traceTrans = context.translate.override_trace;
+    final time = recordedAt;
+
+    return ModernDialog(
+      title: traceTrans.title,
+      subtitle: time == null
+          ? null
+          : traceTrans.recorded_at.replaceAll(
+              '{time}',
+              DateFormat('yyyy-MM-dd HH:mm:ss').format(time),
+            ),
+      titleIcon: Icons.timeline,
+      maxWidth: 640,
+      maxHeightRatio: 0.85,
+      content: _buildContent(context),
+      actionsRight: [
+        DialogActionButton(
+          label: traceTrans.close,
+          isPrimary: false,
+          onPressed: () => Navigator.of(context).pop(),
+        ),
+      ],
+    );
+  }
+
+  Widget _buildContent(BuildContext context) {
+    if (trace.isEmpty) {
+      return Padding(
+        padding: const EdgeInsets.all(24),
+        child: Center(child: Text(context.translate.override_trace.empty)),
+      );
+    }
+
+    return SingleChildScrollView(
+      padding: const EdgeInsets.symmetric(horizontal: 24, vertical: 20),
+      child: Column(
+        crossAxisAlignment: CrossAxisAlignment.start,
+        mainAxisSize: MainAxisSize.min,
+        children: [for (final step in trace) _buildStep(context, step)],
+      ),
+    );
+  }
+
+  // 构建单个覆写的追踪记录
+  Widget _buildStep(BuildContext context, OverrideTraceStep step) {
+    final traceTrans = context.translate.override_trace;
+    final theme = Theme.of(context);
+    final isDark = theme.brightness == Brightness.dark;
+
+    final (statusText, statusColor) = step.isSkipped
+        ? (traceTrans.status_skipped, Colors.orange)
+        : step.isSuccessful
+        ? (traceTrans.status_success, Colors.green)
+        : (traceTrans.status_failed, Colors.red);
+    final elapsedMs = (step.elapsedMicros.toInt() / 1000).toStringAsFixed(1);
+    final errorMessage = step.errorMessage;
+
+    return Container(
+      margin: const EdgeInsets.only(bottom: 12),
+      padding: const EdgeInsets.all(12),
+      decoration: BoxDecoration(
+        color: Colors.white.withValues(alpha: isDark ? 0.04 : 0.5),
+        borderRadius: BorderRadius.circular(12),
+        border: Border.all(
+          color: Colors.white.withValues(alpha: isDark ? 0.1 : 0.2),
+        ),
+      ),
+      child: Column(
+        crossAxisAlignment: CrossAxisAlignment.start,
+        children: [
+          Row(
+            children: [
+              Expanded(
+                child: Text(
+                  step.overrideName,
+                  style: theme.textTheme.titleSmall?.copyWith(
+                    fontWeight: FontWeight.bold,
+                  ),
+                ),
+              ),
+              Text(
+                statusText,
+                style: TextStyle(
+                  fontSize: 12,
+                  fontWeight: FontWeight.w600,
+                  color: statusColor,
+                ),
+              ),
+              const SizedBox(width: 12),
+              Text(
+                traceTrans.elapsed.replaceAll('{ms}', elapsedMs),
+                style: theme.textTheme.bodySmall,
+              ),
+            ],
+          ),
+          const SizedBox(height: 6),
+          Text(
+            traceTrans.counts
+                .replaceAll(
+                  '{proxies}',
+                  _countChange(step, (c) => c.proxyCount),
+                )
+                .replaceAll(
+                  '{groups}',
+                  _countChange(step, (c) => c.groupCount),
+                )
+                .replaceAll(
+                  '{rules}',
+                  _countChange(step, (c) => c.ruleCount),
+                ),
+            style: theme.textTheme.bodySmall?.copyWith(
+              color: theme.colorScheme.onSurface.withAlpha(153),
+            ),
+          ),
+          if (errorMessage != null && errorMessage.isNotEmpty) ...[
+            const SizedBox(height: 6),
+            SelectableText(
+              errorMessage,
+              style: TextStyle(fontSize: 12, color: statusColor),
+            ),
+          ],
+          _buildKeys(
+            context,
+            traceTrans.added_keys,
+            step.addedKeys,
+            Colors.green,
+          ),
+          _buildKeys(
+            context,
+            traceTrans.removed_keys,
+            step.removedKeys,
+            Colors.red,
+          ),
+          _buildKeys(
+            context,
+            traceTrans.changed_keys,
+            step.changedKeys,
+            Colors.orange,
+          ),
+        ],
+      ),
+    );
+  }
+
+  // 数量变化，例如「12 → 15」，未变化时只显示一个数
+  String _countChange(
+    OverrideTraceStep step,
+    int Function(ConfigCounts) count,
+  ) {
+    final beforeCount = count(step.countsBefore);
+    final afterCount = count(step.countsAfter);
+    return beforeCount == afterCount
+        ? '$afterCount'
+        : '$beforeCount → $afterCount';
+  }
+
+  // 构建键路径列表
+  Widget _buildKeys(
+    BuildContext context,
+    String title,
+    List<String> keys,
+    Color color,
+  ) {
+    if (keys.isEmpty) {
+      return const SizedBox.shrink();
+    }
+
+    return Padding(
+      padding: const EdgeInsets.only(top: 10),
+      child: Column(
+        crossAxisAlignment: CrossAxisAlignment.start,
+        children: [
+          Text(
+            '$title (${keys.length})',
+            style: Theme.of(context).textTheme.labelMedium,
+          ),
+          const SizedBox(height: 6),
+          Wrap(
+            spacing: 6,
+            runSpacing: 6,
+            children: keys
+                .map(
+                  (key) => Container(
+                    padding: const EdgeInsets.symmetric(
+                      horizontal: 8,
+                      vertical: 4,
+                    ),
+                    decoration: BoxDecoration(
+                      color: color.withValues(alpha: 0.12),
+                      borderRadius: BorderRadius.circular(6),
+                    ),
+                    child: SelectableText(
+                      key,
+                      style: TextStyle(fontSize: 12, color: color),
+                    ),
+                  ),
+                )
+                .toList(),
+          ),
+        ],
+      ),
+    );
+  }
+}
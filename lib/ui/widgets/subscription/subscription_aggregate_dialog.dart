@@ -0,0 +1,255 @@
+import 'package:flutter/material.dart';
+import 'package:stelliberty/clash/model/subscription_model.dart';
+import 'package:stelliberty/ui/common/modern_dialog.dart';
+import 'package:stelliberty/ui/common/modern_switch.dart';
+import 'package:stelliberty/ui/common/modern_dialog_subs/option_selector.dart';
+import 'package:stelliberty/ui/common/modern_dialog_subs/text_input_field.dart';
+import 'package:stelliberty/ui/widgets/modern_toast.dart';
+import 'package:stelliberty/i18n/i18n.dart';
+import 'package:stelliberty/services/log_print_service.dart';
+
+// 对话框布局常量
+const double _dialogContentPadding = 20.0;
+const double _dialogItemSpacing = 20.0;
+
+// 合并订阅对话框的结果
+class SubscriptionAggregateResult {
+  final String name;
+  final List<String> sourceIds; // 按订阅列表顺序
+  final String ruleSourceId; // 提供规则的订阅
+
+  const SubscriptionAggregateResult({
+    required this.name,
+    required this.sourceIds,
+    required this.ruleSourceId,
+  });
+}
+
+// 合并订阅对话框：选择多个订阅与规则来源，合并为新的本地订阅
+class SubscriptionAggregateDialog extends StatefulWidget {
+  final List<Subscription> subscriptions;
+  final Future<bool> Function(SubscriptionAggregateResult) onConfirm;
+
+  const SubscriptionAggregateDialog({
+    super.key,
+    required this.subscriptions,
+    required this.onConfirm,
+  });
+
+  static Future<void> show(
+    BuildContext context, {
+    required List<Subscription> subscriptions,
+    required Future<bool> Function(SubscriptionAggregateResult) onConfirm,
+  }) {
+    return showDialog<void>(
+      context: context,
+      barrierDismissible: false,
+      builder: (context) => SubscriptionAggregateDialog(
+        subscriptions: subscriptions,
+        onConfirm: onConfirm,
+      ),
+    );
+  }
+
+  @override
+  State<SubscriptionAggregateDialog> createState() =>
+      _SubscriptionAggregateDialogState();
+}
+
+class _SubscriptionAggregateDialogState
+    extends State<SubscriptionAggregateDialog> {
+  final _nameController = TextEditingController();
+  final _formKey = GlobalKey<FormState>();
+  final Set<String> _selectedIds = {};
+  String? _ruleSourceId;
+  bool _isLoading = false;
+
+  // 按订阅列表顺序排列的已选订阅
+  List<Subscription> get _selectedSubscriptions => [
+    for (final subscription in widget.subscriptions)
+      if (_selectedIds.contains(subscription.id)) subscription,
+  ];
+
+  bool get _canConfirm => _selectedIds.length >= 2 && _ruleSourceId != null;
+
+  @override
+  void dispose() {
+    _nameController.dispose();
+    super.dispose();
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    final aggregateTrans = context.translate.subscription_aggregate;
+
+    return ModernDialog(
+      title: aggregateTrans.title,
+      titleIcon: Icons.merge_type,
+      maxWidth: 640,
+      maxHeightRatio: 0.85,
+      content: _buildContent(),
+      actionsLeft: Text(
+        aggregateTrans.hint,
+        style: TextStyle(
+          fontSize: 12,
+          color: Theme.of(context).colorScheme.onSurface.withValues(alpha: 0.6),
+        ),
+      ),
+      actionsRight: [
+        DialogActionButton(
+          label: context.translate.common.cancel,
+          isPrimary: false,
+          onPressed: _isLoading ? null : () => Navigator.of(context).pop(),
+        ),
+        DialogActionButton(
+          label: aggregateTrans.confirm,
+          isPrimary: true,
+          isLoading: _isLoading,
+          onPressed: (_isLoading || !_canConfirm) ? null : _handleConfirm,
+        ),
+      ],
+      onClose: _isLoading ? null : () => Navigator.of(context).pop(),
+    );
+  }
+
+  Widget _buildContent() {
+    final aggregateTrans = context.translate.subscription_aggregate;
+    final selectedSubscriptions = _selectedSubscriptions;
+
+    return SingleChildScrollView(
+      padding: const EdgeInsets.all(_dialogContentPadding),
+      child: Form(
+        key: _formKey,
+        child: Column(
+          crossAxisAlignment: CrossAxisAlignment.start,
+          mainAxisSize: MainAxisSize.min,
+          children: [
+            TextInputField(
+              controller: _nameController,
+              label: aggregateTrans.name_label,
+              hint: aggregateTrans.name_hint,
+              icon: Icons.label_outline,
+              validator: (value) {
+                if (value == null || value.trim().isEmpty) {
+                  return aggregateTrans.name_error;
+                }
+                return null;
+              },
+            ),
+            const SizedBox(height: _dialogItemSpacing),
+            Text(
+              aggregateTrans.sources_title,
+              style: Theme.of(
+                context,
+              ).textTheme.titleSmall?.copyWith(fontWeight: FontWeight.bold),
+            ),
+            const SizedBox(height: 8),
+            for (final subscription in widget.subscriptions)
+              _buildSourceItem(subscription),
+            if (selectedSubscriptions.length >= 2) ...[
+              const SizedBox(height: _dialogItemSpacing),
+              OptionSelectorWidget<String>(
+                title: aggregateTrans.rule_source_title,
+                titleIcon: Icons.rule,
+                options: [
+                  for (final subscription in selectedSubscriptions)
+                    OptionItem(
+                      value: subscription.id,
+                      title: subscription.name,
+                    ),
+                ],
+                selectedValue: _ruleSourceId!,
+                onChanged: (id) => setState(() => _ruleSourceId = id),
+              ),
+            ],
+          ],
+        ),
+      ),
+    );
+  }
+
+  Widget _buildSourceItem(Subscription subscription) {
+    final colorScheme = Theme.of(context).colorScheme;
+    final isDark = Theme.of(context).brightness == Brightness.dark;
+
+    return Container(
+      margin: const EdgeInsets.only(bottom: 8),
+      padding: const EdgeInsets.symmetric(horizontal: 16, vertical: 12),
+      decoration: BoxDecoration(
+        color: Colors.white.withValues(alpha: isDark ? 0.04 : 0.5),
+        borderRadius: BorderRadius.circular(12),
+        border: Border.all(
+          color: Colors.white.withValues(alpha: isDark ? 0.1 : 0.2),
+        ),
+      ),
+      child: Row(
+        children: [
+          Expanded(
+            child: Text(
+              subscription.name,
+              style: TextStyle(fontSize: 14, color: colorScheme.onSurface),
+            ),
+          ),
+          ModernSwitch(
+            value: _selectedIds.contains(subscription.id),
+            onChanged: _isLoading
+                ? null
+                : (enabled) => _toggleSource(subscription.id, enabled),
+          ),
+        ],
+      ),
+    );
+  }
+
+  // 切换来源选择，规则来源默认取第一个已选订阅
+  void _toggleSource(String id, bool enabled) {
+    setState(() {
+      if (enabled) {
+        _selectedIds.add(id);
+      } else {
+        _selectedIds.remove(id);
+      }
+      if (_ruleSourceId == null || !_selectedIds.contains(_ruleSourceId)) {
+        final selected = _selectedSubscriptions;
+        _ruleSourceId = selected.isEmpty ? null : selected.first.id;
+      }
+    });
+  }
+
+  Future<void> _handleConfirm() async {
+    if (!_formKey.currentState!.validate()) {
+      return;
+    }
+
+    setState(() => _isLoading = true);
+
+    final result = SubscriptionAggregateResult(
+      name: _nameController.text.trim(),
+      sourceIds: [for (final s in _selectedSubscriptions) s.id],
+      ruleSourceId: _ruleSourceId!,
+    );
+
+    bool success = false;
+    String? errorMessage;
+    try {
+      success = await widget.onConfirm(result);
+    } catch (error) {
+      errorMessage = error.toString();
+      Logger.error('合并订阅异常：$error');
+    }
+
+    if (!mounted) return;
+
+    if (success) {
+      Navigator.of(context).pop();
+    } else {
+      setState(() => _isLoading = false);
+      ModernToast.error(
+        context.translate.subscription_aggregate.failed.replaceAll(
+          '{error}',
+          errorMessage ?? '',
+        ),
+      );
+    }
+  }
+}
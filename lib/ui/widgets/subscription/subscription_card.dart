@@ -10,6 +10,7 @@ import 'package:stelliberty/ui/widgets/modern_toast.dart';
 import 'package:stelliberty/ui/common/modern_popup_menu.dart';
 import 'package:stelliberty/ui/widgets/modern_tooltip.dart';
 import 'package:stelliberty/ui/widgets/subscription/qr_code_overlay.dart';
+import 'package:stelliberty/ui/widgets/subscription/subscription_diff_dialog.dart';
 import 'package:stelliberty/services/log_print_service.dart';
 
 // 订阅卡片组件：展示订阅概览与操作入口。
@@ -45,6 +46,9 @@ class SubscriptionCard extends StatelessWidget {
   // 查看提供者的回调
   final VoidCallback? onViewProvider;
 
+  // 导出分享链接的回调
+  final VoidCallback? onExportLinks;
+
   const SubscriptionCard({
     super.key,
     required this.subscription,
@@ -57,6 +61,7 @@ class SubscriptionCard extends StatelessWidget {
     this.onManageOverride,
     this.onViewConfig,
     this.onViewProvider,
+    this.onExportLinks,
   });
 
   @override
@@ -65,6 +70,7 @@ class SubscriptionCard extends StatelessWidget {
       builder: (context, provider, child) {
         final isUpdating = provider.isSubscriptionUpdating(subscription.id);
         final isBatchUpdating = provider.isBatchUpdatingSubscriptions;
+        final downloadProgress = provider.getDownloadProgress(subscription.id);
         final colorScheme = Theme.of(context).colorScheme;
         final isDark = Theme.of(context).brightness == Brightness.dark;
 
@@ -118,8 +124,16 @@ class SubscriptionCard extends StatelessWidget {
                         // 弹性空间，让状态标签推到底部
                         const Spacer(),
 
-                        // 状态标签与流量进度条并排（只有真正有流量数据时才显示进度条）
-                        if (subscription.info != null &&
+                        // 下载中显示下载进度；否则状态标签与流量进度条并排
+                        // （只有真正有流量数据时才显示进度条）
+                        if (downloadProgress != null)
+                          _buildDownloadProgress(
+                            context,
+                            downloadProgress.progress,
+                            downloadProgress.downloaded,
+                            downloadProgress.total,
+                          )
+                        else if (subscription.info != null &&
                             subscription.info!.total > 0)
                           _buildStatusWithTraffic(context)
                         else
@@ -285,6 +299,43 @@ class SubscriptionCard extends StatelessWidget {
       );
     }
 
+    // 最近一次更新的节点变化
+    final lastDiff = subscription.lastDiff;
+    if (lastDiff != null && lastDiff.hasChanges) {
+      children.add(
+        const TextSpan(
+          text: ' | ',
+          style: TextStyle(color: Colors.grey, fontSize: 11),
+        ),
+      );
+      children.add(
+        TextSpan(
+          text: SubscriptionDiffDialog.summaryText(context, lastDiff),
+          style: const TextStyle(color: Colors.orange, fontSize: 11),
+        ),
+      );
+    }
+
+    // 更新失败并回退到缓存时的缓存时间
+    final cacheFallbackTime = Provider.of<SubscriptionProvider>(
+      context,
+      listen: false,
+    ).getCacheFallbackTime(subscription.id);
+    if (cacheFallbackTime != null) {
+      children.add(
+        const TextSpan(
+          text: ' | ',
+          style: TextStyle(color: Colors.grey, fontSize: 11),
+        ),
+      );
+      children.add(
+        TextSpan(
+          text: cacheFallbackText(context, cacheFallbackTime),
+          style: const TextStyle(color: Colors.red, fontSize: 11),
+        ),
+      );
+    }
+
     return Text.rich(
       TextSpan(children: children),
       maxLines: 1,
@@ -292,6 +343,24 @@ class SubscriptionCard extends StatelessWidget {
     );
   }
 
+  // 回退到缓存的提示，例如「正在使用 5 分钟前的缓存内容」
+  static String cacheFallbackText(BuildContext context, DateTime cachedAt) {
+    final subTrans = context.translate.subscription;
+    final age = DateTime.now().difference(cachedAt);
+
+    final String ageText;
+    if (age.inMinutes < 1) {
+      ageText = subTrans.just_now;
+    } else if (age.inHours < 1) {
+      ageText = subTrans.minute_ago.replaceAll('{n}', '${age.inMinutes}');
+    } else if (age.inDays < 1) {
+      ageText = subTrans.hour_ago.replaceAll('{n}', '${age.inHours}');
+    } else {
+      ageText = subTrans.day_ago.replaceAll('{n}', '${age.inDays}');
+    }
+    return subTrans.cache_fallback.replaceAll('{age}', ageText);
+  }
+
   // 构建弹出菜单入口（使用自定义菜单组件）。
   // 菜单组件会自动处理分页逻辑。
   Widget _buildModernPopupMenu(BuildContext context, bool isDisabled) {
@@ -358,6 +427,12 @@ class SubscriptionCard extends StatelessWidget {
         label: trans.subscription.menu.provider_view,
         onPressed: onViewProvider,
       ),
+      if (subscription.lastDiff != null)
+        PopupMenuItemData(
+          icon: Icons.difference_outlined,
+          label: trans.subscription.menu.last_changes,
+          onPressed: () => _showLastChanges(context),
+        ),
       // 本地文件订阅不显示复制链接和二维码分享选项
       if (!subscription.isLocalFile)
         PopupMenuItemData(
@@ -371,6 +446,11 @@ class SubscriptionCard extends StatelessWidget {
           label: trans.subscription.menu.qr_share,
           onPressed: () => _showQrCode(context),
         ),
+      PopupMenuItemData(
+        icon: Icons.ios_share,
+        label: trans.subscription.menu.export_links,
+        onPressed: onExportLinks,
+      ),
       PopupMenuItemData(
         icon: Icons.delete,
         label: trans.subscription.menu.delete,
@@ -408,6 +488,44 @@ class SubscriptionCard extends StatelessWidget {
     );
   }
 
+  // 构建下载进度（总长度未知时显示不确定进度条与已接收字节数）
+  Widget _buildDownloadProgress(
+    BuildContext context,
+    double progress,
+    int downloaded,
+    int total,
+  ) {
+    final colorScheme = Theme.of(context).colorScheme;
+
+    return Row(
+      crossAxisAlignment: CrossAxisAlignment.center,
+      children: [
+        Expanded(
+          child: ClipRRect(
+            borderRadius: BorderRadius.circular(4),
+            child: LinearProgressIndicator(
+              value: total > 0 ? progress : null,
+              minHeight: 6,
+              backgroundColor: Colors.grey.withAlpha((255 * 0.2).round()),
+              valueColor: AlwaysStoppedAnimation<Color>(colorScheme.primary),
+            ),
+          ),
+        ),
+        const SizedBox(width: 12),
+        Text(
+          total > 0
+              ? '${_formatBytes(downloaded)}/${_formatBytes(total)}'
+              : _formatBytes(downloaded),
+          style: TextStyle(
+            fontSize: 11,
+            fontWeight: FontWeight.w600,
+            color: colorScheme.primary,
+          ),
+        ),
+      ],
+    );
+  }
+
   // 构建状态与流量并排显示
   Widget _buildStatusWithTraffic(BuildContext context) {
     final trans = context.translate;
@@ -530,6 +648,18 @@ class SubscriptionCard extends StatelessWidget {
     QrCodeOverlay.show(context, data: subscription.url);
   }
 
+  // 显示最近一次更新的变更
+  void _showLastChanges(BuildContext context) {
+    final lastDiff = subscription.lastDiff;
+    if (lastDiff == null) return;
+
+    SubscriptionDiffDialog.show(
+      context,
+      subscriptionName: subscription.name,
+      diff: lastDiff,
+    );
+  }
+
   // 格式化字节数
   String _formatBytes(int bytes) {
     if (bytes < 1024) return '${bytes}B';
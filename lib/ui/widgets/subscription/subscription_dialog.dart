@@ -10,6 +10,7 @@ import 'package:stelliberty/ui/common/modern_dialog_subs/text_input_field.dart';
 import 'package:stelliberty/ui/common/modern_dialog_subs/file_selector.dart';
 import 'package:stelliberty/ui/common/modern_dialog_subs/proxy_mode_selector.dart';
 import 'package:stelliberty/ui/common/modern_dialog_subs/auto_update_mode_selector.dart';
+import 'package:stelliberty/ui/common/modern_dialog_subs/http_settings_editor.dart';
 import 'package:stelliberty/i18n/i18n.dart';
 
 // 对话框间距常量
@@ -36,6 +37,7 @@ class SubscriptionDialog extends StatefulWidget {
   final bool? initialUpdateOnStartup;
   final SubscriptionProxyMode? initialProxyMode;
   final String? initialUserAgent;
+  final DownloadHttpSettings initialHttpSettings;
   final String confirmText;
   final IconData titleIcon;
   final bool isAddMode;
@@ -52,6 +54,7 @@ class SubscriptionDialog extends StatefulWidget {
     this.initialUpdateOnStartup,
     this.initialProxyMode,
     this.initialUserAgent,
+    this.initialHttpSettings = const DownloadHttpSettings(),
     this.confirmText = 'Confirm',
     this.titleIcon = Icons.rss_feed,
     this.isAddMode = false,
@@ -98,6 +101,7 @@ class SubscriptionDialog extends StatefulWidget {
         initialUpdateOnStartup: subscription.shouldUpdateOnStartup,
         initialProxyMode: subscription.proxyMode,
         initialUserAgent: subscription.userAgent,
+        initialHttpSettings: subscription.httpSettings,
         confirmText: trans.subscription_dialog.save_button,
         titleIcon: Icons.edit_outlined,
         isLocalFile: subscription.isLocalFile,
@@ -114,6 +118,7 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
   late final TextEditingController _urlController;
   late final TextEditingController _intervalController;
   late final TextEditingController _userAgentController;
+  late final HttpSettingsController _httpSettingsController;
   late AutoUpdateMode _autoUpdateMode;
   late SubscriptionProxyMode _proxyMode;
 
@@ -149,6 +154,9 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
     _userAgentController = TextEditingController(
       text: widget.initialUserAgent ?? '',
     );
+    _httpSettingsController = HttpSettingsController(
+      widget.initialHttpSettings,
+    );
 
     // 初始化自动更新模式和代理模式
     _autoUpdateMode = widget.initialAutoUpdateMode ?? AutoUpdateMode.disabled;
@@ -159,6 +167,7 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
     _urlController.addListener(_checkForChanges);
     _intervalController.addListener(_checkForChanges);
     _userAgentController.addListener(_checkForChanges);
+    _httpSettingsController.addListener(_checkForChanges);
   }
 
   // 检查内容是否发生变化
@@ -192,6 +201,10 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
       if (currentUA != initialUA) {
         return true;
       }
+
+      if (_httpSettingsController.isChangedFrom(widget.initialHttpSettings)) {
+        return true;
+      }
     }
 
     return false;
@@ -218,11 +231,13 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
     _urlController.removeListener(_checkForChanges);
     _intervalController.removeListener(_checkForChanges);
     _userAgentController.removeListener(_checkForChanges);
+    _httpSettingsController.removeListener(_checkForChanges);
     // 释放控制器
     _nameController.dispose();
     _urlController.dispose();
     _intervalController.dispose();
     _userAgentController.dispose();
+    _httpSettingsController.dispose();
     super.dispose();
   }
 
@@ -360,6 +375,15 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
               ),
               const SizedBox(height: _dialogItemSpacing),
               _buildUserAgentField(),
+              const SizedBox(height: _dialogItemSpacing),
+              HttpSettingsEditor(
+                controller: _httpSettingsController,
+                onCertificateTypeChanged: (value) {
+                  setState(
+                    () => _httpSettingsController.certificateType = value,
+                  );
+                },
+              ),
             ] else if (widget.isAddMode &&
                 _importMethod == SubscriptionImportMethod.localFile) ...[
               const SizedBox(height: _dialogItemSpacing),
@@ -526,6 +550,9 @@ class _SubscriptionDialogState extends State<SubscriptionDialog> {
         localFilePath: _selectedFile?.file.path,
         proxyMode: _proxyMode,
         userAgent: userAgent.isEmpty ? _defaultUserAgent : userAgent,
+        httpSettings: _importMethod == SubscriptionImportMethod.link
+            ? _httpSettingsController.settings
+            : const DownloadHttpSettings(),
       );
 
       // 如果有确认回调，调用它并等待结果
@@ -596,6 +623,7 @@ class SubscriptionDialogResult {
   final String? localFilePath;
   final SubscriptionProxyMode proxyMode;
   final String userAgent;
+  final DownloadHttpSettings httpSettings;
 
   const SubscriptionDialogResult({
     required this.name,
@@ -607,5 +635,6 @@ class SubscriptionDialogResult {
     this.localFilePath,
     this.proxyMode = SubscriptionProxyMode.direct,
     String? userAgent,
+    this.httpSettings = const DownloadHttpSettings(),
   }) : userAgent = userAgent ?? ClashDefaults.defaultUserAgent;
 }
@@ -0,0 +1,157 @@
+import 'package:flutter/material.dart';
+import 'package:stelliberty/clash/model/subscription_model.dart';
+import 'package:stelliberty/i18n/i18n.dart';
+import 'package:stelliberty/ui/common/modern_dialog.dart';
+
+// 订阅变更对话框
+// 显示最近一次更新与旧版本相比的节点、代理组与规则变化
+class SubscriptionDiffDialog extends StatelessWidget {
+  final String subscriptionName;
+  final SubscriptionDiffSummary diff;
+
+  const SubscriptionDiffDialog({
+    super.key,
+    required this.subscriptionName,
+    required this.diff,
+  });
+
+  static Future<void> show(
+    BuildContext context, {
+    required String subscriptionName,
+    required SubscriptionDiffSummary diff,
+  }) {
+    return showDialog<void>(
+      context: context,
+      builder: (context) => SubscriptionDiffDialog(
+        subscriptionName: subscriptionName,
+        diff: diff,
+      ),
+    );
+  }
+
+  // 节点变化摘要，例如「节点 +2 −1 ~3」
+  static String summaryText(
+    BuildContext context,
+    SubscriptionDiffSummary diff,
+  ) {
+    final changedCount = diff.renamedNodes.length + diff.modifiedNodes.length;
+    return context.translate.subscription.diff.summary
+        .replaceAll('{added}', diff.addedNodes.length.toString())
+        .replaceAll('{removed}', diff.removedNodes.length.toString())
+        .replaceAll('{changed}', changedCount.toString());
+  }
+
+  @override
+  Widget build(BuildContext context) {
+    final diffTrans = context.translate.subscription.diff;
+
+    return ModernDialog(
+      title: diffTrans.title,
+      subtitle: subscriptionName,
+      titleIcon: Icons.difference_outlined,
+      maxWidth: 600,
+      maxHeightRatio: 0.8,
+      content: _buildContent(context),
+      actionsRight: [
+        DialogActionButton(
+          label: diffTrans.close,
+          isPrimary: false,
+          onPressed: () => Navigator.of(context).pop(),
+        ),
+      ],
+    );
+  }
+
+  Widget _buildContent(BuildContext context) {
+    final diffTrans = context.translate.subscription.diff;
+
+    if (!diff.hasChanges) {
+      return Padding(
+        padding: const EdgeInsets.all(24),
+        child: Center(child: Text(diffTrans.no_changes)),
+      );
+    }
+
+    final sections = {
+      diffTrans.added_nodes: (diff.addedNodes, Colors.green),
+      diffTrans.removed_nodes: (diff.removedNodes, Colors.red),
+      diffTrans.renamed_nodes: (diff.renamedNodes, Colors.orange),
+      diffTrans.modified_nodes: (diff.modifiedNodes, Colors.orange),
+      diffTrans.added_groups: (diff.addedGroups, Colors.green),
+      diffTrans.removed_groups: (diff.removedGroups, Colors.red),
+      diffTrans.modified_groups: (diff.modifiedGroups, Colors.orange),
+    };
+
+    return SingleChildScrollView(
+      padding: const EdgeInsets.symmetric(horizontal: 24, vertical: 20),
+      child: Column(
+        crossAxisAlignment: CrossAxisAlignment.start,
+        mainAxisSize: MainAxisSize.min,
+        children: [
+          for (final entry in sections.entries)
+            if (entry.value.$1.isNotEmpty)
+              _buildSection(context, entry.key, entry.value.$1, entry.value.$2),
+          if (diff.addedRuleCount > 0 || diff.removedRuleCount > 0)
+            _buildSection(
+              context,
+              diffTrans.rules,
+              [
+                diffTrans.rule_changes
+                    .replaceAll('{added}', diff.addedRuleCount.toString())
+                    .replaceAll('{removed}', diff.removedRuleCount.toString()),
+              ],
+              Colors.grey,
+              isCounted: false,
+            ),
+        ],
+      ),
+    );
+  }
+
+  // 构建变化分组
+  Widget _buildSection(
+    BuildContext context,
+    String title,
+    List<String> items,
+    Color color, {
+    bool isCounted = true,
+  }) {
+    return Padding(
+      padding: const EdgeInsets.only(bottom: 16.0),
+      child: Column(
+        crossAxisAlignment: CrossAxisAlignment.start,
+        children: [
+          Text(
+            isCounted ? '$title (${items.length})' : title,
+            style: Theme.of(
+              context,
+            ).textTheme.titleSmall?.copyWith(fontWeight: FontWeight.bold),
+          ),
+          const SizedBox(height: 8),
+          Wrap(
+            spacing: 6,
+            runSpacing: 6,
+            children: items
+                .map(
+                  (item) => Container(
+                    padding: const EdgeInsets.symmetric(
+                      horizontal: 8,
+                      vertical: 4,
+                    ),
+                    decoration: BoxDecoration(
+                      color: color.withValues(alpha: 0.12),
+                      borderRadius: BorderRadius.circular(6),
+                    ),
+                    child: SelectableText(
+                      item,
+                      style: TextStyle(fontSize: 12, color: color),
+                    ),
+                  ),
+                )
+                .toList(),
+          ),
+        ],
+      ),
+    );
+  }
+}
@@ -0,0 +1,71 @@
+// JavaScript 覆写基准：测量大型配置下首次执行（含解析）与使用预先解析脚本的重复执行耗时。
+// 运行方式：cargo bench --bench js_override
+
+use hub::atoms::override_processor::{JsExecutionLimits, JsExecutor};
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+// 测试的节点数量与每种规模的重复次数
+const NODE_COUNTS: [usize; 3] = [500, 2_000, 5_000];
+const ITERATIONS: u32 = 5;
+
+// 典型覆写脚本：过滤节点、改名、追加代理组与规则
+const SCRIPT: &str = r#"
+function main(config, args) {
+    config.proxies = config.proxies.filter(function (p) { return !/过期|剩余/.test(p.name); });
+    config.proxies.forEach(function (p) { p.name = args.prefix + p.name; p.udp = true; });
+    $utils.upsertGroup(config, { name: 'AUTO', type: 'url-test', proxies: $utils.proxyNames(config) });
+    $utils.appendRules(config, ['DOMAIN-SUFFIX,example.com,AUTO']);
+    return config;
+}
+"#;
+
+fn main() -> Result<(), String> {
+    let mut executor = JsExecutor::with_limits(JsExecutionLimits {
+        timeout: Duration::from_secs(120),
+        ..JsExecutionLimits::default()
+    });
+    let arguments = Some(r#"{"prefix": "★ "}"#);
+
+    for node_count in NODE_COUNTS {
+        let config = build_config(node_count);
+
+        // 每种规模使用不同脚本内容，使首次执行没有预先解析的脚本
+        let script = format!("{}\n// {}", SCRIPT, node_count);
+        let started = Instant::now();
+        executor.apply(&config, &script, arguments)?;
+        let cold = started.elapsed();
+
+        let started = Instant::now();
+        for _ in 0..ITERATIONS {
+            executor.apply(&config, &script, arguments)?;
+        }
+        let warm = started.elapsed() / ITERATIONS;
+
+        println!(
+            "{:>5} 个节点（{:>5} KiB）：首次 {:>8.2?}，重复执行平均 {:>8.2?}",
+            node_count,
+            config.len() / 1024,
+            cold,
+            warm
+        );
+    }
+    Ok(())
+}
+
+// 生成包含指定数量节点的 Clash 配置
+fn build_config(node_count: usize) -> String {
+    let mut config = String::from("mixed-port: 7890\nmode: rule\nproxies:\n");
+    for i in 0..node_count {
+        let _ = writeln!(
+            config,
+            "  - {{name: \"节点 {i:05}\", type: vmess, server: s{i}.example.com, port: {port}, \
+             uuid: 9c7f3b1e-0000-4000-8000-{i:012}, alterId: 0, cipher: auto, tls: true, \
+             network: ws, ws-opts: {{path: \"/ray\\u2028{i}\", headers: {{Host: cdn.example.com}}}}}}",
+            port = 10_000 + i % 50_000
+        );
+    }
+    config.push_str("proxy-groups:\n  - {name: PROXY, type: select, proxies: [DIRECT]}\n");
+    config.push_str("rules:\n  - DOMAIN-SUFFIX,google.com,PROXY\n  - MATCH,PROXY\n");
+    config
+}
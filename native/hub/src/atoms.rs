@@ -13,5 +13,5 @@ pub use ipc_client::{IpcClient, IpcHttpResponse};
 pub use logger::init;
 pub use override_processor::OverrideProcessor;
 pub use path_resolver as path_service;
-pub use proxy_parser::ProxyParser;
-pub use shared_types::{OverrideConfig, OverrideFormat};
+pub use proxy_parser::{ProfileDiffer, ProxyParser, ProxySerializer, SubscriptionAggregator};
+pub use shared_types::{OverrideConfig, OverrideFormat, ParseReport, SubscriptionParseOptions};
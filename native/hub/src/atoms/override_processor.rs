@@ -1,10 +1,17 @@
-// 覆写处理器原子模块：提供 YAML 合并与 JavaScript 执行能力。
+// 覆写处理器原子模块：提供 YAML 合并、JSON Patch 与 JavaScript 执行能力。
 // 面向上层提供稳定的覆写处理接口。
 
+mod conditions;
 mod js_executor;
+mod js_host;
+mod js_runtime;
+mod js_value;
+mod json_patch;
 mod processor;
+mod trace;
 mod yaml_merger;
 
-pub use js_executor::JsExecutor;
+pub use js_executor::{JsExecutionLimits, JsExecutor};
+pub use json_patch::{JsonPatcher, PatchError};
 pub use processor::OverrideProcessor;
 pub use yaml_merger::YamlMerger;
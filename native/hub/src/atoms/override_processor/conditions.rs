@@ -0,0 +1,202 @@
+// 覆写匹配条件：按订阅、操作系统、运行时开关与核心版本决定是否应用覆写。
+// 条件所需信息缺失时视为不满足，跳过覆写并给出原因。
+
+use crate::atoms::shared_types::{OverrideConditions, OverrideMatchContext, RuntimeFlag};
+use regex::Regex;
+
+// 匹配条件判定器
+pub struct ConditionMatcher;
+
+impl ConditionMatcher {
+    // 判断条件是否全部满足，不满足时返回跳过原因
+    pub fn check(
+        conditions: &OverrideConditions,
+        context: &OverrideMatchContext,
+    ) -> Result<(), String> {
+        if let Some(pattern) = &conditions.subscription_pattern {
+            Self::check_subscription(pattern, context)?;
+        }
+        if !conditions.platforms.is_empty() {
+            Self::check_platform(&conditions.platforms, std::env::consts::OS)?;
+        }
+        for condition in &conditions.runtime_flags {
+            let enabled_flags = context
+                .runtime_flags
+                .as_ref()
+                .ok_or("运行时开关未知，无法判断开关条件")?;
+            let is_enabled = enabled_flags.contains(&condition.flag);
+            if is_enabled != condition.is_enabled {
+                return Err(format!(
+                    "{}{}，要求{}",
+                    Self::flag_name(condition.flag),
+                    Self::state_name(is_enabled),
+                    Self::state_name(condition.is_enabled)
+                ));
+            }
+        }
+        if let Some(min_version) = &conditions.min_core_version {
+            Self::check_core_version(min_version, context.core_version.as_deref())?;
+        }
+        Ok(())
+    }
+
+    fn check_subscription(pattern: &str, context: &OverrideMatchContext) -> Result<(), String> {
+        let regex = Regex::new(pattern).map_err(|e| format!("订阅匹配规则无效：{}", e))?;
+        let candidates: Vec<&str> = [&context.subscription_id, &context.subscription_url]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        if candidates.is_empty() {
+            return Err("当前订阅未知，无法判断订阅条件".to_string());
+        }
+        if candidates.iter().any(|candidate| regex.is_match(candidate)) {
+            Ok(())
+        } else {
+            Err(format!("当前订阅不匹配 {}", pattern))
+        }
+    }
+
+    fn check_platform(platforms: &[String], current: &str) -> Result<(), String> {
+        if platforms
+            .iter()
+            .any(|platform| platform.trim().eq_ignore_ascii_case(current))
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "当前系统 {} 不在 {} 之列",
+                current,
+                platforms.join(" / ")
+            ))
+        }
+    }
+
+    fn check_core_version(min_version: &str, current: Option<&str>) -> Result<(), String> {
+        let required = Self::parse_version(min_version)
+            .ok_or_else(|| format!("最低核心版本 {} 无法识别", min_version))?;
+        let current_version = current.ok_or("核心版本未知，无法判断版本条件")?;
+        let actual = Self::parse_version(current_version)
+            .ok_or_else(|| format!("核心版本 {} 无法识别", current_version))?;
+
+        // 逐段比较，缺少的段视为 0
+        let length = required.len().max(actual.len());
+        let pad = |parts: &[u64]| {
+            let mut parts = parts.to_vec();
+            parts.resize(length, 0);
+            parts
+        };
+        if pad(&actual) >= pad(&required) {
+            Ok(())
+        } else {
+            Err(format!("核心版本 {} 低于 {}", current_version, min_version))
+        }
+    }
+
+    // 提取版本号中第一段“数字.数字…”，如 "Mihomo Meta v1.19.13" → [1, 19, 13]
+    fn parse_version(version: &str) -> Option<Vec<u64>> {
+        let start = version.find(|c: char| c.is_ascii_digit())?;
+        let digits: String = version[start..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        digits
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse().ok())
+            .collect()
+    }
+
+    fn flag_name(flag: RuntimeFlag) -> &'static str {
+        match flag {
+            RuntimeFlag::TunEnabled => "TUN 模式",
+            RuntimeFlag::Ipv6Enabled => "IPv6",
+            RuntimeFlag::AllowLanEnabled => "局域网连接",
+            RuntimeFlag::DnsOverrideEnabled => "DNS 覆写",
+            RuntimeFlag::KeepAliveEnabled => "TCP Keep-Alive",
+            RuntimeFlag::TcpConcurrentEnabled => "TCP 并发",
+            RuntimeFlag::UnifiedDelayEnabled => "统一延迟",
+        }
+    }
+
+    fn state_name(is_enabled: bool) -> &'static str {
+        if is_enabled { "已开启" } else { "已关闭" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atoms::shared_types::RuntimeFlagCondition;
+
+    #[test]
+    fn test_conditions() {
+        let context = OverrideMatchContext {
+            subscription_id: Some("sub-1".to_string()),
+            subscription_url: Some("https://provider.example/clash?token=x".to_string()),
+            core_version: Some("Mihomo Meta v1.19.13 linux amd64".to_string()),
+            runtime_flags: Some(vec![RuntimeFlag::TunEnabled]),
+        };
+        let check = |conditions: OverrideConditions| ConditionMatcher::check(&conditions, &context);
+
+        assert!(check(OverrideConditions::default()).is_ok());
+        assert!(
+            check(OverrideConditions {
+                subscription_pattern: Some(r"provider\.example".to_string()),
+                runtime_flags: vec![
+                    RuntimeFlagCondition {
+                        flag: RuntimeFlag::TunEnabled,
+                        is_enabled: true,
+                    },
+                    RuntimeFlagCondition {
+                        flag: RuntimeFlag::Ipv6Enabled,
+                        is_enabled: false,
+                    },
+                ],
+                min_core_version: Some("v1.19".to_string()),
+                ..Default::default()
+            })
+            .is_ok()
+        );
+        assert!(
+            check(OverrideConditions {
+                subscription_pattern: Some("^other$".to_string()),
+                ..Default::default()
+            })
+            .is_err()
+        );
+        assert!(
+            check(OverrideConditions {
+                min_core_version: Some("1.19.14".to_string()),
+                ..Default::default()
+            })
+            .is_err()
+        );
+        assert!(
+            check(OverrideConditions {
+                platforms: vec!["Plan9".to_string()],
+                ..Default::default()
+            })
+            .is_err()
+        );
+
+        // 缺少判断所需信息时跳过
+        let unknown = OverrideMatchContext::default();
+        let conditions = OverrideConditions {
+            runtime_flags: vec![RuntimeFlagCondition {
+                flag: RuntimeFlag::TunEnabled,
+                is_enabled: false,
+            }],
+            ..Default::default()
+        };
+        assert!(ConditionMatcher::check(&conditions, &unknown).is_err());
+        assert!(ConditionMatcher::check(&conditions, &context).is_err());
+
+        assert!(ConditionMatcher::check_platform(&["Linux".to_string()], "linux").is_ok());
+        assert_eq!(
+            ConditionMatcher::parse_version("v1.19.13"),
+            Some(vec![1, 19, 13])
+        );
+        assert_eq!(ConditionMatcher::parse_version("Unknown"), None);
+    }
+}
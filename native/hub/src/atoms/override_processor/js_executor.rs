@@ -1,159 +1,303 @@
 // JavaScript 覆写执行器：使用 Boa 引擎执行用户脚本。
-// 脚本需提供 `main(config)` 入口函数。
+// 脚本需提供 `main(config, $arguments)` 入口函数；在常驻运行时线程中执行（每次使用新的上下文，并按内容哈希预先解析脚本），
+// 并受循环次数、递归深度、执行时间与输出大小限制。console 输出随结果一并返回。
 
-use boa_engine::{Context, Source};
+use super::js_host::JsHost;
+use super::js_runtime::{ScriptJob, ScriptOutcome, ScriptRuntime};
 use serde_json::Value as JsonValue;
-use serde_yaml_ng::Value as YamlValue;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
+
+// JavaScript 执行限制
+#[derive(Debug, Clone, Copy)]
+pub struct JsExecutionLimits {
+    pub loop_iteration_limit: u64, // 单个函数内循环迭代次数上限
+    pub recursion_limit: usize,    // 函数递归深度上限
+    pub timeout: Duration,         // 执行时间上限（由看门狗计时）
+    pub max_output_bytes: usize,   // 脚本返回的配置大小上限（按 JSON 估算）
+}
+
+impl Default for JsExecutionLimits {
+    fn default() -> Self {
+        Self {
+            loop_iteration_limit: 10_000_000,
+            recursion_limit: 256,
+            timeout: Duration::from_secs(10),
+            max_output_bytes: 32 * 1024 * 1024,
+        }
+    }
+}
 
 // JavaScript 执行器
 pub struct JsExecutor {
-    context: Context,
+    limits: JsExecutionLimits,
+    logs: Vec<String>, // 最近一次执行的 console 输出
 }
 
 impl JsExecutor {
-    // 创建 JavaScript 执行器并初始化 Boa 上下文。
+    // 创建使用默认执行限制的 JavaScript 执行器。
     pub fn new() -> Result<Self, String> {
-        let context = Context::default();
+        Ok(Self::with_limits(JsExecutionLimits::default()))
+    }
 
-        Ok(Self { context })
+    // 创建使用指定执行限制的 JavaScript 执行器。
+    pub fn with_limits(limits: JsExecutionLimits) -> Self {
+        Self {
+            limits,
+            logs: Vec::new(),
+        }
+    }
+
+    // 取出最近一次执行的 console 输出（执行失败时同样可用）
+    pub fn take_logs(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.logs)
     }
 
-    // 应用 JavaScript 覆写：YAML→JSON→执行 main(config)→YAML。
-    // 返回覆写后的配置内容。
-    pub fn apply(&mut self, base_content: &str, js_code: &str) -> Result<String, String> {
+    // 应用 JavaScript 覆写：YAML→JSON→执行 main(config, $arguments)→YAML。
+    // 配置以 JSON 值直接转换为脚本对象，不经过 JSON 文本；arguments 为 JSON 对象文本。
+    pub fn apply(
+        &mut self,
+        base_content: &str,
+        js_code: &str,
+        arguments: Option<&str>,
+    ) -> Result<String, String> {
         log::info!("JavaScript 覆写开始");
+        self.logs.clear();
+        let arguments = JsHost::parse_arguments(arguments)?;
         log::info!("基础配置长度：{}字节", base_content.len());
         log::info!("JS 脚本长度：{}字节", js_code.len());
 
         // 1. 解析 YAML → JSON
-        let yaml_val: YamlValue = serde_yaml_ng::from_str(base_content).map_err(|e| {
+        let config: JsonValue = serde_yaml_ng::from_str(base_content).map_err(|e| {
             log::error!("✗ 解析 YAML 配置失败：{}", e);
             format!("解析配置失败：{}", e)
         })?;
 
-        let json_val: JsonValue = serde_json::to_value(&yaml_val).map_err(|e| {
-            log::error!("✗ 转换为 JSON 失败：{}", e);
-            format!("转换为 JSON 失败：{}", e)
-        })?;
+        match config.get("proxies").and_then(|p| p.as_array()) {
+            Some(proxies) => log::info!("配置中包含{}个代理节点", proxies.len()),
+            None => log::warn!("配置中未找到 proxies 字段"),
+        }
+
+        // 2. 在脚本运行时中执行，看门狗负责超时
+        log::info!("→ 开始执行 JavaScript…");
+        let result = self.run_with_watchdog(js_code, config, arguments)?;
+        log::info!("✓ JavaScript 执行成功");
+
+        match result.get("proxies").and_then(|p| p.as_array()) {
+            Some(proxies) => log::info!("返回的配置中包含{}个代理节点", proxies.len()),
+            None => log::warn!("返回的配置中未找到 proxies 字段"),
+        }
 
-        let config_json = serde_json::to_string(&json_val).map_err(|e| {
-            log::error!("✗ 序列化 JSON 失败：{}", e);
-            format!("序列化 JSON 失败：{}", e)
+        // 3. JSON → YAML
+        let final_yaml = serde_yaml_ng::to_string(&result).map_err(|e| {
+            log::error!("✗ 序列化 YAML 失败：{}", e);
+            format!("序列化 YAML 失败：{}", e)
         })?;
 
-        log::info!(
-            "✓ YAML → JSON 转换成功，JSON 长度：{}字节",
-            config_json.len()
-        );
+        log::info!("✓ YAML 序列化成功，最终长度：{} 字节", final_yaml.len());
+
+        log::info!("JavaScript 覆写成功");
+        Ok(final_yaml)
+    }
+
+    // 将脚本提交到运行时线程并等待结果。
+    // Boa 无法从外部中断执行（指令预算仅在 fuzz 特性下提供），超时后只能放弃该运行时。
+    // 循环次数按单个循环计算，嵌套循环仍可长时间运行，因此超时线程数量达到上限时拒绝新的执行。
+    fn run_with_watchdog(
+        &mut self,
+        js_code: &str,
+        config: JsonValue,
+        arguments: JsonValue,
+    ) -> Result<JsonValue, String> {
+        let (reply, receiver) = mpsc::channel::<ScriptOutcome>();
+        let job = ScriptJob {
+            code: js_code.to_string(),
+            config,
+            arguments,
+            limits: self.limits,
+            reply,
+        };
+
+        // 空闲运行时的线程可能已异常退出，此时改用新线程重试一次
+        let runtime = ScriptRuntime::acquire(self.limits.timeout)?;
+        let runtime = match runtime.submit(job) {
+            Ok(()) => runtime,
+            Err(job) => {
+                let runtime = ScriptRuntime::spawn()?;
+                runtime
+                    .submit(*job)
+                    .map_err(|_| "JavaScript 执行线程异常退出".to_string())?;
+                runtime
+            }
+        };
 
-        // 检查 proxies 字段
-        if let Some(proxies) = json_val.get("proxies") {
-            if let Some(arr) = proxies.as_array() {
-                log::info!("配置中包含{}个代理节点", arr.len());
-                if let Some(first_proxy) = arr.first() {
-                    log::info!(
-                        "  第一个代理节点：{}",
-                        serde_json::to_string(first_proxy).unwrap_or_default()
-                    );
+        match receiver.recv_timeout(self.limits.timeout) {
+            Ok((result, logs)) => {
+                runtime.release();
+                for line in &logs {
+                    log::info!("[脚本] {}", line);
                 }
+                self.logs = logs;
+                result
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                log::error!("✗ JavaScript 执行超时");
+                runtime.abandon();
+                Err(format!(
+                    "JavaScript 执行超时（超过 {} 毫秒）",
+                    self.limits.timeout.as_millis()
+                ))
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                log::error!("✗ JavaScript 执行线程异常退出");
+                Err("JavaScript 执行线程异常退出".to_string())
             }
-        } else {
-            log::warn!("配置中未找到 proxies 字段");
         }
+    }
+}
 
-        // 转义 JSON 字符串中的反斜杠和单引号，以便安全地嵌入 JavaScript
-        let escaped_config = config_json.replace('\\', "\\\\").replace('\'', "\\'");
-
-        // 2. 构建完整的 JavaScript 代码
-        // 用户脚本必须定义 main(config) 函数
-        let full_js_code = format!(
-            r#"
-            (function() {{
-                // 用户的覆写代码（定义 main 函数）
-                {}
-
-                // 初始化配置对象（从基础配置的 JSON）
-                var config = JSON.parse('{}');
-
-                // 调用 main 函数并传入配置
-                if (typeof main === 'function') {{
-                    config = main(config);
-                }} else {{
-                    throw new Error('覆写脚本必须定义 main(config) 函数');
-                }}
-
-                // 返回修改后的配置
-                return JSON.stringify(config);
-            }})()
-            "#,
-            js_code, escaped_config
-        );
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        log::info!(
-            "✓ JavaScript 代码构建完成，总长度：{}字节",
-            full_js_code.len()
-        );
+    #[test]
+    fn test_execution_limits() -> Result<(), String> {
+        let mut executor = JsExecutor::with_limits(JsExecutionLimits {
+            loop_iteration_limit: 1_000,
+            recursion_limit: 32,
+            timeout: Duration::from_secs(5),
+            max_output_bytes: 1024,
+        });
+        let base = "proxies: []";
 
-        // 3. 执行 JavaScript
-        log::info!("→ 开始执行 JavaScript…");
-        let source = Source::from_bytes(&full_js_code);
-        let result = self.context.eval(source).map_err(|e| {
-            log::error!("✗ JavaScript 执行失败：{}", e);
-            format!("JavaScript 执行失败：{}", e)
-        })?;
+        let result = executor.apply(
+            base,
+            "function main(c) { c.mode = 'rule'; return c; }",
+            None,
+        )?;
+        assert!(result.contains("mode: rule"));
 
-        log::info!("✓ JavaScript 执行成功");
+        let error = executor
+            .apply(base, "function main(c) { while (true) {} }", None)
+            .err()
+            .ok_or("死循环未被中止")?;
+        assert!(error.contains("循环迭代次数上限"), "{}", error);
 
-        // 4. 提取结果字符串
-        let result_str = result.to_string(&mut self.context).map_err(|e| {
-            log::error!("✗ 提取 JavaScript 结果失败：{}", e);
-            format!("提取 JavaScript 结果失败：{}", e)
-        })?;
+        let error = executor
+            .apply(
+                base,
+                "function f(n) { return f(n + 1); } function main(c) { return f(0); }",
+                None,
+            )
+            .err()
+            .ok_or("无限递归未被中止")?;
+        assert!(error.contains("递归深度上限"), "{}", error);
 
-        let result_str = result_str.to_std_string().map_err(|e| {
-            log::error!("✗ 转换结果字符串失败：{}", e);
-            format!("转换结果字符串失败：{}", e)
-        })?;
+        let error = executor
+            .apply(
+                base,
+                "function main(c) { c.data = 'x'.repeat(4096); return c; }",
+                None,
+            )
+            .err()
+            .ok_or("超大输出未被拒绝")?;
+        assert!(error.contains("输出超过大小上限"), "{}", error);
 
-        log::info!("✓ JavaScript 结果长度：{}字节", result_str.len());
+        // 看门狗超时：脚本线程随后由循环次数上限结束
+        let mut executor = JsExecutor::with_limits(JsExecutionLimits {
+            timeout: Duration::from_millis(10),
+            ..JsExecutionLimits::default()
+        });
+        let error = executor
+            .apply(base, "function main(c) { while (true) {} }", None)
+            .err()
+            .ok_or("超时未被中止")?;
+        assert!(error.contains("执行超时"), "{}", error);
 
-        // 5. JSON → YAML
-        let json_result: JsonValue = serde_json::from_str(&result_str).map_err(|e| {
-            log::error!("✗ 解析 JavaScript 结果失败：{}", e);
-            log::error!("✗ 错误的 JSON 内容：{}", result_str);
-            format!("解析 JavaScript 结果失败：{}", e)
-        })?;
+        // 超时的脚本仍在运行时，其他脚本照常执行
+        let mut executor = JsExecutor::new()?;
+        let result = executor.apply(
+            base,
+            "function main(c) { c.mode = 'global'; return c; }",
+            None,
+        )?;
+        assert!(result.contains("mode: global"));
+        Ok(())
+    }
 
-        log::info!("✓ JSON 解析成功");
-
-        // 检查返回的 proxies 字段
-        if let Some(proxies) = json_result.get("proxies") {
-            if let Some(arr) = proxies.as_array() {
-                log::info!("返回的配置中包含{}个代理节点", arr.len());
-                if let Some(first_proxy) = arr.first() {
-                    log::info!(
-                        "  返回的第一个代理节点：{}",
-                        serde_json::to_string(first_proxy).unwrap_or_default()
-                    );
-                }
-            }
-        } else {
-            log::warn!("返回的配置中未找到 proxies 字段");
-        }
+    #[test]
+    fn test_host_api() -> Result<(), String> {
+        let mut executor = JsExecutor::new()?;
+        let base = r#"
+proxies:
+  - {name: "HK 01", type: ss, server: hk.example.com, port: 1}
+  - {name: "过期时间", type: ss, server: info.example.com, port: 1}
+proxy-groups:
+  - {name: PROXY, type: select, proxies: ["HK 01", "过期时间"]}
+rules:
+  - MATCH,PROXY
+"#;
+        let script = r#"
+function main(config, args) {
+    console.log('mode', args.mode, { n: $arguments.n });
+    $utils.removeProxies(config, '过期');
+    $utils.appendRules(config, 'DOMAIN,example.com,DIRECT');
+    config.extra = YAML.parse(YAML.stringify({ tags: $utils.proxyNames(config) }));
+    config.mode = args.mode;
+    return config;
+}
+"#;
 
-        let yaml_result: YamlValue = serde_json::from_value(json_result).map_err(|e| {
-            log::error!("✗ 转换为 YAML 失败：{}", e);
-            format!("转换为 YAML 失败：{}", e)
-        })?;
+        let result = executor.apply(base, script, Some(r#"{"mode": "global", "n": 1}"#))?;
+        let config: serde_yaml_ng::Value =
+            serde_yaml_ng::from_str(&result).map_err(|e| e.to_string())?;
+        assert_eq!(config["mode"].as_str(), Some("global"));
+        assert_eq!(config["proxies"].as_sequence().map(|p| p.len()), Some(1));
+        assert_eq!(
+            config["proxy-groups"][0]["proxies"][0].as_str(),
+            Some("HK 01")
+        );
+        assert_eq!(config["rules"][1].as_str(), Some("MATCH,PROXY"));
+        assert_eq!(config["extra"]["tags"][0].as_str(), Some("HK 01"));
+        assert_eq!(executor.take_logs(), [r#"[log] mode global {"n":1}"#]);
 
-        let final_yaml = serde_yaml_ng::to_string(&yaml_result).map_err(|e| {
-            log::error!("✗ 序列化 YAML 失败：{}", e);
-            format!("序列化 YAML 失败：{}", e)
-        })?;
+        assert!(executor.apply(base, script, Some("[1]")).is_err());
+        Ok(())
+    }
 
-        log::info!("✓ YAML 序列化成功，最终长度：{} 字节", final_yaml.len());
+    #[test]
+    fn test_value_conversion() -> Result<(), String> {
+        let mut executor = JsExecutor::new()?;
+        let base = "name: \"a\\u2028b'c\\\\\"\nport: 443\nratio: 0.5\n";
+        let script = r#"
+function main(config) {
+    config.same = config.name === 'a\u2028b\'c\\';
+    config.port = Math.floor(config.port * 1.0);
+    config.half = config.ratio * 3;
+    config.skip = function () {};
+    config.gone = undefined;
+    config.list = [undefined, NaN];
+    config.when = { toJSON: function () { return 'now'; } };
+    return config;
+}
+"#;
 
-        log::info!("JavaScript 覆写成功");
-        Ok(final_yaml)
+        let result = executor.apply(base, script, None)?;
+        let config: JsonValue = serde_yaml_ng::from_str(&result).map_err(|e| e.to_string())?;
+        assert_eq!(config["name"], "a\u{2028}b'c\\");
+        assert_eq!(config["same"], true);
+        assert_eq!(config["port"], 443);
+        assert_eq!(config["half"], 1.5);
+        assert_eq!(config["list"], serde_json::json!([null, null]));
+        assert_eq!(config["when"], "now");
+        assert!(config.get("skip").is_none() && config.get("gone").is_none());
+        assert!(result.contains("port: 443\n"));
+
+        let error = executor
+            .apply(base, "function main(c) { c.self = c; return c; }", None)
+            .err()
+            .ok_or("循环引用未被拒绝")?;
+        assert!(error.contains("循环引用"), "{}", error);
+        Ok(())
     }
 }
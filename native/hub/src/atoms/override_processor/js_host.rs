@@ -0,0 +1,242 @@
+// JavaScript 覆写宿主接口：为脚本提供 console、$arguments、YAML 与 $utils 辅助函数。
+// 约定与 Clash Verge / Mihomo Party 一致：入口为 main(config)，第二个参数为脚本参数。
+
+use boa_engine::property::Attribute;
+use boa_engine::{
+    Context, JsArgs, JsNativeError, JsResult, JsString, JsValue, NativeFunction, Source, js_string,
+};
+use serde_json::Value as JsonValue;
+use serde_yaml_ng::Value as YamlValue;
+use std::cell::RefCell;
+
+// 单次执行最多保留的日志条数与单条日志长度
+const MAX_LOG_ENTRIES: usize = 1000;
+const MAX_LOG_CHARS: usize = 4000;
+
+// 脚本前置代码：在原生函数之上构建 console、YAML 与 $utils
+const PRELUDE: &str = r#"
+var console = (function () {
+    function format(args) {
+        return Array.prototype.map.call(args, function (value) {
+            if (typeof value === 'string') return value;
+            try {
+                var text = JSON.stringify(value);
+                return text === undefined ? String(value) : text;
+            } catch (e) {
+                return String(value);
+            }
+        }).join(' ');
+    }
+    function writer(level) {
+        return function () { __hostLog(level, format(arguments)); };
+    }
+    return {
+        log: writer('log'),
+        info: writer('info'),
+        debug: writer('debug'),
+        warn: writer('warn'),
+        error: writer('error'),
+    };
+})();
+
+var YAML = {
+    parse: function (text) { return __hostYamlParse(String(text)); },
+    stringify: function (value) { return __hostYamlStringify(value); },
+};
+
+var $utils = (function () {
+    function toRegExp(pattern, flags) {
+        return pattern instanceof RegExp ? pattern : new RegExp(pattern, flags === undefined ? 'i' : flags);
+    }
+    function proxies(config) {
+        if (!Array.isArray(config.proxies)) config.proxies = [];
+        return config.proxies;
+    }
+    function groups(config) {
+        if (!Array.isArray(config['proxy-groups'])) config['proxy-groups'] = [];
+        return config['proxy-groups'];
+    }
+    function rules(config) {
+        if (!Array.isArray(config.rules)) config.rules = [];
+        return config.rules;
+    }
+    function isFinalRule(rule) {
+        return typeof rule === 'string' && /^\s*(MATCH|FINAL)\s*,/i.test(rule);
+    }
+
+    return {
+        toRegExp: toRegExp,
+        // 名称匹配正则的节点
+        filterProxies: function (config, pattern, flags) {
+            var regex = toRegExp(pattern, flags);
+            return proxies(config).filter(function (p) { return regex.test(p.name); });
+        },
+        // 节点名称列表，省略 pattern 时返回全部
+        proxyNames: function (config, pattern, flags) {
+            var list = pattern === undefined ? proxies(config) : this.filterProxies(config, pattern, flags);
+            return list.map(function (p) { return p.name; });
+        },
+        // 删除名称匹配的节点，并从代理组中移除引用，返回被删除的名称
+        removeProxies: function (config, pattern, flags) {
+            var regex = toRegExp(pattern, flags);
+            var removed = [];
+            config.proxies = proxies(config).filter(function (p) {
+                if (regex.test(p.name)) { removed.push(p.name); return false; }
+                return true;
+            });
+            groups(config).forEach(function (g) {
+                if (Array.isArray(g.proxies)) {
+                    g.proxies = g.proxies.filter(function (name) { return removed.indexOf(name) < 0; });
+                }
+            });
+            return removed;
+        },
+        getGroup: function (config, name) {
+            return groups(config).find(function (g) { return g.name === name; });
+        },
+        // 替换同名代理组，不存在时插入到 index（默认末尾）
+        upsertGroup: function (config, group, index) {
+            var list = groups(config);
+            var existing = list.findIndex(function (g) { return g.name === group.name; });
+            if (existing >= 0) list[existing] = group;
+            else if (index === undefined || index >= list.length) list.push(group);
+            else list.splice(Math.max(index, 0), 0, group);
+            return group;
+        },
+        // 向代理组追加成员（跳过已存在的名称）
+        addProxiesToGroup: function (config, groupName, names) {
+            var group = this.getGroup(config, groupName);
+            if (!group) throw new Error('代理组不存在：' + groupName);
+            if (!Array.isArray(group.proxies)) group.proxies = [];
+            [].concat(names).forEach(function (name) {
+                if (group.proxies.indexOf(name) < 0) group.proxies.push(name);
+            });
+            return group;
+        },
+        prependRules: function (config, newRules) {
+            config.rules = [].concat(newRules, rules(config));
+            return config.rules;
+        },
+        // 追加规则，保持 MATCH/FINAL 规则在最后
+        appendRules: function (config, newRules) {
+            var list = rules(config);
+            var finalIndex = list.findIndex(isFinalRule);
+            if (finalIndex < 0) list.push.apply(list, [].concat(newRules));
+            else list.splice.apply(list, [finalIndex, 0].concat(newRules));
+            return list;
+        },
+    };
+})();
+"#;
+
+thread_local! {
+    // 当前线程中脚本输出的日志（脚本在独立线程中执行，每次执行互不影响）
+    static SCRIPT_LOGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+// 脚本宿主接口
+pub struct JsHost;
+
+impl JsHost {
+    // 在上下文中安装宿主接口（每个上下文一次）
+    pub fn install(context: &mut Context) -> JsResult<()> {
+        context.register_global_builtin_callable(
+            js_string!("__hostLog"),
+            2,
+            NativeFunction::from_fn_ptr(Self::host_log),
+        )?;
+        context.register_global_builtin_callable(
+            js_string!("__hostYamlParse"),
+            1,
+            NativeFunction::from_fn_ptr(Self::host_yaml_parse),
+        )?;
+        context.register_global_builtin_callable(
+            js_string!("__hostYamlStringify"),
+            1,
+            NativeFunction::from_fn_ptr(Self::host_yaml_stringify),
+        )?;
+
+        context.register_global_property(
+            js_string!("$arguments"),
+            JsValue::undefined(),
+            Attribute::all(),
+        )?;
+
+        context.eval(Source::from_bytes(PRELUDE))?;
+        Ok(())
+    }
+
+    // 注入本次执行的脚本参数 $arguments，返回同一个参数对象
+    pub fn set_arguments(context: &mut Context, arguments: &JsonValue) -> JsResult<JsValue> {
+        let arguments = JsValue::from_json(arguments, context)?;
+        context
+            .global_object()
+            .set(js_string!("$arguments"), arguments.clone(), true, context)?;
+        Ok(arguments)
+    }
+
+    // 取出当前线程记录的脚本日志
+    pub fn take_logs() -> Vec<String> {
+        SCRIPT_LOGS.with(|logs| std::mem::take(&mut *logs.borrow_mut()))
+    }
+
+    // 解析脚本参数：未提供时为空对象，提供时必须是 JSON 对象
+    pub fn parse_arguments(arguments: Option<&str>) -> Result<JsonValue, String> {
+        match arguments.map(str::trim).filter(|a| !a.is_empty()) {
+            None => Ok(JsonValue::Object(Default::default())),
+            Some(text) => match serde_json::from_str::<JsonValue>(text) {
+                Ok(value) if value.is_object() => Ok(value),
+                Ok(_) => Err("脚本参数必须是 JSON 对象".to_string()),
+                Err(e) => Err(format!("脚本参数不是有效的 JSON：{}", e)),
+            },
+        }
+    }
+
+    // __hostLog(level, message)
+    fn host_log(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let level = args.get_or_undefined(0).to_string(context)?;
+        let message = args.get_or_undefined(1).to_string(context)?;
+        let mut message = message.to_std_string_escaped();
+        if message.chars().count() > MAX_LOG_CHARS {
+            message = message.chars().take(MAX_LOG_CHARS).collect::<String>() + "…";
+        }
+
+        SCRIPT_LOGS.with(|logs| {
+            let mut logs = logs.borrow_mut();
+            if logs.len() < MAX_LOG_ENTRIES {
+                logs.push(format!("[{}] {}", level.to_std_string_escaped(), message));
+            } else if logs.len() == MAX_LOG_ENTRIES {
+                logs.push("[warn] 日志过多，后续输出已省略".to_string());
+            }
+        });
+        Ok(JsValue::undefined())
+    }
+
+    // __hostYamlParse(text)
+    fn host_yaml_parse(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+        let text = args
+            .get_or_undefined(0)
+            .to_string(context)?
+            .to_std_string_escaped();
+        let yaml: YamlValue = serde_yaml_ng::from_str(&text)
+            .map_err(|e| JsNativeError::syntax().with_message(format!("YAML 解析失败：{}", e)))?;
+        let json = serde_json::to_value(yaml)
+            .map_err(|e| JsNativeError::typ().with_message(format!("YAML 转换失败：{}", e)))?;
+        JsValue::from_json(&json, context)
+    }
+
+    // __hostYamlStringify(value)
+    fn host_yaml_stringify(
+        _: &JsValue,
+        args: &[JsValue],
+        context: &mut Context,
+    ) -> JsResult<JsValue> {
+        let json = args
+            .get_or_undefined(0)
+            .to_json(context)?
+            .unwrap_or(JsonValue::Null);
+        let text = serde_yaml_ng::to_string(&json)
+            .map_err(|e| JsNativeError::typ().with_message(format!("YAML 序列化失败：{}", e)))?;
+        Ok(JsString::from(text.as_str()).into())
+    }
+}
@@ -0,0 +1,323 @@
+// JavaScript 脚本运行时：常驻线程执行覆写脚本，每次执行使用新的 Boa 上下文，脚本的全局变量不会在多次执行间保留。
+// Boa 解析的脚本绑定所属上下文，无法跨上下文复用，因此按内容哈希缓存的是已解析、尚未执行的上下文，
+// 执行后立即在空闲时为同一脚本准备下一个，使重复执行不必等待解析。
+
+use super::js_executor::JsExecutionLimits;
+use super::js_host::JsHost;
+use super::js_value::JsonBridge;
+use boa_engine::{Context, JsError, JsValue, Script, Source, js_string};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::time::Duration;
+
+// 运行时线程的栈大小
+const SCRIPT_THREAD_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+// 每个运行时线程最多缓存的脚本数量
+const MAX_CACHED_SCRIPTS: usize = 16;
+
+// 同时存在的超时运行时上限：单个失控脚本不影响其他脚本执行，持续超时达到上限时拒绝新的执行
+const MAX_ABANDONED_RUNTIMES: usize = 4;
+
+// 空闲的运行时（执行期间被取出；超时的运行时不再放回，执行结束后线程自行退出）
+static IDLE_RUNTIME: Mutex<Option<ScriptRuntime>> = Mutex::new(None);
+
+// 已超时但线程仍在执行的运行时数量，线程结束时通知等待者
+static ABANDONED_RUNTIMES: Mutex<usize> = Mutex::new(0);
+static ABANDONED_RUNTIME_EXITED: Condvar = Condvar::new();
+
+// 运行时线程状态
+const RUNTIME_RUNNING: u8 = 0;
+const RUNTIME_ABANDONED: u8 = 1;
+const RUNTIME_EXITED: u8 = 2;
+
+// 一次脚本执行请求
+pub struct ScriptJob {
+    pub code: String,
+    pub config: JsonValue,
+    pub arguments: JsonValue,
+    pub limits: JsExecutionLimits,
+    pub reply: Sender<ScriptOutcome>,
+}
+
+// 脚本执行结果与 console 输出
+pub type ScriptOutcome = (Result<JsonValue, String>, Vec<String>);
+
+// 脚本运行时线程的句柄
+pub struct ScriptRuntime {
+    sender: Sender<ScriptJob>,
+    state: Arc<AtomicU8>,
+}
+
+impl ScriptRuntime {
+    // 取出空闲的运行时，没有时创建新线程。
+    // 超时仍在运行的线程达到上限时最多等待 wait，仍未结束则拒绝执行，避免失控脚本不断累积线程。
+    pub fn acquire(wait: Duration) -> Result<Self, String> {
+        let abandoned = ABANDONED_RUNTIMES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let (abandoned, _) = ABANDONED_RUNTIME_EXITED
+            .wait_timeout_while(abandoned, wait, |count| *count >= MAX_ABANDONED_RUNTIMES)
+            .unwrap_or_else(PoisonError::into_inner);
+        if *abandoned >= MAX_ABANDONED_RUNTIMES {
+            return Err("多个超时的 JavaScript 覆写仍在运行，暂时无法执行新的脚本".to_string());
+        }
+        drop(abandoned);
+
+        let idle = IDLE_RUNTIME
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        match idle {
+            Some(runtime) => Ok(runtime),
+            None => Self::spawn(),
+        }
+    }
+
+    // 执行完成后放回，供后续执行复用线程
+    pub fn release(self) {
+        let mut idle = IDLE_RUNTIME.lock().unwrap_or_else(PoisonError::into_inner);
+        if idle.is_none() {
+            *idle = Some(self);
+        }
+    }
+
+    // 放弃超时的运行时，线程在当前脚本结束后退出并撤销计数
+    pub fn abandon(self) {
+        let mut abandoned = ABANDONED_RUNTIMES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let is_running = self
+            .state
+            .compare_exchange(
+                RUNTIME_RUNNING,
+                RUNTIME_ABANDONED,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .is_ok();
+        // 线程已先行退出时无需计数
+        if is_running {
+            *abandoned += 1;
+        }
+    }
+
+    // 提交执行请求，运行时线程已退出时返回请求以便重试
+    pub fn submit(&self, job: ScriptJob) -> Result<(), Box<ScriptJob>> {
+        self.sender.send(job).map_err(|e| Box::new(e.0))
+    }
+
+    // 创建运行时线程
+    pub fn spawn() -> Result<Self, String> {
+        let (sender, receiver) = mpsc::channel::<ScriptJob>();
+        let state = Arc::new(AtomicU8::new(RUNTIME_RUNNING));
+        let guard = AbandonGuard(state.clone());
+        std::thread::Builder::new()
+            .name("js-override".to_string())
+            .stack_size(SCRIPT_THREAD_STACK_SIZE)
+            .spawn(move || Self::run(receiver, guard))
+            .map_err(|e| format!("创建 JavaScript 执行线程失败：{}", e))?;
+        Ok(Self { sender, state })
+    }
+
+    // 运行时线程主循环：所有句柄释放后退出
+    fn run(receiver: Receiver<ScriptJob>, _guard: AbandonGuard) {
+        let mut cache = ScriptCache::default();
+        while let Ok(job) = receiver.recv() {
+            let result = cache.execute(&job);
+            let _ = job.reply.send((result, JsHost::take_logs()));
+            cache.refill(&job.code);
+        }
+    }
+}
+
+// 运行时线程退出（含异常退出）时撤销超时计数
+struct AbandonGuard(Arc<AtomicU8>);
+
+impl Drop for AbandonGuard {
+    fn drop(&mut self) {
+        let mut abandoned = ABANDONED_RUNTIMES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if self.0.swap(RUNTIME_EXITED, Ordering::SeqCst) == RUNTIME_ABANDONED {
+            *abandoned = abandoned.saturating_sub(1);
+            ABANDONED_RUNTIME_EXITED.notify_all();
+            log::info!("超时的 JavaScript 执行线程已结束");
+        }
+    }
+}
+
+// 已解析、尚未执行的脚本：独立上下文（已安装宿主接口）与其中编译好的脚本
+struct PreparedScript {
+    context: Context,
+    script: Script,
+}
+
+// 按脚本内容哈希缓存预先解析的脚本（最近最少使用淘汰）。
+// 每个缓存项只执行一次，执行后丢弃其上下文，并在回复结果后为同一脚本准备新的上下文。
+#[derive(Default)]
+struct ScriptCache {
+    scripts: HashMap<[u8; 32], PreparedScript>,
+    order: VecDeque<[u8; 32]>, // 由旧到新
+}
+
+impl ScriptCache {
+    // 取出预先解析的脚本执行，未命中时当场解析
+    fn execute(&mut self, job: &ScriptJob) -> Result<JsonValue, String> {
+        let key = Self::key(&job.code);
+        self.order.retain(|k| *k != key);
+
+        let prepared = match self.scripts.remove(&key) {
+            Some(prepared) => {
+                log::info!("✓ 使用预先解析的覆写脚本");
+                prepared
+            }
+            None => Self::prepare(&job.code)?,
+        };
+        Self::run(prepared, job)
+    }
+
+    // 为刚执行过的脚本准备新的上下文，供下一次执行使用；解析失败的脚本不缓存
+    fn refill(&mut self, code: &str) {
+        let key = Self::key(code);
+        if self.scripts.contains_key(&key) {
+            return;
+        }
+        let Ok(prepared) = Self::prepare(code) else {
+            return;
+        };
+
+        self.scripts.insert(key, prepared);
+        self.order.push_back(key);
+        while self.order.len() > MAX_CACHED_SCRIPTS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.scripts.remove(&oldest);
+            }
+        }
+    }
+
+    fn key(code: &str) -> [u8; 32] {
+        Sha256::digest(code.as_bytes()).into()
+    }
+
+    // 在新上下文中安装宿主接口并解析、编译脚本，不执行顶层代码
+    fn prepare(code: &str) -> Result<PreparedScript, String> {
+        let mut context = Context::default();
+        JsHost::install(&mut context).map_err(|e| format!("初始化脚本宿主接口失败：{}", e))?;
+
+        let script = Script::parse(Source::from_bytes(code), None, &mut context)
+            .and_then(|script| script.codeblock(&mut context).map(|_| script))
+            .map_err(|e| format!("JavaScript 解析失败：{}", e))?;
+        Ok(PreparedScript { context, script })
+    }
+
+    // 执行脚本顶层代码，再注入配置与参数调用 main(config, $arguments)
+    fn run(prepared: PreparedScript, job: &ScriptJob) -> Result<JsonValue, String> {
+        let PreparedScript {
+            mut context,
+            script,
+        } = prepared;
+        let limits = &job.limits;
+        Self::apply_limits(&mut context, limits);
+
+        script
+            .evaluate(&mut context)
+            .map_err(|e| Self::describe_error(&e, limits))?;
+
+        let main = context
+            .global_object()
+            .get(js_string!("main"), &mut context)
+            .ok()
+            .and_then(|main| main.as_callable())
+            .ok_or("覆写脚本必须定义 main(config) 函数")?;
+
+        let arguments = JsHost::set_arguments(&mut context, &job.arguments)
+            .map_err(|e| format!("注入脚本参数失败：{}", e))?;
+        let config = JsonBridge::to_js(&job.config, &mut context)?;
+
+        let result = main
+            .call(&JsValue::undefined(), &[config, arguments], &mut context)
+            .map_err(|e| Self::describe_error(&e, limits))?;
+
+        match JsonBridge::to_json(&result, &mut context, limits.max_output_bytes)? {
+            Some(config) if config.is_object() => Ok(config),
+            _ => Err("覆写脚本的 main 函数必须返回配置对象".to_string()),
+        }
+    }
+
+    fn apply_limits(context: &mut Context, limits: &JsExecutionLimits) {
+        let runtime_limits = context.runtime_limits_mut();
+        runtime_limits.set_loop_iteration_limit(limits.loop_iteration_limit);
+        runtime_limits.set_recursion_limit(limits.recursion_limit);
+    }
+
+    // 将执行错误转换为说明文字，触发运行时限制时指明具体限制
+    fn describe_error(error: &JsError, limits: &JsExecutionLimits) -> String {
+        let message = match error.as_native().filter(|n| n.is_runtime_limit()) {
+            None => format!("JavaScript 执行失败：{}", error),
+            Some(native) => {
+                let message = native.message();
+                if message.contains("loop iteration") {
+                    format!(
+                        "JavaScript 超过循环迭代次数上限（{}）",
+                        limits.loop_iteration_limit
+                    )
+                } else if message.contains("recursive calls") {
+                    format!("JavaScript 超过递归深度上限（{}）", limits.recursion_limit)
+                } else if message.contains("call stack") {
+                    "JavaScript 超过调用栈大小上限".to_string()
+                } else {
+                    format!("JavaScript 超过运行时限制：{}", message)
+                }
+            }
+        };
+        log::error!("✗ {}", message);
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runs_are_isolated() -> Result<(), String> {
+        let (reply, _receiver) = mpsc::channel();
+        let mut job = ScriptJob {
+            code: "var runs = 0; function main(c) { leaked = true; c.runs = ++runs; return c; }"
+                .to_string(),
+            config: serde_json::json!({}),
+            arguments: serde_json::json!({}),
+            limits: JsExecutionLimits::default(),
+            reply,
+        };
+        let mut cache = ScriptCache::default();
+
+        // 同一脚本再次执行使用预先解析的新上下文，全局状态不会保留
+        assert_eq!(cache.execute(&job)?["runs"], 1);
+        cache.refill(&job.code);
+        assert_eq!(cache.scripts.len(), 1);
+        assert_eq!(cache.execute(&job)?["runs"], 1);
+        assert!(cache.scripts.is_empty());
+
+        // 其他脚本看不到之前脚本留下的全局变量
+        job.code = "function main(c) { c.leaked = typeof leaked !== 'undefined' || typeof runs !== 'undefined'; return c; }"
+            .to_string();
+        assert_eq!(cache.execute(&job)?["leaked"], false);
+
+        // 执行失败不影响之后的执行，解析失败的脚本不缓存
+        job.code = "function main(c) { throw new Error('boom'); }".to_string();
+        assert!(cache.execute(&job).is_err());
+        job.code = "function main(c) {".to_string();
+        assert!(cache.execute(&job).is_err());
+        cache.refill(&job.code);
+        assert!(cache.scripts.is_empty());
+        job.code = "function main(c) { c.ok = true; return c; }".to_string();
+        assert_eq!(cache.execute(&job)?["ok"], true);
+        Ok(())
+    }
+}
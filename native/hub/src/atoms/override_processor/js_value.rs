@@ -0,0 +1,186 @@
+// JSON 与 Boa 值的直接转换：配置不再经过 JSON 文本往返。
+// 返回值转换遵循 JSON.stringify 语义（忽略函数与 undefined、调用 toJSON、整数保持整数）。
+
+use boa_engine::property::PropertyKey;
+use boa_engine::{Context, JsError, JsObject, JsValue, JsVariant, js_string};
+use serde_json::{Map, Number, Value as JsonValue};
+use std::collections::HashSet;
+
+// 返回值允许的最大嵌套深度
+const MAX_DEPTH: usize = 512;
+
+// 可精确表示为整数的浮点数上限（2^53）
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+// JSON 与 JavaScript 值转换器
+pub struct JsonBridge<'a> {
+    context: &'a mut Context,
+    object_keys: JsObject, // Object.keys，用于读取可枚举的自有属性
+    seen: HashSet<JsObject>,
+    budget: usize, // 剩余可输出的字节数（按字符串与数字长度估算）
+    max_bytes: usize,
+}
+
+impl<'a> JsonBridge<'a> {
+    // 将 JSON 值转换为 JavaScript 值
+    pub fn to_js(value: &JsonValue, context: &mut Context) -> Result<JsValue, String> {
+        JsValue::from_json(value, context).map_err(|e| format!("转换配置失败：{}", e))
+    }
+
+    // 将 JavaScript 值转换为 JSON 值，输出估算超过 max_bytes 时失败。
+    // 值本身无法序列化（如 undefined 或函数）时返回 None。
+    pub fn to_json(
+        value: &JsValue,
+        context: &'a mut Context,
+        max_bytes: usize,
+    ) -> Result<Option<JsonValue>, String> {
+        let object_keys = context
+            .intrinsics()
+            .constructors()
+            .object()
+            .constructor()
+            .get(js_string!("keys"), context)
+            .ok()
+            .and_then(|keys| keys.as_callable())
+            .ok_or("读取脚本结果失败：Object.keys 不可用")?;
+
+        let mut bridge = Self {
+            context,
+            object_keys,
+            seen: HashSet::new(),
+            budget: max_bytes,
+            max_bytes,
+        };
+        bridge.convert(value, 0)
+    }
+
+    fn convert(&mut self, value: &JsValue, depth: usize) -> Result<Option<JsonValue>, String> {
+        let json = match value.variant() {
+            JsVariant::Undefined | JsVariant::Symbol(_) => return Ok(None),
+            JsVariant::Null => JsonValue::Null,
+            JsVariant::Boolean(b) => JsonValue::Bool(b),
+            JsVariant::Integer32(i) => {
+                self.consume(11)?;
+                JsonValue::from(i)
+            }
+            JsVariant::Float64(f) => {
+                self.consume(24)?;
+                Self::number(f)
+            }
+            JsVariant::String(s) => {
+                let s = s.to_std_string_escaped();
+                self.consume(s.len() + 2)?;
+                JsonValue::String(s)
+            }
+            JsVariant::BigInt(_) => {
+                return Err("读取脚本结果失败：BigInt 无法转换为配置值".to_string());
+            }
+            JsVariant::Object(object) => return self.convert_object(object, depth),
+        };
+        Ok(Some(json))
+    }
+
+    fn convert_object(
+        &mut self,
+        object: JsObject,
+        depth: usize,
+    ) -> Result<Option<JsonValue>, String> {
+        if object.is_callable() {
+            return Ok(None);
+        }
+        if depth >= MAX_DEPTH {
+            return Err(format!("读取脚本结果失败：嵌套深度超过 {} 层", MAX_DEPTH));
+        }
+
+        // 与 JSON.stringify 一致：优先使用 toJSON（如 Date）
+        let to_json = self.get(&object, js_string!("toJSON").into())?;
+        if let Some(to_json) = to_json.as_callable() {
+            let value = to_json
+                .call(&object.clone().into(), &[], self.context)
+                .map_err(Self::read_error)?;
+            return self.convert(&value, depth + 1);
+        }
+
+        if !self.seen.insert(object.clone()) {
+            return Err("读取脚本结果失败：配置对象存在循环引用".to_string());
+        }
+
+        let json = if object.is_array() {
+            let length = self
+                .get(&object, js_string!("length").into())?
+                .to_length(self.context)
+                .map_err(Self::read_error)?;
+            let mut array = Vec::with_capacity(length.min(1 << 16) as usize);
+            for index in 0..length {
+                self.consume(1)?;
+                let item = self.get(&object, index.into())?;
+                array.push(self.convert(&item, depth + 1)?.unwrap_or(JsonValue::Null));
+            }
+            JsonValue::Array(array)
+        } else {
+            let keys = self
+                .object_keys
+                .call(
+                    &JsValue::undefined(),
+                    &[object.clone().into()],
+                    self.context,
+                )
+                .map_err(Self::read_error)?;
+            let keys = keys
+                .as_object()
+                .ok_or("读取脚本结果失败：无法枚举对象属性")?;
+            let count = self
+                .get(&keys, js_string!("length").into())?
+                .to_length(self.context)
+                .map_err(Self::read_error)?;
+
+            let mut map = Map::new();
+            for index in 0..count {
+                let key = self
+                    .get(&keys, index.into())?
+                    .to_string(self.context)
+                    .map_err(Self::read_error)?;
+                let value = self.get(&object, key.clone().into())?;
+                if let Some(value) = self.convert(&value, depth + 1)? {
+                    let key = key.to_std_string_escaped();
+                    self.consume(key.len() + 4)?;
+                    map.insert(key, value);
+                }
+            }
+            JsonValue::Object(map)
+        };
+
+        self.seen.remove(&object);
+        Ok(Some(json))
+    }
+
+    fn get(&mut self, object: &JsObject, key: PropertyKey) -> Result<JsValue, String> {
+        object.get(key, self.context).map_err(Self::read_error)
+    }
+
+    // 整数值的浮点数输出为整数，NaN 与无穷大按 JSON.stringify 输出 null
+    fn number(value: f64) -> JsonValue {
+        if value.fract() == 0.0 && value.abs() < MAX_SAFE_INTEGER {
+            JsonValue::from(value as i64)
+        } else {
+            Number::from_f64(value).map_or(JsonValue::Null, JsonValue::Number)
+        }
+    }
+
+    fn consume(&mut self, bytes: usize) -> Result<(), String> {
+        match self.budget.checked_sub(bytes) {
+            Some(rest) => {
+                self.budget = rest;
+                Ok(())
+            }
+            None => Err(format!(
+                "JavaScript 输出超过大小上限（{} 字节）",
+                self.max_bytes
+            )),
+        }
+    }
+
+    fn read_error(error: JsError) -> String {
+        format!("读取脚本结果失败：{}", error)
+    }
+}
@@ -0,0 +1,400 @@
+// JSON Patch（RFC 6902）与 JSON Merge Patch（RFC 7386）覆写。
+// 补丁内容可为 JSON 或等价的 YAML；在保持键顺序的 YAML 值上操作。
+
+use serde_yaml_ng::{Mapping, Value as YamlValue};
+
+// JSON Patch 应用失败的原因
+#[derive(Debug)]
+pub enum PatchError {
+    PreconditionFailed(String), // test 操作不满足，整个覆写放弃
+    Invalid(String),            // 补丁格式错误或路径无效
+}
+
+// 一条 JSON Patch 操作
+enum PatchOperation {
+    Add(Vec<String>, YamlValue),
+    Remove(Vec<String>),
+    Replace(Vec<String>, YamlValue),
+    Move(Vec<String>, Vec<String>), // (from, path)
+    Copy(Vec<String>, Vec<String>), // (from, path)
+    Test(Vec<String>, YamlValue),
+}
+
+// JSON Patch / Merge Patch 应用器
+pub struct JsonPatcher;
+
+impl Default for JsonPatcher {
+    fn default() -> Self {
+        Self
+    }
+}
+
+impl JsonPatcher {
+    // 创建新的补丁应用器
+    pub fn new() -> Self {
+        Self
+    }
+
+    // 应用 JSON Patch：所有操作依次执行，任一操作失败则整个补丁不生效。
+    pub fn apply_json_patch(
+        &self,
+        base_content: &str,
+        patch_content: &str,
+    ) -> Result<String, PatchError> {
+        let operations = Self::parse_json_patch(patch_content).map_err(PatchError::Invalid)?;
+        let mut config = Self::parse_base(base_content).map_err(PatchError::Invalid)?;
+
+        for (i, operation) in operations.into_iter().enumerate() {
+            Self::apply_operation(&mut config, operation).map_err(|e| match e {
+                PatchError::PreconditionFailed(e) => {
+                    PatchError::PreconditionFailed(format!("第 {} 个操作：{}", i + 1, e))
+                }
+                PatchError::Invalid(e) => {
+                    PatchError::Invalid(format!("第 {} 个操作：{}", i + 1, e))
+                }
+            })?;
+        }
+
+        Self::serialize(&config).map_err(PatchError::Invalid)
+    }
+
+    // 应用 JSON Merge Patch：对象递归合并，null 表示删除，其余值直接替换。
+    pub fn apply_merge_patch(
+        &self,
+        base_content: &str,
+        patch_content: &str,
+    ) -> Result<String, String> {
+        let patch = Self::parse_merge_patch(patch_content)?;
+        let mut config = Self::parse_base(base_content)?;
+        Self::merge_patch(&mut config, patch);
+        Self::serialize(&config)
+    }
+
+    // 校验 JSON Patch 内容（用于下载时检查）
+    pub fn validate_json_patch(patch_content: &str) -> Result<(), String> {
+        Self::parse_json_patch(patch_content).map(|_| ())
+    }
+
+    // 校验 JSON Merge Patch 内容（用于下载时检查）
+    pub fn validate_merge_patch(patch_content: &str) -> Result<(), String> {
+        Self::parse_merge_patch(patch_content).map(|_| ())
+    }
+
+    fn parse_base(base_content: &str) -> Result<YamlValue, String> {
+        serde_yaml_ng::from_str(base_content).map_err(|e| format!("解析基础配置失败：{}", e))
+    }
+
+    fn serialize(config: &YamlValue) -> Result<String, String> {
+        serde_yaml_ng::to_string(config).map_err(|e| format!("序列化配置失败：{}", e))
+    }
+
+    // Merge Patch 必须是对象（RFC 7386 允许其他值替换整个文档，但配置根节点必须是对象）
+    fn parse_merge_patch(patch_content: &str) -> Result<YamlValue, String> {
+        let patch: YamlValue = serde_yaml_ng::from_str(patch_content)
+            .map_err(|e| format!("解析 Merge Patch 失败：{}", e))?;
+        if !patch.is_mapping() {
+            return Err("Merge Patch 必须是对象".to_string());
+        }
+        Ok(patch)
+    }
+
+    fn parse_json_patch(patch_content: &str) -> Result<Vec<PatchOperation>, String> {
+        let patch: YamlValue = serde_yaml_ng::from_str(patch_content)
+            .map_err(|e| format!("解析 JSON Patch 失败：{}", e))?;
+        let YamlValue::Sequence(items) = patch else {
+            return Err("JSON Patch 必须是操作数组".to_string());
+        };
+
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| {
+                Self::parse_operation(item).map_err(|e| format!("第 {} 个操作：{}", i + 1, e))
+            })
+            .collect()
+    }
+
+    fn parse_operation(item: YamlValue) -> Result<PatchOperation, String> {
+        let YamlValue::Mapping(mut map) = item else {
+            return Err("操作必须是对象".to_string());
+        };
+        let op = map
+            .get("op")
+            .and_then(|op| op.as_str())
+            .ok_or("缺少 op 字段")?
+            .to_string();
+        let path = Self::parse_pointer(
+            map.get("path")
+                .and_then(|p| p.as_str())
+                .ok_or("缺少 path 字段")?,
+        )?;
+        let from = map
+            .get("from")
+            .and_then(|f| f.as_str())
+            .map(Self::parse_pointer)
+            .transpose()?;
+        // 值可以是 null，因此以字段是否存在判断
+        let value = map.shift_remove("value");
+
+        let operation = match (op.as_str(), from, value) {
+            ("add", _, Some(value)) => PatchOperation::Add(path, value),
+            ("remove", _, _) => PatchOperation::Remove(path),
+            ("replace", _, Some(value)) => PatchOperation::Replace(path, value),
+            ("move", Some(from), _) => {
+                if path.len() > from.len() && path.starts_with(&from) {
+                    return Err("move 不能将值移动到其自身的子节点".to_string());
+                }
+                PatchOperation::Move(from, path)
+            }
+            ("copy", Some(from), _) => PatchOperation::Copy(from, path),
+            ("test", _, Some(value)) => PatchOperation::Test(path, value),
+            ("add" | "replace" | "test", _, None) => {
+                return Err(format!("{} 操作缺少 value 字段", op));
+            }
+            ("move" | "copy", None, _) => return Err(format!("{} 操作缺少 from 字段", op)),
+            _ => return Err(format!("不支持的操作：{}", op)),
+        };
+        Ok(operation)
+    }
+
+    // 解析 JSON Pointer（RFC 6901），空字符串表示根节点
+    fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+        if pointer.is_empty() {
+            return Ok(Vec::new());
+        }
+        let Some(rest) = pointer.strip_prefix('/') else {
+            return Err(format!("无效的 JSON Pointer：{}", pointer));
+        };
+        Ok(rest
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect())
+    }
+
+    fn apply_operation(
+        config: &mut YamlValue,
+        operation: PatchOperation,
+    ) -> Result<(), PatchError> {
+        match operation {
+            PatchOperation::Add(path, value) => Self::add(config, &path, value),
+            PatchOperation::Remove(path) => Self::remove(config, &path).map(|_| ()),
+            PatchOperation::Replace(path, value) => {
+                *Self::resolve_mut(config, &path)? = value;
+                Ok(())
+            }
+            PatchOperation::Move(from, path) => {
+                let value = Self::remove(config, &from)?;
+                Self::add(config, &path, value)
+            }
+            PatchOperation::Copy(from, path) => {
+                let value = Self::resolve_mut(config, &from)?.clone();
+                Self::add(config, &path, value)
+            }
+            PatchOperation::Test(path, expected) => {
+                let actual = Self::resolve_mut(config, &path).map_err(|_| {
+                    PatchError::PreconditionFailed(format!("路径不存在：{}", Self::display(&path)))
+                })?;
+                if Self::values_equal(actual, &expected) {
+                    Ok(())
+                } else {
+                    Err(PatchError::PreconditionFailed(format!(
+                        "{} 的值与预期不符",
+                        Self::display(&path)
+                    )))
+                }
+            }
+        }
+    }
+
+    fn add(config: &mut YamlValue, path: &[String], value: YamlValue) -> Result<(), PatchError> {
+        let Some((last, parent_path)) = path.split_last() else {
+            *config = value;
+            return Ok(());
+        };
+
+        match Self::resolve_mut(config, parent_path)? {
+            YamlValue::Mapping(map) => {
+                map.insert(YamlValue::String(last.clone()), value);
+            }
+            YamlValue::Sequence(items) => {
+                let index = if last == "-" {
+                    items.len()
+                } else {
+                    Self::array_index(last, items.len() + 1, path)?
+                };
+                items.insert(index, value);
+            }
+            _ => return Err(Self::invalid_path(parent_path)),
+        }
+        Ok(())
+    }
+
+    fn remove(config: &mut YamlValue, path: &[String]) -> Result<YamlValue, PatchError> {
+        let Some((last, parent_path)) = path.split_last() else {
+            return Err(PatchError::Invalid("不能删除根节点".to_string()));
+        };
+
+        match Self::resolve_mut(config, parent_path)? {
+            YamlValue::Mapping(map) => map
+                .shift_remove(last.as_str())
+                .ok_or_else(|| Self::invalid_path(path)),
+            YamlValue::Sequence(items) => {
+                let index = Self::array_index(last, items.len(), path)?;
+                Ok(items.remove(index))
+            }
+            _ => Err(Self::invalid_path(parent_path)),
+        }
+    }
+
+    fn resolve_mut<'a>(
+        config: &'a mut YamlValue,
+        path: &[String],
+    ) -> Result<&'a mut YamlValue, PatchError> {
+        let mut current = config;
+        for (depth, token) in path.iter().enumerate() {
+            current = match current {
+                YamlValue::Mapping(map) => map.get_mut(token.as_str()),
+                YamlValue::Sequence(items) => {
+                    let index = Self::array_index(token, items.len(), &path[..=depth])?;
+                    items.get_mut(index)
+                }
+                _ => None,
+            }
+            .ok_or_else(|| Self::invalid_path(&path[..=depth]))?;
+        }
+        Ok(current)
+    }
+
+    // 解析数组下标：十进制、无前导零且小于 limit
+    fn array_index(token: &str, limit: usize, path: &[String]) -> Result<usize, PatchError> {
+        let is_canonical = !token.is_empty()
+            && token.bytes().all(|b| b.is_ascii_digit())
+            && (token == "0" || !token.starts_with('0'));
+        token
+            .parse::<usize>()
+            .ok()
+            .filter(|index| is_canonical && *index < limit)
+            .ok_or_else(|| Self::invalid_path(path))
+    }
+
+    // 比较两个值，数字按数值比较
+    fn values_equal(a: &YamlValue, b: &YamlValue) -> bool {
+        match (a, b) {
+            (YamlValue::Number(a), YamlValue::Number(b)) => a.as_f64() == b.as_f64(),
+            (YamlValue::Sequence(a), YamlValue::Sequence(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| Self::values_equal(a, b))
+            }
+            (YamlValue::Mapping(a), YamlValue::Mapping(b)) => {
+                a.len() == b.len()
+                    && a.iter().all(|(key, value)| {
+                        b.get(key)
+                            .is_some_and(|other| Self::values_equal(value, other))
+                    })
+            }
+            _ => a == b,
+        }
+    }
+
+    fn merge_patch(target: &mut YamlValue, patch: YamlValue) {
+        let YamlValue::Mapping(patch) = patch else {
+            *target = patch;
+            return;
+        };
+        if !target.is_mapping() {
+            *target = YamlValue::Mapping(Mapping::new());
+        }
+        if let YamlValue::Mapping(map) = target {
+            for (key, value) in patch {
+                if value.is_null() {
+                    map.shift_remove(&key);
+                } else {
+                    Self::merge_patch(map.entry(key).or_insert(YamlValue::Null), value);
+                }
+            }
+        }
+    }
+
+    fn invalid_path(path: &[String]) -> PatchError {
+        PatchError::Invalid(format!("路径不存在：{}", Self::display(path)))
+    }
+
+    // 还原 JSON Pointer 文本用于提示
+    fn display(path: &[String]) -> String {
+        path.iter()
+            .map(|token| format!("/{}", token.replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+mode: rule
+dns: {enable: true, ipv6: false}
+proxy-groups:
+  - {name: PROXY, type: select, proxies: [HK, JP]}
+rules: ["DOMAIN,a.com,PROXY", "MATCH,PROXY"]
+"#;
+
+    fn parse(content: &str) -> Result<YamlValue, String> {
+        serde_yaml_ng::from_str(content).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn test_json_patch() -> Result<(), String> {
+        let patcher = JsonPatcher::new();
+        let patch = r#"[
+            {"op": "test", "path": "/proxy-groups/0/name", "value": "PROXY"},
+            {"op": "add", "path": "/proxy-groups/0/proxies/-", "value": "US"},
+            {"op": "add", "path": "/rules/1", "value": "DOMAIN,b.com,DIRECT"},
+            {"op": "replace", "path": "/mode", "value": "global"},
+            {"op": "remove", "path": "/dns/ipv6"},
+            {"op": "copy", "from": "/dns", "path": "/dns-backup"},
+            {"op": "move", "from": "/dns-backup/enable", "path": "/a~1b"}
+        ]"#;
+
+        let result = parse(
+            &patcher
+                .apply_json_patch(BASE, patch)
+                .map_err(|e| format!("{:?}", e))?,
+        )?;
+        assert_eq!(result["mode"].as_str(), Some("global"));
+        assert_eq!(result["proxy-groups"][0]["proxies"][2].as_str(), Some("US"));
+        assert_eq!(result["rules"][1].as_str(), Some("DOMAIN,b.com,DIRECT"));
+        assert_eq!(result["rules"][2].as_str(), Some("MATCH,PROXY"));
+        assert!(result["dns"].get("ipv6").is_none());
+        assert_eq!(result["a/b"].as_bool(), Some(true));
+
+        let failed = patcher.apply_json_patch(
+            BASE,
+            r#"[{"op": "test", "path": "/mode", "value": "global"}, {"op": "remove", "path": "/dns"}]"#,
+        );
+        assert!(matches!(failed, Err(PatchError::PreconditionFailed(_))));
+
+        let invalid = patcher.apply_json_patch(BASE, r#"[{"op": "remove", "path": "/rules/05"}]"#);
+        assert!(matches!(invalid, Err(PatchError::Invalid(_))));
+        assert!(JsonPatcher::validate_json_patch(r#"[{"op": "add", "path": "/x"}]"#).is_err());
+        assert!(JsonPatcher::validate_json_patch(r#"{"op": "add"}"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_patch() -> Result<(), String> {
+        let patcher = JsonPatcher::new();
+        let patch = r#"{"mode": "direct", "dns": {"ipv6": null, "listen": ":53"}, "rules": ["MATCH,DIRECT"]}"#;
+
+        let merged = patcher.apply_merge_patch(BASE, patch)?;
+        let result = parse(&merged)?;
+        assert_eq!(result["mode"].as_str(), Some("direct"));
+        assert_eq!(result["dns"]["listen"].as_str(), Some(":53"));
+        assert!(result["dns"].get("ipv6").is_none());
+        assert_eq!(result["rules"].as_sequence().map(|r| r.len()), Some(1));
+        // 键顺序保持不变
+        assert!(merged.starts_with("mode: direct\ndns:"));
+
+        assert!(JsonPatcher::validate_merge_patch("[1]").is_err());
+        Ok(())
+    }
+}
@@ -1,14 +1,31 @@
-// 覆写处理器：组合 YAML 合并与 JavaScript 执行能力。
+// 覆写处理器：组合 YAML 合并、JSON Patch 与 JavaScript 执行能力。
 // 提供统一的覆写应用流程。
 
-use super::js_executor::JsExecutor;
+use super::conditions::ConditionMatcher;
+use super::js_executor::{JsExecutionLimits, JsExecutor};
+use super::json_patch::{JsonPatcher, PatchError};
+use super::trace::ConfigSnapshot;
 use super::yaml_merger::YamlMerger;
-use crate::atoms::shared_types::{OverrideConfig, OverrideFormat};
+use crate::atoms::shared_types::{
+    OverrideConfig, OverrideFormat, OverrideMatchContext, OverrideTraceStep, SkippedOverride,
+};
+use std::time::Instant;
+
+// 单个覆写的处理结果
+enum OverrideOutcome {
+    Applied(String), // 覆写后的配置
+    Skipped(String), // 未生效的原因
+}
 
 // 覆写处理器
 pub struct OverrideProcessor {
     yaml_merger: YamlMerger,
+    json_patcher: JsonPatcher,
     js_executor: JsExecutor,
+    logs: Vec<String>, // 脚本的 console 输出与跳过原因，带覆写名称前缀
+    trace: Option<Vec<OverrideTraceStep>>, // 开启追踪时的逐个覆写记录
+    match_context: OverrideMatchContext, // 评估覆写匹配条件的上下文
+    skipped: Vec<SkippedOverride>, // 条件不满足或前置条件失败而跳过的覆写
 }
 
 impl OverrideProcessor {
@@ -20,10 +37,53 @@ impl OverrideProcessor {
 
         Ok(Self {
             yaml_merger,
+            json_patcher: JsonPatcher::new(),
             js_executor,
+            logs: Vec::new(),
+            trace: None,
+            match_context: OverrideMatchContext::default(),
+            skipped: Vec::new(),
         })
     }
 
+    // 创建使用指定 JavaScript 执行限制的覆写处理器。
+    pub fn with_js_limits(limits: JsExecutionLimits) -> Self {
+        Self {
+            yaml_merger: YamlMerger::new(),
+            json_patcher: JsonPatcher::new(),
+            js_executor: JsExecutor::with_limits(limits),
+            logs: Vec::new(),
+            trace: None,
+            match_context: OverrideMatchContext::default(),
+            skipped: Vec::new(),
+        }
+    }
+
+    // 取出累计的脚本日志（覆写失败时同样可用）
+    pub fn take_logs(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.logs)
+    }
+
+    // 开启追踪：之后每次应用覆写都记录每个覆写的耗时与配置变化
+    pub fn enable_trace(&mut self) {
+        self.trace = Some(Vec::new());
+    }
+
+    // 取出追踪记录（覆写失败时同样可用），未开启追踪时为空
+    pub fn take_trace(&mut self) -> Vec<OverrideTraceStep> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    // 设置评估匹配条件的上下文，未设置时依赖订阅、核心版本或运行时开关的条件均不满足
+    pub fn set_match_context(&mut self, context: OverrideMatchContext) {
+        self.match_context = context;
+    }
+
+    // 取出被跳过的覆写及原因（覆写失败时同样可用）
+    pub fn take_skipped(&mut self) -> Vec<SkippedOverride> {
+        std::mem::take(&mut self.skipped)
+    }
+
     // 按顺序应用覆写并返回最终配置。
     pub fn apply_overrides(
         &mut self,
@@ -40,20 +100,111 @@ impl OverrideProcessor {
                 override_cfg.format
             );
 
-            current_config = match override_cfg.format {
-                OverrideFormat::Yaml => self
-                    .yaml_merger
-                    .apply(&current_config, &override_cfg.content)
-                    .map_err(|e| format!("YAML 覆写失败：{}", e))?,
-                OverrideFormat::Javascript => self
-                    .js_executor
-                    .apply(&current_config, &override_cfg.content)
-                    .map_err(|e| format!("JavaScript 覆写失败：{}", e))?,
-            };
-
-            log::info!("[{}] 覆写应用成功", i);
+            let before = self
+                .trace
+                .is_some()
+                .then(|| ConfigSnapshot::capture(&current_config));
+            let started = Instant::now();
+            let outcome = self.apply_override(&current_config, override_cfg);
+
+            if let (Some(trace), Some(before)) = (self.trace.as_mut(), before) {
+                let step = OverrideTraceStep {
+                    override_id: override_cfg.id.clone(),
+                    override_name: override_cfg.name.clone(),
+                    elapsed_micros: started.elapsed().as_micros() as u64,
+                    is_successful: outcome.is_ok(),
+                    is_skipped: matches!(outcome, Ok(OverrideOutcome::Skipped(_))),
+                    error_message: match &outcome {
+                        Ok(OverrideOutcome::Skipped(reason)) => Some(reason.clone()),
+                        Err(e) => Some(e.clone()),
+                        Ok(OverrideOutcome::Applied(_)) => None,
+                    },
+                    ..Default::default()
+                };
+                let after = match &outcome {
+                    Ok(OverrideOutcome::Applied(config)) => Some(ConfigSnapshot::capture(config)),
+                    _ => None,
+                };
+                trace.push(before.trace_step(after.as_ref(), step));
+            }
+
+            match outcome? {
+                OverrideOutcome::Applied(config) => {
+                    current_config = config;
+                    log::info!("[{}] 覆写应用成功", i);
+                }
+                OverrideOutcome::Skipped(reason) => {
+                    log::warn!("[{}] 覆写已跳过：{}", i, reason);
+                    self.logs
+                        .push(format!("[{}] 已跳过：{}", override_cfg.name, reason));
+                    self.skipped.push(SkippedOverride {
+                        override_id: override_cfg.id.clone(),
+                        override_name: override_cfg.name.clone(),
+                        reason,
+                    });
+                }
+            }
         }
 
         Ok(current_config)
     }
+
+    // 应用单个覆写
+    fn apply_override(
+        &mut self,
+        current_config: &str,
+        override_cfg: &OverrideConfig,
+    ) -> Result<OverrideOutcome, String> {
+        let matched = override_cfg
+            .conditions
+            .as_ref()
+            .map_or(Ok(()), |conditions| {
+                ConditionMatcher::check(conditions, &self.match_context)
+            });
+        if let Err(reason) = matched {
+            return Ok(OverrideOutcome::Skipped(format!("条件不满足：{}", reason)));
+        }
+
+        let config = match override_cfg.format {
+            OverrideFormat::Yaml => self
+                .yaml_merger
+                .apply(current_config, &override_cfg.content)
+                .map_err(|e| format!("YAML 覆写失败：{}", e))?,
+            OverrideFormat::JsonPatch => match self
+                .json_patcher
+                .apply_json_patch(current_config, &override_cfg.content)
+            {
+                Ok(config) => config,
+                // test 操作不满足时放弃本覆写，继续处理后续覆写
+                Err(PatchError::PreconditionFailed(reason)) => {
+                    return Ok(OverrideOutcome::Skipped(format!(
+                        "前置条件不满足：{}",
+                        reason
+                    )));
+                }
+                Err(PatchError::Invalid(e)) => {
+                    return Err(format!("JSON Patch 覆写失败：{}", e));
+                }
+            },
+            OverrideFormat::MergePatch => self
+                .json_patcher
+                .apply_merge_patch(current_config, &override_cfg.content)
+                .map_err(|e| format!("Merge Patch 覆写失败：{}", e))?,
+            OverrideFormat::Javascript => {
+                let result = self.js_executor.apply(
+                    current_config,
+                    &override_cfg.content,
+                    override_cfg.arguments.as_deref(),
+                );
+                self.logs.extend(
+                    self.js_executor
+                        .take_logs()
+                        .into_iter()
+                        .map(|line| format!("[{}] {}", override_cfg.name, line)),
+                );
+                result.map_err(|e| format!("JavaScript 覆写失败：{}", e))?
+            }
+        };
+        Ok(OverrideOutcome::Applied(config))
+    }
 }
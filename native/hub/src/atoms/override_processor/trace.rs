@@ -0,0 +1,141 @@
+// 覆写追踪：记录每个覆写前后配置的结构差异与节点、代理组、规则数量。
+// 仅在开启追踪时解析配置，不影响正常覆写流程的性能。
+
+use crate::atoms::shared_types::{ConfigCounts, OverrideTraceStep};
+use serde_yaml_ng::Value as YamlValue;
+
+// 每类差异最多记录的键路径数量
+const MAX_TRACE_KEYS: usize = 200;
+
+// 配置快照
+pub struct ConfigSnapshot {
+    value: YamlValue, // 解析失败时为 Null
+}
+
+impl ConfigSnapshot {
+    // 解析配置内容生成快照
+    pub fn capture(config: &str) -> Self {
+        Self {
+            value: serde_yaml_ng::from_str(config).unwrap_or(YamlValue::Null),
+        }
+    }
+
+    // 节点、代理组与规则数量
+    pub fn counts(&self) -> ConfigCounts {
+        let count = |key: &str| {
+            self.value
+                .get(key)
+                .and_then(|v| v.as_sequence())
+                .map_or(0, |items| items.len() as u32)
+        };
+        ConfigCounts {
+            proxy_count: count("proxies"),
+            group_count: count("proxy-groups"),
+            rule_count: count("rules"),
+        }
+    }
+
+    // 生成追踪记录：after 为 None 表示覆写未生效（失败或跳过）
+    pub fn trace_step(
+        &self,
+        after: Option<&ConfigSnapshot>,
+        mut step: OverrideTraceStep,
+    ) -> OverrideTraceStep {
+        step.counts_before = self.counts();
+        match after {
+            Some(after) => {
+                step.counts_after = after.counts();
+                Self::diff(&self.value, &after.value, &mut String::new(), &mut step);
+                for keys in [
+                    &mut step.added_keys,
+                    &mut step.removed_keys,
+                    &mut step.changed_keys,
+                ] {
+                    Self::truncate(keys);
+                }
+            }
+            None => step.counts_after = step.counts_before.clone(),
+        }
+        step
+    }
+
+    // 递归比较映射，数组与标量整体比较；键路径以“.”连接
+    fn diff(
+        before: &YamlValue,
+        after: &YamlValue,
+        path: &mut String,
+        step: &mut OverrideTraceStep,
+    ) {
+        let (YamlValue::Mapping(before_map), YamlValue::Mapping(after_map)) = (before, after)
+        else {
+            if before != after {
+                step.changed_keys.push(path.clone());
+            }
+            return;
+        };
+
+        for (key, before_value) in before_map {
+            let length = path.len();
+            Self::push_key(path, key);
+            match after_map.get(key) {
+                Some(after_value) => Self::diff(before_value, after_value, path, step),
+                None => step.removed_keys.push(path.clone()),
+            }
+            path.truncate(length);
+        }
+        for key in after_map
+            .keys()
+            .filter(|key| !before_map.contains_key(*key))
+        {
+            let length = path.len();
+            Self::push_key(path, key);
+            step.added_keys.push(path.clone());
+            path.truncate(length);
+        }
+    }
+
+    fn push_key(path: &mut String, key: &YamlValue) {
+        if !path.is_empty() {
+            path.push('.');
+        }
+        match key {
+            YamlValue::String(key) => path.push_str(key),
+            key => path.push_str(serde_yaml_ng::to_string(key).unwrap_or_default().trim_end()),
+        }
+    }
+
+    fn truncate(keys: &mut Vec<String>) {
+        if keys.len() > MAX_TRACE_KEYS {
+            let rest = keys.len() - MAX_TRACE_KEYS;
+            keys.truncate(MAX_TRACE_KEYS);
+            keys.push(format!("…（另有 {} 项）", rest));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_step() {
+        let before = ConfigSnapshot::capture(
+            "mode: rule\ndns: {enable: true, ipv6: true}\nproxies: [{name: a}]\nrules: [MATCH]\n",
+        );
+        let after = ConfigSnapshot::capture(
+            "mode: global\ndns: {enable: true, listen: ':53'}\nproxies: [{name: a}, {name: b}]\n",
+        );
+
+        let step = before.trace_step(Some(&after), OverrideTraceStep::default());
+        assert_eq!(step.added_keys, ["dns.listen"]);
+        assert_eq!(step.removed_keys, ["dns.ipv6", "rules"]);
+        assert_eq!(step.changed_keys, ["mode", "proxies"]);
+        assert_eq!(step.counts_before.proxy_count, 1);
+        assert_eq!(step.counts_after.proxy_count, 2);
+        assert_eq!(step.counts_after.rule_count, 0);
+
+        let step = before.trace_step(None, OverrideTraceStep::default());
+        assert!(step.changed_keys.is_empty());
+        assert_eq!(step.counts_after.rule_count, 1);
+    }
+}
@@ -1,8 +1,75 @@
 // YAML 配置深度合并：支持特殊语法的覆写合并策略。
 // 用于将覆写配置稳定合并到基础配置。
 
+use regex::Regex;
 use serde_yaml_ng::Value as YamlValue;
 
+// 数组元素选择器：`[字段=值]`、`[字段~=正则]`，省略字段时匹配元素本身
+struct ElementSelector {
+    field: Option<String>,
+    pattern: SelectorPattern,
+}
+
+enum SelectorPattern {
+    Equals(String),
+    Regex(Regex),
+}
+
+// 对选中数组元素执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ElementAction {
+    Merge,        // key[sel]：合并到选中元素
+    Replace,      // key[sel]!：替换选中元素
+    Remove,       // key[sel]-：删除选中元素
+    InsertBefore, // +key[sel]：插入到第一个选中元素之前
+    InsertAfter,  // key[sel]+：插入到第一个选中元素之后
+}
+
+impl ElementSelector {
+    // 解析方括号内的选择器
+    fn parse(text: &str) -> Result<Self, String> {
+        let (field, pattern) = if let Some((field, pattern)) = text.split_once("~=") {
+            let regex =
+                Regex::new(pattern).map_err(|e| format!("无效的正则表达式“{}”：{}", pattern, e))?;
+            (field, SelectorPattern::Regex(regex))
+        } else if let Some((field, value)) = text.split_once('=') {
+            (field, SelectorPattern::Equals(value.to_string()))
+        } else {
+            return Err(format!("无效的数组元素选择器：[{}]", text));
+        };
+
+        let field = field.trim();
+        Ok(Self {
+            field: (!field.is_empty()).then(|| field.to_string()),
+            pattern,
+        })
+    }
+
+    fn matches(&self, item: &YamlValue) -> bool {
+        let target = match &self.field {
+            Some(field) => item.get(field.as_str()),
+            None => Some(item),
+        };
+        let Some(text) = target.and_then(Self::scalar_text) else {
+            return false;
+        };
+        match &self.pattern {
+            SelectorPattern::Equals(value) => text == *value,
+            SelectorPattern::Regex(regex) => regex.is_match(&text),
+        }
+    }
+
+    // 标量的文本形式，用于与选择器比较
+    fn scalar_text(value: &YamlValue) -> Option<String> {
+        match value {
+            YamlValue::String(s) => Some(s.clone()),
+            YamlValue::Number(n) => Some(n.to_string()),
+            YamlValue::Bool(b) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+}
+
 // YAML 合并器
 pub struct YamlMerger;
 
@@ -35,8 +102,8 @@ impl YamlMerger {
         serde_yaml_ng::to_string(&merged).map_err(|e| format!("序列化配置失败：{}", e))
     }
 
-    // 深度合并两个 YAML 值，支持 `key!`、`+key`、`key+`、`<key>` 特殊语法。
-    // 用于控制替换策略与数组拼接方向。
+    // 深度合并两个 YAML 值，支持 `key!`、`+key`、`key+`、`key-`、`<key>` 特殊语法，
+    // 以及按选择器编辑数组元素的 `key[sel]`、`key[sel]!`、`key[sel]-`、`+key[sel]`、`key[sel]+`。
     fn deep_merge(base: YamlValue, override_val: YamlValue) -> Result<YamlValue, String> {
         match (base, override_val) {
             (YamlValue::Mapping(mut base_map), YamlValue::Mapping(override_map)) => {
@@ -45,6 +112,20 @@ impl YamlMerger {
                 for (key, override_value) in override_map {
                     let key_str = key.as_str().ok_or_else(|| "键必须是字符串".to_string())?;
 
+                    // 0. 数组元素指令 (key[sel]、key[sel]!、key[sel]-、+key[sel]、key[sel]+)
+                    if let Some((actual_key, selector, action)) =
+                        Self::parse_element_directive(key_str)?
+                    {
+                        Self::apply_element_directive(
+                            &mut base_map,
+                            actual_key,
+                            &selector,
+                            action,
+                            override_value,
+                        )?;
+                        continue;
+                    }
+
                     // 1. 强制替换模式 (key!)
                     if let Some(actual_key) = key_str.strip_suffix('!') {
                         let yaml_key = YamlValue::String(actual_key.to_string());
@@ -88,7 +169,31 @@ impl YamlMerger {
                         continue;
                     }
 
-                    // 4. 去除包装标记 (<key>)
+                    // 4. 删除模式 (key-)：值为数组时删除其中列出的元素，否则删除整个键
+                    if let Some(actual_key) = key_str.strip_suffix('-').filter(|k| !k.is_empty()) {
+                        let yaml_key = YamlValue::String(actual_key.to_string());
+
+                        match (base_map.get_mut(&yaml_key), override_value) {
+                            (Some(YamlValue::Sequence(base_arr)), YamlValue::Sequence(values)) => {
+                                let before = base_arr.len();
+                                base_arr.retain(|item| !values.contains(item));
+                                log::debug!(
+                                    "数组删除：{}（{}项）",
+                                    actual_key,
+                                    before - base_arr.len()
+                                );
+                            }
+                            // 基础配置中没有对应数组，无需删除
+                            (_, YamlValue::Sequence(_)) => {}
+                            _ => {
+                                base_map.shift_remove(&yaml_key);
+                                log::debug!("删除键：{}", actual_key);
+                            }
+                        }
+                        continue;
+                    }
+
+                    // 5. 去除包装标记 (<key>)
                     let clean_key = if key_str.starts_with('<')
                         && key_str.ends_with('>')
                         && key_str.len() > 2
@@ -100,7 +205,7 @@ impl YamlMerger {
 
                     let yaml_key = YamlValue::String(clean_key.to_string());
 
-                    // 5. 默认行为：递归合并或替换
+                    // 6. 默认行为：递归合并或替换
                     if let Some(base_value) = base_map.remove(&yaml_key) {
                         // 使用 remove 避免克隆，然后递归合并
                         let merged_value = Self::deep_merge(base_value, override_value)?;
@@ -124,4 +229,192 @@ impl YamlMerger {
             }
         }
     }
+
+    // 解析数组元素指令，返回（数组键、选择器、操作）；不是元素指令时返回 None。
+    // 使用 `<key>` 包装的键不解析为指令。
+    fn parse_element_directive(
+        key_str: &str,
+    ) -> Result<Option<(&str, ElementSelector, ElementAction)>, String> {
+        if key_str.starts_with('<') && key_str.ends_with('>') {
+            return Ok(None);
+        }
+
+        let (rest, action) = if let Some(rest) = key_str.strip_prefix('+') {
+            (rest, ElementAction::InsertBefore)
+        } else if let Some(rest) = key_str.strip_suffix('+') {
+            (rest, ElementAction::InsertAfter)
+        } else if let Some(rest) = key_str.strip_suffix('!') {
+            (rest, ElementAction::Replace)
+        } else if let Some(rest) = key_str.strip_suffix('-') {
+            (rest, ElementAction::Remove)
+        } else {
+            (key_str, ElementAction::Merge)
+        };
+
+        let Some((actual_key, selector)) = rest
+            .strip_suffix(']')
+            .and_then(|rest| rest.split_once('['))
+            .filter(|(actual_key, _)| !actual_key.is_empty())
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some((
+            actual_key,
+            ElementSelector::parse(selector)?,
+            action,
+        )))
+    }
+
+    // 对数组中被选中的元素执行操作，目标不是数组时忽略
+    fn apply_element_directive(
+        base_map: &mut serde_yaml_ng::Mapping,
+        actual_key: &str,
+        selector: &ElementSelector,
+        action: ElementAction,
+        override_value: YamlValue,
+    ) -> Result<(), String> {
+        let yaml_key = YamlValue::String(actual_key.to_string());
+        let Some(YamlValue::Sequence(base_arr)) = base_map.get_mut(&yaml_key) else {
+            log::warn!("数组元素指令的目标不是数组，已忽略：{}", actual_key);
+            return Ok(());
+        };
+
+        match action {
+            ElementAction::Merge | ElementAction::Replace => {
+                let mut matched = 0;
+                for item in base_arr.iter_mut().filter(|item| selector.matches(item)) {
+                    *item = if action == ElementAction::Merge {
+                        let base_item = std::mem::replace(item, YamlValue::Null);
+                        Self::deep_merge(base_item, override_value.clone())?
+                    } else {
+                        override_value.clone()
+                    };
+                    matched += 1;
+                }
+                if matched == 0 {
+                    log::warn!("数组元素指令未匹配任何元素：{}", actual_key);
+                }
+                log::debug!("数组元素修改：{}（{}项）", actual_key, matched);
+            }
+            ElementAction::Remove => {
+                let before = base_arr.len();
+                base_arr.retain(|item| !selector.matches(item));
+                log::debug!(
+                    "数组元素删除：{}（{}项）",
+                    actual_key,
+                    before - base_arr.len()
+                );
+            }
+            ElementAction::InsertBefore | ElementAction::InsertAfter => {
+                let new_items = match override_value {
+                    YamlValue::Sequence(items) => items,
+                    item => vec![item],
+                };
+                // 未找到锚点元素时追加到末尾
+                let index = match base_arr.iter().position(|item| selector.matches(item)) {
+                    Some(i) if action == ElementAction::InsertBefore => i,
+                    Some(i) => i + 1,
+                    None => base_arr.len(),
+                };
+                log::debug!(
+                    "数组插入：{}（{}项，位置 {}）",
+                    actual_key,
+                    new_items.len(),
+                    index
+                );
+                base_arr.splice(index..index, new_items);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(base: &str, override_content: &str) -> Result<YamlValue, String> {
+        let merged = YamlMerger::new().apply(base, override_content)?;
+        serde_yaml_ng::from_str(&merged).map_err(|e| e.to_string())
+    }
+
+    fn strings(value: &YamlValue) -> Vec<&str> {
+        value
+            .as_sequence()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+            .collect()
+    }
+
+    const BASE: &str = r#"
+mode: rule
+dns: {enable: true}
+proxies: [{name: HK}, {name: JP}, {name: US}]
+proxy-groups:
+  - {name: PROXY, type: select, proxies: [HK, JP]}
+  - {name: AUTO, type: url-test, proxies: [HK]}
+rules: ["DOMAIN,a.com,PROXY", "DOMAIN,b.com,DIRECT", "MATCH,PROXY"]
+"#;
+
+    #[test]
+    fn test_delete_directives() -> Result<(), String> {
+        let merged = merge(
+            BASE,
+            r#"
+dns-: ~
+rules-: ["DOMAIN,b.com,DIRECT"]
+proxies[name~=^(JP|US)$]-: ~
+"#,
+        )?;
+        assert!(merged.get("dns").is_none());
+        assert_eq!(merged["mode"].as_str(), Some("rule"));
+        assert_eq!(
+            strings(&merged["rules"]),
+            ["DOMAIN,a.com,PROXY", "MATCH,PROXY"]
+        );
+        assert_eq!(merged["proxies"].as_sequence().map(|p| p.len()), Some(1));
+
+        // 以 <key-> 转义时为普通键
+        let merged = merge(BASE, "<dns->: 1")?;
+        assert_eq!(merged["dns-"].as_i64(), Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_element_directives() -> Result<(), String> {
+        let merged = merge(
+            BASE,
+            r#"
+proxy-groups[name=PROXY]:
+  type: fallback
+  proxies+: [US]
+proxy-groups[name=AUTO]!: {name: AUTO, type: load-balance, proxies: [JP]}
++rules[~=^MATCH,]: ["DOMAIN,c.com,PROXY"]
+rules[=DOMAIN,a.com,PROXY]+: DOMAIN,d.com,PROXY
++proxies[name=NONE]: [{name: SG}]
+"#,
+        )?;
+        let groups = &merged["proxy-groups"];
+        assert_eq!(groups[0]["type"].as_str(), Some("fallback"));
+        assert_eq!(strings(&groups[0]["proxies"]), ["HK", "JP", "US"]);
+        assert_eq!(groups[1]["type"].as_str(), Some("load-balance"));
+        assert_eq!(
+            strings(&merged["rules"]),
+            [
+                "DOMAIN,a.com,PROXY",
+                "DOMAIN,d.com,PROXY",
+                "DOMAIN,b.com,DIRECT",
+                "DOMAIN,c.com,PROXY",
+                "MATCH,PROXY"
+            ]
+        );
+        // 未找到锚点时追加到末尾
+        assert_eq!(merged["proxies"][3]["name"].as_str(), Some("SG"));
+
+        assert!(merge(BASE, "rules[name]: 1").is_err());
+        assert!(merge(BASE, "rules[~=(]-: ~").is_err());
+        Ok(())
+    }
 }
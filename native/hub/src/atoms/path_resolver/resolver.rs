@@ -34,6 +34,9 @@ pub struct PathService {
     // 日志文件路径
     log_file: PathBuf,
 
+    // 订阅缓存目录
+    subscription_cache_dir: PathBuf,
+
     // Windows 特有：自启动任务目录
     #[cfg(target_os = "windows")]
     tasks_dir: PathBuf,
@@ -74,6 +77,9 @@ impl PathService {
         // 日志文件路径
         let log_file = app_data_dir.join("running.logs");
 
+        // 订阅缓存目录
+        let subscription_cache_dir = app_data_dir.join("subscription_cache");
+
         // Windows 自启动任务目录
         #[cfg(target_os = "windows")]
         let tasks_dir = {
@@ -90,6 +96,7 @@ impl PathService {
             assets_service_dir,
             assets_service_binary,
             log_file,
+            subscription_cache_dir,
             #[cfg(target_os = "windows")]
             tasks_dir,
         })
@@ -153,6 +160,7 @@ impl PathService {
                 .join("service")
                 .join("stelliberty-service"),
             log_file: current_dir.join("data").join("running.logs"),
+            subscription_cache_dir: current_dir.join("data").join("subscription_cache"),
             #[cfg(target_os = "windows")]
             tasks_dir: current_dir.join("tasks"),
         }
@@ -188,6 +196,11 @@ impl PathService {
         &self.log_file
     }
 
+    // 获取订阅缓存目录
+    pub fn subscription_cache_dir(&self) -> &PathBuf {
+        &self.subscription_cache_dir
+    }
+
     // 获取自启动任务目录（仅 Windows）
     #[cfg(target_os = "windows")]
     pub fn tasks_dir(&self) -> &PathBuf {
@@ -268,6 +281,14 @@ pub fn log_file() -> PathBuf {
         .unwrap_or_else(|_| PathBuf::from("running.logs"))
 }
 
+// 获取订阅缓存目录
+pub fn subscription_cache_dir() -> PathBuf {
+    PATH_SERVICE
+        .read()
+        .map(|s| s.subscription_cache_dir().clone())
+        .unwrap_or_else(|_| PathBuf::from("subscription_cache"))
+}
+
 // 获取自启动任务目录（仅 Windows）
 #[cfg(target_os = "windows")]
 pub fn tasks_dir() -> PathBuf {
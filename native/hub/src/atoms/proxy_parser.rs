@@ -1,5 +1,15 @@
 // 代理链接解析器原子模块
 
+mod aggregator;
+mod diff;
+mod grouping;
+mod ios_formats;
 mod parser;
+mod serializer;
+mod shadowsocks;
+mod singbox;
 
+pub use aggregator::SubscriptionAggregator;
+pub use diff::ProfileDiffer;
 pub use parser::ProxyParser;
+pub use serializer::ProxySerializer;
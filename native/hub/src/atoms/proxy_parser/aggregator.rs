@@ -0,0 +1,358 @@
+// 多订阅聚合：将多个订阅合并为一份 Clash 配置。
+// 节点名加来源标签前缀，每个来源生成一个代理组，规则取自指定的基准订阅。
+
+use super::grouping::ProxyGrouper;
+use super::parser::ProxyParser;
+use crate::atoms::shared_types::{
+    AggregatedSourceReport, ParseReport, SubscriptionParseOptions, SubscriptionSource,
+};
+use serde_json::{Value as JsonValue, json};
+use std::collections::{HashMap, HashSet};
+
+// 全局选择组与自动测速组名称
+const GLOBAL_GROUP: &str = "PROXY";
+const AUTO_GROUP: &str = "AUTO";
+
+// 单个来源的解析结果
+struct ParsedSource {
+    label: String,
+    config: JsonValue,                 // 解析后的完整配置
+    is_full_config: bool,              // 是否为原样保留的 Clash YAML（含自定义代理组）
+    members: Vec<String>,              // 聚合后的节点名
+    name_map: HashMap<String, String>, // 原节点名 → 聚合后节点名
+}
+
+// 多订阅聚合器
+pub struct SubscriptionAggregator;
+
+impl SubscriptionAggregator {
+    // 聚合多个订阅，返回合并后的 Clash 配置与各来源的解析结果。
+    // 解析失败的来源会被跳过；rule_source_index 指定提供规则的来源。
+    pub fn aggregate(
+        sources: &[SubscriptionSource],
+        rule_source_index: usize,
+        options: &SubscriptionParseOptions,
+    ) -> Result<(String, Vec<AggregatedSourceReport>), String> {
+        if sources.is_empty() {
+            return Err("未提供任何订阅来源".to_string());
+        }
+
+        let labels = Self::unique_labels(sources);
+        let mut parsed: Vec<Option<ParsedSource>> = Vec::new();
+        let mut reports = Vec::new();
+
+        for (source, label) in sources.iter().zip(labels) {
+            match Self::parse_source(&source.content, options) {
+                Ok((config, report)) => {
+                    log::info!(
+                        "订阅来源「{}」解析成功，{}个节点",
+                        label,
+                        report.accepted_count
+                    );
+                    parsed.push(Some(ParsedSource {
+                        label: label.clone(),
+                        is_full_config: report.source_format == "clash_yaml",
+                        config,
+                        members: Vec::new(),
+                        name_map: HashMap::new(),
+                    }));
+                    reports.push(AggregatedSourceReport {
+                        label,
+                        is_successful: true,
+                        report: Some(report),
+                        error_message: None,
+                    });
+                }
+                Err(e) => {
+                    log::warn!("订阅来源「{}」解析失败，已跳过：{}", label, e);
+                    parsed.push(None);
+                    reports.push(AggregatedSourceReport {
+                        label,
+                        is_successful: false,
+                        report: None,
+                        error_message: Some(e),
+                    });
+                }
+            }
+        }
+
+        if parsed.iter().all(Option::is_none) {
+            return Err("所有订阅来源均解析失败".to_string());
+        }
+
+        let proxies = Self::merge_proxies(&mut parsed);
+        if proxies.is_empty() {
+            return Err("所有订阅来源均未包含代理节点".to_string());
+        }
+
+        let config = Self::build_config(proxies, &parsed, rule_source_index, options)?;
+        Ok((ProxyParser::to_clash_yaml(config)?, reports))
+    }
+
+    // 解析单个来源，返回 JSON 形式的配置与解析报告
+    fn parse_source(
+        content: &str,
+        options: &SubscriptionParseOptions,
+    ) -> Result<(JsonValue, ParseReport), String> {
+        let (config, report) = ProxyParser::parse_subscription_with_report(content, options)?;
+
+        let yaml_value: serde_yaml_ng::Value =
+            serde_yaml_ng::from_str(&config).map_err(|e| format!("YAML 解析失败：{}", e))?;
+        let config =
+            serde_json::to_value(yaml_value).map_err(|e| format!("转换为 JSON 失败：{}", e))?;
+
+        Ok((config, report))
+    }
+
+    // 为各来源节点加标签前缀并合并，统一解决重名
+    fn merge_proxies(parsed: &mut [Option<ParsedSource>]) -> Vec<JsonValue> {
+        let mut proxies = Vec::new();
+        let mut owners: Vec<(usize, String)> = Vec::new();
+
+        for (index, source) in parsed.iter().enumerate() {
+            let Some(source) = source else { continue };
+            for proxy in source.config["proxies"].as_array().into_iter().flatten() {
+                let original = proxy["name"].as_str().unwrap_or_default().to_string();
+                let mut proxy = proxy.clone();
+                proxy["name"] = json!(format!("[{}] {}", source.label, original));
+                proxies.push(proxy);
+                owners.push((index, original));
+            }
+        }
+
+        ProxyGrouper::dedupe_names(&mut proxies);
+
+        for (proxy, (index, original)) in proxies.iter().zip(owners) {
+            let name = proxy["name"].as_str().unwrap_or_default().to_string();
+            if let Some(source) = parsed[index].as_mut() {
+                source.members.push(name.clone());
+                source.name_map.entry(original).or_insert(name);
+            }
+        }
+
+        proxies
+    }
+
+    // 构建聚合配置：全局选择组、自动测速组、来源组、地区组，以及基准来源的代理组与规则
+    fn build_config(
+        proxies: Vec<JsonValue>,
+        parsed: &[Option<ParsedSource>],
+        rule_source_index: usize,
+        options: &SubscriptionParseOptions,
+    ) -> Result<JsonValue, String> {
+        let proxy_names: Vec<String> = proxies
+            .iter()
+            .filter_map(|p| p["name"].as_str().map(|s| s.to_string()))
+            .collect();
+
+        // 没有节点的来源（例如只使用 proxy-providers）不生成来源组
+        let source_groups: Vec<JsonValue> = parsed
+            .iter()
+            .flatten()
+            .filter(|source| !source.members.is_empty())
+            .map(|source| {
+                json!({
+                    "name": source.label,
+                    "type": "select",
+                    "proxies": source.members,
+                })
+            })
+            .collect();
+
+        let region_groups = if options.is_region_grouping_enabled {
+            ProxyGrouper::build_region_groups(&proxy_names)
+        } else {
+            Vec::new()
+        };
+
+        let mut global_members: Vec<String> = source_groups
+            .iter()
+            .chain(&region_groups)
+            .filter_map(|g| g["name"].as_str().map(|s| s.to_string()))
+            .collect();
+        global_members.push(AUTO_GROUP.to_string());
+
+        let mut proxy_groups = vec![
+            json!({
+                "name": GLOBAL_GROUP,
+                "type": "select",
+                "proxies": global_members,
+            }),
+            json!({
+                "name": AUTO_GROUP,
+                "type": "url-test",
+                "proxies": proxy_names,
+                "url": "https://www.gstatic.com/generate_204",
+                "interval": 300
+            }),
+        ];
+        proxy_groups.extend(source_groups);
+        proxy_groups.extend(region_groups);
+
+        let mut config = json!({ "proxies": proxies });
+
+        match parsed.get(rule_source_index).and_then(Option::as_ref) {
+            Some(base) => {
+                if base.is_full_config {
+                    let reserved: HashSet<String> = proxy_groups
+                        .iter()
+                        .filter_map(|g| g["name"].as_str().map(|s| s.to_string()))
+                        .collect();
+                    proxy_groups.extend(Self::carry_base_groups(base, &reserved));
+
+                    for key in ["proxy-providers", "rule-providers"] {
+                        if base.config[key].is_object() {
+                            config[key] = base.config[key].clone();
+                        }
+                    }
+                }
+                config["rules"] = match &base.config["rules"] {
+                    JsonValue::Array(rules) if !rules.is_empty() => json!(rules),
+                    _ => json!([format!("MATCH,{}", GLOBAL_GROUP)]),
+                };
+            }
+            None => {
+                log::warn!("规则来源（序号 {}）不可用，使用默认规则", rule_source_index);
+                config["rules"] = json!([format!("MATCH,{}", GLOBAL_GROUP)]);
+            }
+        }
+
+        config["proxy-groups"] = JsonValue::Array(proxy_groups);
+        Ok(config)
+    }
+
+    // 保留基准来源的代理组：节点名替换为聚合后的名称。
+    // 与聚合生成的组重名时丢弃原组，规则中的引用随之指向聚合组；
+    // 包含节点的 select 组前置全局选择组，使其可选用其他来源的节点。
+    fn carry_base_groups(base: &ParsedSource, reserved: &HashSet<String>) -> Vec<JsonValue> {
+        let mut groups = Vec::new();
+
+        for group in base.config["proxy-groups"].as_array().into_iter().flatten() {
+            let group_name = group["name"].as_str().unwrap_or_default();
+            if reserved.contains(group_name) {
+                log::warn!(
+                    "基准订阅的代理组「{}」与聚合代理组重名，已由聚合组替代",
+                    group_name
+                );
+                continue;
+            }
+
+            let mut group = group.clone();
+            if let Some(members) = group["proxies"].as_array() {
+                let has_nodes = members
+                    .iter()
+                    .filter_map(|m| m.as_str())
+                    .any(|m| base.name_map.contains_key(m));
+
+                let mut remapped: Vec<JsonValue> = members
+                    .iter()
+                    .map(
+                        |member| match member.as_str().and_then(|m| base.name_map.get(m)) {
+                            Some(name) => json!(name),
+                            None => member.clone(),
+                        },
+                    )
+                    .collect();
+
+                if has_nodes && group["type"].as_str() == Some("select") {
+                    remapped.insert(0, json!(GLOBAL_GROUP));
+                }
+                group["proxies"] = JsonValue::Array(remapped);
+            }
+            groups.push(group);
+        }
+
+        groups
+    }
+
+    // 生成唯一的来源标签，空标签使用「订阅 N」
+    fn unique_labels(sources: &[SubscriptionSource]) -> Vec<String> {
+        let mut used = HashSet::new();
+        sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| {
+                let base = match source.label.trim() {
+                    "" => format!("订阅 {}", index + 1),
+                    label => label.to_string(),
+                };
+                let mut label = base.clone();
+                let mut suffix = 2;
+                while used.contains(&label) {
+                    label = format!("{} ({})", base, suffix);
+                    suffix += 1;
+                }
+                used.insert(label.clone());
+                label
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate_sources() -> Result<(), String> {
+        let clash_yaml = r#"
+proxies:
+  - {name: "HK 01", type: ss, server: hk.example.com, port: 8388, cipher: aes-128-gcm, password: pw}
+proxy-groups:
+  - {name: "节点选择", type: select, proxies: ["HK 01", DIRECT]}
+  - {name: PROXY, type: select, proxies: ["HK 01"]}
+rules:
+  - DOMAIN-SUFFIX,google.com,节点选择
+  - MATCH,PROXY
+"#;
+        let links =
+            "trojan://secret@a.example.com:443#HK%2001\ntrojan://secret@b.example.com:443#HK%2001";
+
+        let sources = vec![
+            SubscriptionSource {
+                label: "机场A".to_string(),
+                content: clash_yaml.to_string(),
+            },
+            SubscriptionSource {
+                label: "机场B".to_string(),
+                content: links.to_string(),
+            },
+            SubscriptionSource {
+                label: "机场B".to_string(),
+                content: "not a subscription".to_string(),
+            },
+        ];
+
+        let (config, reports) =
+            SubscriptionAggregator::aggregate(&sources, 0, &SubscriptionParseOptions::default())?;
+        let config: serde_yaml_ng::Value =
+            serde_yaml_ng::from_str(&config).map_err(|e| e.to_string())?;
+        let config = serde_json::to_value(config).map_err(|e| e.to_string())?;
+
+        let names: Vec<&str> = config["proxies"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|p| p["name"].as_str())
+            .collect();
+        assert_eq!(
+            names,
+            ["[机场A] HK 01", "[机场B] HK 01", "[机场B] HK 01 (2)"]
+        );
+
+        let groups = &config["proxy-groups"];
+        assert_eq!(groups[0]["proxies"], json!(["机场A", "机场B", "AUTO"]));
+        assert_eq!(groups[2]["name"], "机场A");
+        assert_eq!(groups[4]["name"], "节点选择");
+        assert_eq!(
+            groups[4]["proxies"],
+            json!(["PROXY", "[机场A] HK 01", "DIRECT"])
+        );
+        assert_eq!(groups.as_array().map(|g| g.len()), Some(5));
+        assert_eq!(config["rules"][0], "DOMAIN-SUFFIX,google.com,节点选择");
+
+        assert_eq!(reports[2].label, "机场B (2)");
+        assert!(!reports[2].is_successful);
+        Ok(())
+    }
+}
@@ -0,0 +1,330 @@
+// 订阅版本差异：比较新旧两份 Clash 配置的节点、代理组与规则。
+// 名称不同但服务器、端口与凭据相同的节点视为改名，以便发现订阅方悄悄替换节点。
+
+use crate::atoms::shared_types::{ModifiedGroup, ModifiedNode, ProfileDiff, RenamedNode};
+use serde_json::{Map, Value as JsonValue, json};
+use std::collections::{HashMap, HashSet};
+
+// 用于识别同一节点的凭据字段
+const CREDENTIAL_FIELDS: [&str; 7] = [
+    "uuid",
+    "password",
+    "auth-str",
+    "auth",
+    "psk",
+    "private-key",
+    "username",
+];
+
+// 订阅差异比较器
+pub struct ProfileDiffer;
+
+impl ProfileDiffer {
+    // 比较新旧两份 Clash YAML 配置
+    pub fn diff(old_content: &str, new_content: &str) -> Result<ProfileDiff, String> {
+        let old = Self::parse_config(old_content).map_err(|e| format!("旧版本：{}", e))?;
+        let new = Self::parse_config(new_content).map_err(|e| format!("新版本：{}", e))?;
+
+        let mut diff = ProfileDiff::default();
+        let renames = Self::diff_nodes(&old, &new, &mut diff);
+        Self::diff_groups(&old, &new, &renames, &mut diff);
+        Self::diff_rules(&old, &new, &mut diff);
+
+        diff.has_changes = !diff.added_nodes.is_empty()
+            || !diff.removed_nodes.is_empty()
+            || !diff.renamed_nodes.is_empty()
+            || !diff.modified_nodes.is_empty()
+            || !diff.added_groups.is_empty()
+            || !diff.removed_groups.is_empty()
+            || !diff.modified_groups.is_empty()
+            || diff.added_rule_count > 0
+            || diff.removed_rule_count > 0;
+
+        Ok(diff)
+    }
+
+    // 解析 YAML 配置为 JSON 对象
+    fn parse_config(content: &str) -> Result<JsonValue, String> {
+        let yaml_value: serde_yaml_ng::Value =
+            serde_yaml_ng::from_str(content).map_err(|e| format!("YAML 解析失败：{}", e))?;
+        let config =
+            serde_json::to_value(yaml_value).map_err(|e| format!("转换为 JSON 失败：{}", e))?;
+
+        if !config.is_object() {
+            return Err("配置不是有效的 Clash 配置".to_string());
+        }
+        Ok(config)
+    }
+
+    // 比较节点，返回改名映射（旧名称 → 新名称）
+    fn diff_nodes(
+        old: &JsonValue,
+        new: &JsonValue,
+        diff: &mut ProfileDiff,
+    ) -> HashMap<String, String> {
+        let old_nodes = Self::named_entries(old, "proxies");
+        let new_nodes = Self::named_entries(new, "proxies");
+        let old_by_name: HashMap<&str, &Map<String, JsonValue>> = old_nodes
+            .iter()
+            .map(|(name, node)| (*name, *node))
+            .collect();
+        let new_names: HashSet<&str> = new_nodes.iter().map(|(name, _)| *name).collect();
+
+        // 同名节点：比较参数
+        for (name, node) in &new_nodes {
+            if let Some(old_node) = old_by_name.get(name) {
+                let changed_fields = Self::changed_fields(old_node, node);
+                if changed_fields.is_empty() {
+                    diff.unchanged_node_count += 1;
+                } else {
+                    diff.modified_nodes.push(ModifiedNode {
+                        name: name.to_string(),
+                        changed_fields,
+                    });
+                }
+            }
+        }
+
+        // 仅存在于旧版本的节点按指纹排队，供新增节点匹配改名
+        let mut removed_by_fingerprint: HashMap<String, Vec<&str>> = HashMap::new();
+        let mut removed: Vec<&str> = Vec::new();
+        for (name, node) in &old_nodes {
+            if new_names.contains(name) {
+                continue;
+            }
+            removed.push(name);
+            if let Some(fingerprint) = Self::fingerprint(node) {
+                removed_by_fingerprint
+                    .entry(fingerprint)
+                    .or_default()
+                    .push(name);
+            }
+        }
+
+        let mut renames = HashMap::new();
+        for (name, node) in &new_nodes {
+            if old_by_name.contains_key(name) {
+                continue;
+            }
+
+            // 同一指纹有多个候选时按原顺序依次匹配
+            let old_name = Self::fingerprint(node)
+                .and_then(|fingerprint| removed_by_fingerprint.get_mut(&fingerprint))
+                .filter(|candidates| !candidates.is_empty())
+                .map(|candidates| candidates.remove(0));
+            let old_node = old_name.and_then(|old_name| old_by_name.get(old_name));
+
+            match (old_name, old_node) {
+                (Some(old_name), Some(old_node)) => {
+                    diff.renamed_nodes.push(RenamedNode {
+                        old_name: old_name.to_string(),
+                        new_name: name.to_string(),
+                        changed_fields: Self::changed_fields(old_node, node),
+                    });
+                    renames.insert(old_name.to_string(), name.to_string());
+                }
+                _ => diff.added_nodes.push(name.to_string()),
+            }
+        }
+
+        diff.removed_nodes = removed
+            .into_iter()
+            .filter(|name| !renames.contains_key(*name))
+            .map(|name| name.to_string())
+            .collect();
+
+        renames
+    }
+
+    // 比较代理组：成员列表先按改名映射转换，单纯的节点改名不视为代理组变化
+    fn diff_groups(
+        old: &JsonValue,
+        new: &JsonValue,
+        renames: &HashMap<String, String>,
+        diff: &mut ProfileDiff,
+    ) {
+        let old_groups = Self::named_entries(old, "proxy-groups");
+        let new_groups = Self::named_entries(new, "proxy-groups");
+        let old_by_name: HashMap<&str, &Map<String, JsonValue>> = old_groups
+            .iter()
+            .map(|(name, group)| (*name, *group))
+            .collect();
+        let new_names: HashSet<&str> = new_groups.iter().map(|(name, _)| *name).collect();
+
+        let members = |group: &Map<String, JsonValue>| -> Vec<String> {
+            group
+                .get("proxies")
+                .and_then(|p| p.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|m| m.as_str().map(|s| s.to_string()))
+                .collect()
+        };
+
+        for (name, group) in &new_groups {
+            let Some(old_group) = old_by_name.get(name) else {
+                diff.added_groups.push(name.to_string());
+                continue;
+            };
+
+            let old_members: Vec<String> = members(old_group)
+                .into_iter()
+                .map(|m| renames.get(&m).cloned().unwrap_or(m))
+                .collect();
+            let new_members = members(group);
+
+            let added_members: Vec<String> = new_members
+                .iter()
+                .filter(|m| !old_members.contains(m))
+                .cloned()
+                .collect();
+            let removed_members: Vec<String> = old_members
+                .iter()
+                .filter(|m| !new_members.contains(m))
+                .cloned()
+                .collect();
+            let is_type_changed = old_group.get("type") != group.get("type");
+
+            if is_type_changed || !added_members.is_empty() || !removed_members.is_empty() {
+                diff.modified_groups.push(ModifiedGroup {
+                    name: name.to_string(),
+                    is_type_changed,
+                    added_members,
+                    removed_members,
+                });
+            }
+        }
+
+        diff.removed_groups = old_groups
+            .iter()
+            .filter(|(name, _)| !new_names.contains(name))
+            .map(|(name, _)| name.to_string())
+            .collect();
+    }
+
+    // 比较规则：按规则文本做多重集合差
+    fn diff_rules(old: &JsonValue, new: &JsonValue, diff: &mut ProfileDiff) {
+        let rules = |config: &JsonValue| -> Vec<String> {
+            config["rules"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|r| r.as_str().map(|s| s.trim().to_string()))
+                .collect()
+        };
+        let old_rules = rules(old);
+        let new_rules = rules(new);
+
+        let mut counts: HashMap<&str, i64> = HashMap::new();
+        for rule in &old_rules {
+            *counts.entry(rule).or_default() += 1;
+        }
+        for rule in &new_rules {
+            *counts.entry(rule).or_default() -= 1;
+        }
+
+        diff.old_rule_count = old_rules.len() as u32;
+        diff.new_rule_count = new_rules.len() as u32;
+        diff.removed_rule_count = counts.values().filter(|c| **c > 0).sum::<i64>() as u32;
+        diff.added_rule_count = counts.values().filter(|c| **c < 0).map(|c| -c).sum::<i64>() as u32;
+    }
+
+    // 读取带名称的条目列表（节点或代理组），保持原顺序，重名时保留第一个
+    fn named_entries<'a>(
+        config: &'a JsonValue,
+        key: &str,
+    ) -> Vec<(&'a str, &'a Map<String, JsonValue>)> {
+        let mut seen = HashSet::new();
+        config[key]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let entry = entry.as_object()?;
+                let name = entry.get("name")?.as_str()?;
+                seen.insert(name).then_some((name, entry))
+            })
+            .collect()
+    }
+
+    // 节点指纹：类型、服务器、端口与凭据，缺少服务器的节点无法识别改名
+    fn fingerprint(node: &Map<String, JsonValue>) -> Option<String> {
+        let server = node.get("server")?.as_str()?;
+        // 端口可能是数字或字符串，统一为字符串比较
+        let port = match node.get("port") {
+            Some(JsonValue::String(port)) => port.clone(),
+            Some(port) => port.to_string(),
+            None => String::new(),
+        };
+        let credentials: Vec<&JsonValue> = CREDENTIAL_FIELDS
+            .iter()
+            .map(|field| node.get(*field).unwrap_or(&JsonValue::Null))
+            .collect();
+
+        Some(json!([node.get("type"), server.to_lowercase(), port, credentials]).to_string())
+    }
+
+    // 列出除名称外取值不同的字段
+    fn changed_fields(old: &Map<String, JsonValue>, new: &Map<String, JsonValue>) -> Vec<String> {
+        let mut fields: Vec<String> = old
+            .keys()
+            .chain(new.keys().filter(|key| !old.contains_key(*key)))
+            .filter(|key| *key != "name" && old.get(*key) != new.get(*key))
+            .cloned()
+            .collect();
+        fields.sort();
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_diff() -> Result<(), String> {
+        let old = r#"
+proxies:
+  - {name: "HK 01", type: ss, server: hk.example.com, port: 8388, cipher: aes-128-gcm, password: pw}
+  - {name: "JP 01", type: trojan, server: jp.example.com, port: 443, password: secret}
+  - {name: "US 01", type: trojan, server: us.example.com, port: 443, password: secret}
+proxy-groups:
+  - {name: PROXY, type: select, proxies: ["HK 01", "JP 01", "US 01"]}
+  - {name: OLD, type: select, proxies: [DIRECT]}
+rules:
+  - DOMAIN-SUFFIX,google.com,PROXY
+  - MATCH,PROXY
+"#;
+        let new = r#"
+proxies:
+  - {name: "HK 01", type: ss, server: hk.example.com, port: 8388, cipher: chacha20-ietf-poly1305, password: pw}
+  - {name: "日本 01", type: trojan, server: jp.example.com, port: "443", password: secret}
+  - {name: "SG 01", type: trojan, server: sg.example.com, port: 443, password: secret}
+proxy-groups:
+  - {name: PROXY, type: select, proxies: ["HK 01", "日本 01", "SG 01"]}
+  - {name: AUTO, type: url-test, proxies: ["HK 01"]}
+rules:
+  - DOMAIN-SUFFIX,youtube.com,PROXY
+  - DOMAIN-SUFFIX,google.com,PROXY
+  - MATCH,PROXY
+"#;
+
+        let diff = ProfileDiffer::diff(old, new)?;
+        assert!(diff.has_changes);
+        assert_eq!(diff.added_nodes, ["SG 01"]);
+        assert_eq!(diff.removed_nodes, ["US 01"]);
+        assert_eq!(diff.renamed_nodes.len(), 1);
+        assert_eq!(diff.renamed_nodes[0].old_name, "JP 01");
+        assert_eq!(diff.renamed_nodes[0].new_name, "日本 01");
+        assert_eq!(diff.modified_nodes[0].changed_fields, ["cipher"]);
+        assert_eq!(diff.added_groups, ["AUTO"]);
+        assert_eq!(diff.removed_groups, ["OLD"]);
+        assert_eq!(diff.modified_groups[0].added_members, ["SG 01"]);
+        assert_eq!(diff.modified_groups[0].removed_members, ["US 01"]);
+        assert_eq!((diff.old_rule_count, diff.new_rule_count), (2, 3));
+        assert_eq!((diff.added_rule_count, diff.removed_rule_count), (1, 0));
+
+        assert!(!ProfileDiffer::diff(old, old)?.has_changes);
+        Ok(())
+    }
+}
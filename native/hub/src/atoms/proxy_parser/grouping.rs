@@ -0,0 +1,275 @@
+// 代理节点整理：名称去重、信息节点过滤与按地区自动分组。
+// 用于生成可被 mihomo 直接加载的代理组。
+
+use regex::Regex;
+use serde_json::{Value as JsonValue, json};
+use std::collections::HashSet;
+
+// 默认排除的信息节点关键字（流量、到期时间、官网公告等）
+pub const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &[
+    "剩余流量",
+    "到期时间",
+    "过期时间",
+    "套餐到期",
+    "距离下次重置",
+    "官网",
+    "网址",
+    "(?i)traffic",
+    "(?i)expire",
+];
+
+// 地区定义：组名、旗帜、地区代码与关键字
+struct Region {
+    group_name: &'static str,
+    flags: &'static [&'static str],
+    codes: &'static [&'static str],
+    keywords: &'static [&'static str],
+}
+
+const REGIONS: &[Region] = &[
+    Region {
+        group_name: "🇭🇰 香港",
+        flags: &["🇭🇰"],
+        codes: &["HK", "HKG"],
+        keywords: &["香港", "Hong Kong", "HongKong"],
+    },
+    Region {
+        group_name: "🇹🇼 台湾",
+        flags: &["🇹🇼"],
+        codes: &["TW", "TWN"],
+        keywords: &["台湾", "台灣", "台北", "Taiwan", "Taipei"],
+    },
+    Region {
+        group_name: "🇯🇵 日本",
+        flags: &["🇯🇵"],
+        codes: &["JP", "JPN"],
+        keywords: &["日本", "东京", "大阪", "Japan", "Tokyo", "Osaka"],
+    },
+    Region {
+        group_name: "🇸🇬 新加坡",
+        flags: &["🇸🇬"],
+        codes: &["SG", "SGP"],
+        keywords: &["新加坡", "狮城", "Singapore"],
+    },
+    Region {
+        group_name: "🇰🇷 韩国",
+        flags: &["🇰🇷"],
+        codes: &["KR", "KOR"],
+        keywords: &["韩国", "首尔", "Korea", "Seoul"],
+    },
+    Region {
+        group_name: "🇺🇸 美国",
+        flags: &["🇺🇸"],
+        codes: &["US", "USA"],
+        keywords: &[
+            "美国",
+            "洛杉矶",
+            "圣何塞",
+            "硅谷",
+            "西雅图",
+            "纽约",
+            "United States",
+            "America",
+            "Los Angeles",
+            "San Jose",
+            "Seattle",
+            "New York",
+        ],
+    },
+    Region {
+        group_name: "🇬🇧 英国",
+        flags: &["🇬🇧"],
+        codes: &["UK", "GB", "GBR"],
+        keywords: &["英国", "伦敦", "United Kingdom", "London"],
+    },
+    Region {
+        group_name: "🇩🇪 德国",
+        flags: &["🇩🇪"],
+        codes: &["DE", "DEU"],
+        keywords: &["德国", "法兰克福", "Germany", "Frankfurt"],
+    },
+];
+
+// 代理节点整理器
+pub struct ProxyGrouper;
+
+impl ProxyGrouper {
+    // 移除名称匹配排除规则的节点，返回保留的节点与被排除的数量。
+    // 规则为正则表达式，无法编译时按普通关键字匹配。
+    pub fn filter_excluded(
+        proxies: Vec<JsonValue>,
+        patterns: &[String],
+    ) -> (Vec<JsonValue>, usize) {
+        if patterns.is_empty() {
+            return (proxies, 0);
+        }
+
+        let matchers: Vec<Regex> = patterns
+            .iter()
+            .filter(|p| !p.is_empty())
+            .filter_map(|p| {
+                Regex::new(p)
+                    .or_else(|_| Regex::new(&regex::escape(p)))
+                    .ok()
+            })
+            .collect();
+
+        let before = proxies.len();
+        let kept: Vec<JsonValue> = proxies
+            .into_iter()
+            .filter(|proxy| {
+                let name = proxy["name"].as_str().unwrap_or_default();
+                let is_excluded = matchers.iter().any(|m| m.is_match(name));
+                if is_excluded {
+                    log::debug!("排除信息节点：{}", name);
+                }
+                !is_excluded
+            })
+            .collect();
+
+        let excluded = before - kept.len();
+        (kept, excluded)
+    }
+
+    // 解决节点名称冲突：按出现顺序保留首个名称，后续重名节点追加 ` (n)` 后缀。
+    pub fn dedupe_names(proxies: &mut [JsonValue]) {
+        let mut used: HashSet<String> = HashSet::new();
+
+        for proxy in proxies.iter_mut() {
+            let name = match proxy["name"].as_str() {
+                Some(name) if !name.is_empty() => name.to_string(),
+                _ => proxy["type"].as_str().unwrap_or("proxy").to_string(),
+            };
+
+            let mut unique = name.clone();
+            let mut index = 2;
+            while used.contains(&unique) {
+                unique = format!("{} ({})", name, index);
+                index += 1;
+            }
+
+            if unique != name {
+                log::debug!("节点重名，重命名：{} → {}", name, unique);
+            }
+            proxy["name"] = json!(unique);
+            used.insert(unique);
+        }
+    }
+
+    // 按地区生成 url-test 代理组，仅包含至少有一个节点的地区。
+    pub fn build_region_groups(proxy_names: &[String]) -> Vec<JsonValue> {
+        REGIONS
+            .iter()
+            .filter_map(|region| {
+                let matcher = Self::region_matcher(region)?;
+                let members: Vec<&String> = proxy_names
+                    .iter()
+                    .filter(|name| matcher.is_match(name))
+                    .collect();
+
+                if members.is_empty() {
+                    return None;
+                }
+
+                Some(json!({
+                    "name": region.group_name,
+                    "type": "url-test",
+                    "proxies": members,
+                    "url": "https://www.gstatic.com/generate_204",
+                    "interval": 300,
+                    "tolerance": 50,
+                }))
+            })
+            .collect()
+    }
+
+    // 构建地区匹配正则：旗帜与关键字直接匹配，地区代码要求前后不是字母
+    fn region_matcher(region: &Region) -> Option<Regex> {
+        let literals = region
+            .flags
+            .iter()
+            .chain(region.keywords)
+            .map(|s| regex::escape(s))
+            .collect::<Vec<_>>()
+            .join("|");
+        let codes = region.codes.join("|");
+
+        Regex::new(&format!(
+            "(?i:{})|(?:^|[^A-Za-z])(?:{})(?:[^A-Za-z]|$)",
+            literals, codes
+        ))
+        .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedupe_names() {
+        let mut proxies = vec![
+            json!({"name": "🇭🇰 HK 01"}),
+            json!({"name": "🇭🇰 HK 01"}),
+            json!({"name": "🇭🇰 HK 01 (2)"}),
+            json!({"name": "🇭🇰 HK 01"}),
+        ];
+        ProxyGrouper::dedupe_names(&mut proxies);
+
+        let names: Vec<&str> = proxies.iter().filter_map(|p| p["name"].as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "🇭🇰 HK 01",
+                "🇭🇰 HK 01 (2)",
+                "🇭🇰 HK 01 (2) (2)",
+                "🇭🇰 HK 01 (3)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_excluded() {
+        let proxies = vec![
+            json!({"name": "剩余流量：100 GB"}),
+            json!({"name": "套餐到期：2026-12-31"}),
+            json!({"name": "🇯🇵 JP 01"}),
+        ];
+        let patterns: Vec<String> = DEFAULT_EXCLUDE_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+
+        let (kept, excluded) = ProxyGrouper::filter_excluded(proxies, &patterns);
+        assert_eq!(excluded, 2);
+        assert_eq!(kept[0]["name"], "🇯🇵 JP 01");
+    }
+
+    #[test]
+    fn test_region_groups() {
+        let names: Vec<String> = [
+            "🇭🇰 HK 01",
+            "香港 IPLC 02",
+            "JP-Tokyo",
+            "US01 洛杉矶",
+            "Russia 01",
+            "PUSH",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let groups = ProxyGrouper::build_region_groups(&names);
+        let summary: Vec<(&str, usize)> = groups
+            .iter()
+            .map(|g| {
+                (
+                    g["name"].as_str().unwrap_or_default(),
+                    g["proxies"].as_array().map(|p| p.len()).unwrap_or(0),
+                )
+            })
+            .collect();
+
+        assert_eq!(summary, [("🇭🇰 香港", 2), ("🇯🇵 日本", 1), ("🇺🇸 美国", 1)]);
+    }
+}
@@ -0,0 +1,801 @@
+// iOS 客户端代理行解析：支持 Surge、Loon 与 Quantumult X 的代理配置行。
+// 输出与分享链接解析器一致的 Clash 代理节点。
+
+use serde_json::{Value as JsonValue, json};
+use std::collections::HashMap;
+
+// Quantumult X 的代理类型前缀
+const QUANX_TYPES: &[&str] = &["shadowsocks", "vmess", "vless", "trojan", "http", "socks5"];
+
+// 代理配置行中的通用字段
+struct ProxyLine {
+    name: String,
+    proxy_type: String,
+    server: String,
+    port: i64,
+    // 位置参数（Loon 使用位置参数传递加密方式、密码等）
+    positional: Vec<String>,
+    // 键值参数（Surge 与 Quantumult X 使用键值参数）
+    options: HashMap<String, String>,
+}
+
+impl ProxyLine {
+    // 读取键值参数
+    fn option(&self, key: &str) -> Option<&str> {
+        self.options.get(key).map(|s| s.as_str())
+    }
+
+    // 读取布尔型键值参数
+    fn flag(&self, key: &str) -> bool {
+        matches!(self.option(key), Some("true") | Some("1"))
+    }
+
+    // 优先读取键值参数，不存在时读取指定位置参数
+    fn option_or_positional(&self, key: &str, index: usize) -> Option<&str> {
+        self.option(key)
+            .or_else(|| self.positional.get(index).map(|s| s.as_str()))
+    }
+}
+
+// iOS 客户端代理行解析器
+pub struct IosFormatParser;
+
+impl IosFormatParser {
+    // 判断是否为 Surge/Loon/Quantumult X 代理行
+    pub fn is_proxy_line(line: &str) -> bool {
+        Self::is_quanx_line(line) || Self::split_surge_line(line).is_some()
+    }
+
+    // 解析单行代理配置
+    pub fn parse_line(line: &str) -> Result<JsonValue, String> {
+        if Self::is_quanx_line(line) {
+            Self::parse_quanx(line)
+        } else {
+            Self::parse_surge_loon(line)
+        }
+    }
+
+    // 识别代理行的类型（Quantumult X 为前缀，Surge/Loon 为等号后的第一项）
+    pub fn detect_type(line: &str) -> Option<String> {
+        let proxy_type = if Self::is_quanx_line(line) {
+            line.split_once('=')?.0
+        } else {
+            Self::split_surge_line(line)?.1.split(',').next()?
+        };
+        Some(proxy_type.trim().to_ascii_lowercase())
+    }
+
+    // 判断是否为代理配置段落（[Proxy] / [server_local]）
+    pub fn is_proxy_section(section: &str) -> bool {
+        matches!(
+            section.to_ascii_lowercase().as_str(),
+            "proxy" | "server_local" | "remote proxy"
+        )
+    }
+
+    // Quantumult X 格式：type=host:port, key=value, ..., tag=name
+    fn is_quanx_line(line: &str) -> bool {
+        let first = line.split(',').next().unwrap_or_default();
+        match first.split_once('=') {
+            Some((proxy_type, address)) => {
+                QUANX_TYPES.contains(&proxy_type.trim().to_ascii_lowercase().as_str())
+                    && address.trim().rsplit_once(':').is_some()
+            }
+            None => false,
+        }
+    }
+
+    // Surge/Loon 格式：name = type, server, port, ...
+    fn split_surge_line(line: &str) -> Option<(&str, &str)> {
+        let (name, rest) = line.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() || !rest.contains(',') {
+            return None;
+        }
+        Some((name, rest))
+    }
+
+    // 解析 Surge/Loon 代理行
+    fn parse_surge_loon(line: &str) -> Result<JsonValue, String> {
+        let (name, rest) = Self::split_surge_line(line).ok_or("不是有效的 Surge/Loon 代理行")?;
+        let mut parts = rest.split(',').map(|s| s.trim());
+
+        let proxy_type = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let server = Self::unquote(parts.next().ok_or("缺少服务器地址")?);
+        let port = parts
+            .next()
+            .ok_or("缺少端口")?
+            .parse::<i64>()
+            .map_err(|_| "端口解析失败")?;
+
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        for part in parts.filter(|p| !p.is_empty()) {
+            match part.split_once('=') {
+                Some((key, value)) => {
+                    options.insert(key.trim().to_ascii_lowercase(), Self::unquote(value));
+                }
+                None => positional.push(Self::unquote(part)),
+            }
+        }
+
+        let line = ProxyLine {
+            name: name.to_string(),
+            proxy_type,
+            server,
+            port,
+            positional,
+            options,
+        };
+
+        match line.proxy_type.as_str() {
+            "ss" | "shadowsocks" => Self::build_shadowsocks(&line),
+            "shadowsocksr" => Self::build_shadowsocksr(&line),
+            "vmess" => Self::build_vmess(&line),
+            "vless" => Self::build_vless(&line),
+            "trojan" => Self::build_trojan(&line),
+            "http" | "https" => Self::build_http(&line),
+            "socks5" | "socks5-tls" => Self::build_socks(&line),
+            "snell" => Self::build_snell(&line),
+            "tuic" | "tuic-v5" => Self::build_tuic(&line),
+            "hysteria2" => Self::build_hysteria2(&line),
+            other => Err(format!("不支持的代理类型：{}", other)),
+        }
+    }
+
+    // 解析 Quantumult X 代理行
+    fn parse_quanx(line: &str) -> Result<JsonValue, String> {
+        let mut parts = line.split(',').map(|s| s.trim());
+
+        let (proxy_type, address) = parts
+            .next()
+            .and_then(|p| p.split_once('='))
+            .ok_or("不是有效的 Quantumult X 代理行")?;
+        let (server, port) = address.trim().rsplit_once(':').ok_or("缺少端口")?;
+        let port = port.parse::<i64>().map_err(|_| "端口解析失败")?;
+
+        let mut options = HashMap::new();
+        for part in parts {
+            if let Some((key, value)) = part.split_once('=') {
+                options.insert(key.trim().to_ascii_lowercase(), Self::unquote(value));
+            }
+        }
+
+        let name = options
+            .get("tag")
+            .cloned()
+            .unwrap_or_else(|| format!("{}:{}", server, port));
+
+        let line = ProxyLine {
+            name,
+            proxy_type: proxy_type.trim().to_ascii_lowercase(),
+            server: server.trim_matches(['[', ']']).to_string(),
+            port,
+            positional: Vec::new(),
+            options,
+        };
+
+        match line.proxy_type.as_str() {
+            "shadowsocks" if line.option("ssr-protocol").is_some() => {
+                Self::build_quanx_shadowsocksr(&line)
+            }
+            "shadowsocks" => Self::build_quanx_shadowsocks(&line),
+            "vmess" | "vless" | "trojan" => Self::build_quanx_v2ray(&line),
+            "http" | "socks5" => Self::build_quanx_http_socks(&line),
+            other => Err(format!("不支持的代理类型：{}", other)),
+        }
+    }
+
+    // Shadowsocks（Surge: encrypt-method/password；Loon: 位置参数 method, "password"）
+    fn build_shadowsocks(line: &ProxyLine) -> Result<JsonValue, String> {
+        let cipher = line
+            .option_or_positional("encrypt-method", 0)
+            .or_else(|| line.option("method"))
+            .ok_or("缺少加密方式")?;
+        let password = line.option_or_positional("password", 1).ok_or("缺少密码")?;
+
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "ss",
+            "server": line.server,
+            "port": line.port,
+            "cipher": cipher,
+            "password": password,
+            "udp": true,
+        });
+
+        // simple-obfs（Surge: obfs/obfs-host；Loon: obfs-name/obfs-host）
+        if let Some(mode) = line.option("obfs").or_else(|| line.option("obfs-name")) {
+            let mut opts = json!({ "mode": mode });
+            if let Some(host) = line.option("obfs-host") {
+                opts["host"] = json!(host);
+            }
+            proxy["plugin"] = json!("obfs");
+            proxy["plugin-opts"] = opts;
+        }
+
+        Ok(proxy)
+    }
+
+    // ShadowsocksR（Loon：位置参数 method, "password"）
+    fn build_shadowsocksr(line: &ProxyLine) -> Result<JsonValue, String> {
+        let cipher = line
+            .option_or_positional("method", 0)
+            .ok_or("缺少加密方式")?;
+        let password = line.option_or_positional("password", 1).ok_or("缺少密码")?;
+
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "ssr",
+            "server": line.server,
+            "port": line.port,
+            "cipher": cipher,
+            "password": password,
+            "protocol": line.option("protocol").unwrap_or("origin"),
+            "obfs": line.option("obfs").unwrap_or("plain"),
+            "udp": true,
+        });
+
+        if let Some(param) = line.option("protocol-param") {
+            proxy["protocol-param"] = json!(param);
+        }
+        if let Some(param) = line.option("obfs-param") {
+            proxy["obfs-param"] = json!(param);
+        }
+
+        Ok(proxy)
+    }
+
+    // VMess（Surge: username=uuid；Loon: 位置参数 method, "uuid"）
+    fn build_vmess(line: &ProxyLine) -> Result<JsonValue, String> {
+        let (cipher, uuid) = match line.option("username") {
+            Some(uuid) => ("auto", uuid),
+            None => (
+                line.positional
+                    .first()
+                    .map(|s| s.as_str())
+                    .unwrap_or("auto"),
+                line.positional.get(1).ok_or("缺少 UUID")?.as_str(),
+            ),
+        };
+
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "vmess",
+            "server": line.server,
+            "port": line.port,
+            "uuid": uuid,
+            "alterId": line.option("alterid").and_then(|s| s.parse::<i64>().ok()).unwrap_or(0),
+            "cipher": cipher,
+            "udp": true,
+            "network": "tcp",
+        });
+
+        Self::apply_tls_options(line, &mut proxy, "servername");
+        Self::apply_transport_options(line, &mut proxy);
+
+        Ok(proxy)
+    }
+
+    // VLESS（Loon：位置参数 "uuid"）
+    fn build_vless(line: &ProxyLine) -> Result<JsonValue, String> {
+        let uuid = line.option_or_positional("uuid", 0).ok_or("缺少 UUID")?;
+
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "vless",
+            "server": line.server,
+            "port": line.port,
+            "uuid": uuid,
+            "network": "tcp",
+            "udp": true,
+            "skip-cert-verify": false,
+        });
+
+        if let Some(flow) = line.option("flow") {
+            proxy["flow"] = json!(flow);
+        }
+        if let Some(public_key) = line.option("public-key") {
+            proxy["reality-opts"] = json!({
+                "public-key": public_key,
+                "short-id": line.option("short-id").unwrap_or_default(),
+            });
+            proxy["tls"] = json!(true);
+        }
+
+        Self::apply_tls_options(line, &mut proxy, "servername");
+        Self::apply_transport_options(line, &mut proxy);
+
+        Ok(proxy)
+    }
+
+    // Trojan（Surge: password=；Loon: 位置参数 "password"）
+    fn build_trojan(line: &ProxyLine) -> Result<JsonValue, String> {
+        let password = line.option_or_positional("password", 0).ok_or("缺少密码")?;
+
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "trojan",
+            "server": line.server,
+            "port": line.port,
+            "password": password,
+            "udp": true,
+            "skip-cert-verify": false,
+        });
+
+        Self::apply_tls_options(line, &mut proxy, "sni");
+        // Trojan 默认启用 TLS，无需 tls 字段
+        if let Some(map) = proxy.as_object_mut() {
+            map.remove("tls");
+        }
+        Self::apply_transport_options(line, &mut proxy);
+
+        Ok(proxy)
+    }
+
+    // HTTP/HTTPS（用户名与密码可为位置参数或键值参数）
+    fn build_http(line: &ProxyLine) -> Result<JsonValue, String> {
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "http",
+            "server": line.server,
+            "port": line.port,
+        });
+
+        Self::apply_credentials(line, &mut proxy);
+        if line.proxy_type == "https" || line.flag("over-tls") {
+            proxy["tls"] = json!(true);
+        }
+        Self::apply_tls_options(line, &mut proxy, "sni");
+
+        Ok(proxy)
+    }
+
+    // SOCKS5 / SOCKS5-TLS
+    fn build_socks(line: &ProxyLine) -> Result<JsonValue, String> {
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "socks5",
+            "server": line.server,
+            "port": line.port,
+            "udp": true,
+        });
+
+        Self::apply_credentials(line, &mut proxy);
+        if line.proxy_type == "socks5-tls" || line.flag("over-tls") {
+            proxy["tls"] = json!(true);
+        }
+        Self::apply_tls_options(line, &mut proxy, "sni");
+
+        Ok(proxy)
+    }
+
+    // Snell（Surge）
+    fn build_snell(line: &ProxyLine) -> Result<JsonValue, String> {
+        let psk = line.option("psk").ok_or("缺少 psk")?;
+
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "snell",
+            "server": line.server,
+            "port": line.port,
+            "psk": psk,
+        });
+
+        if let Some(version) = line.option("version").and_then(|v| v.parse::<i64>().ok()) {
+            proxy["version"] = json!(version);
+            if version >= 3 {
+                proxy["udp"] = json!(true);
+            }
+        }
+        if let Some(mode) = line.option("obfs") {
+            let mut opts = json!({ "mode": mode });
+            if let Some(host) = line.option("obfs-host") {
+                opts["host"] = json!(host);
+            }
+            proxy["obfs-opts"] = opts;
+        }
+
+        Ok(proxy)
+    }
+
+    // TUIC（Surge：tuic 使用 token，tuic-v5 使用 uuid/password）
+    fn build_tuic(line: &ProxyLine) -> Result<JsonValue, String> {
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "tuic",
+            "server": line.server,
+            "port": line.port,
+            "skip-cert-verify": line.flag("skip-cert-verify"),
+        });
+
+        if let Some(token) = line.option("token") {
+            proxy["token"] = json!(token);
+        } else {
+            proxy["uuid"] = json!(line.option("uuid").ok_or("缺少 UUID")?);
+            proxy["password"] = json!(line.option("password").unwrap_or_default());
+        }
+
+        if let Some(sni) = line.option("sni") {
+            proxy["sni"] = json!(sni);
+        }
+        if let Some(alpn) = line.option("alpn") {
+            proxy["alpn"] = json!(alpn.split(',').map(|s| s.trim()).collect::<Vec<_>>());
+        }
+
+        Ok(proxy)
+    }
+
+    // Hysteria2（Surge: password=；Loon: 位置参数 "password"）
+    fn build_hysteria2(line: &ProxyLine) -> Result<JsonValue, String> {
+        let password = line.option_or_positional("password", 0).ok_or("缺少密码")?;
+
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "hysteria2",
+            "server": line.server,
+            "port": line.port,
+            "password": password,
+            "skip-cert-verify": line.flag("skip-cert-verify"),
+        });
+
+        if let Some(sni) = line.option("sni").or_else(|| line.option("tls-name")) {
+            proxy["sni"] = json!(sni);
+        }
+        if let Some(down) = line
+            .option("download-bandwidth")
+            .and_then(|s| s.parse::<i64>().ok())
+        {
+            proxy["down"] = json!(down);
+        }
+
+        Ok(proxy)
+    }
+
+    // Quantumult X Shadowsocks
+    fn build_quanx_shadowsocks(line: &ProxyLine) -> Result<JsonValue, String> {
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "ss",
+            "server": line.server,
+            "port": line.port,
+            "cipher": line.option("method").ok_or("缺少加密方式")?,
+            "password": line.option("password").ok_or("缺少密码")?,
+            "udp": line.flag("udp-relay"),
+        });
+
+        match line.option("obfs") {
+            Some(mode @ ("http" | "tls")) => {
+                let mut opts = json!({ "mode": mode });
+                if let Some(host) = line.option("obfs-host") {
+                    opts["host"] = json!(host);
+                }
+                proxy["plugin"] = json!("obfs");
+                proxy["plugin-opts"] = opts;
+            }
+            Some(mode @ ("ws" | "wss")) => {
+                let mut opts = json!({
+                    "mode": "websocket",
+                    "path": line.option("obfs-uri").unwrap_or("/"),
+                });
+                if let Some(host) = line.option("obfs-host") {
+                    opts["host"] = json!(host);
+                }
+                if mode == "wss" {
+                    opts["tls"] = json!(true);
+                }
+                proxy["plugin"] = json!("v2ray-plugin");
+                proxy["plugin-opts"] = opts;
+            }
+            _ => {}
+        }
+
+        Ok(proxy)
+    }
+
+    // Quantumult X ShadowsocksR（shadowsocks 行带 ssr-protocol）
+    fn build_quanx_shadowsocksr(line: &ProxyLine) -> Result<JsonValue, String> {
+        let mut proxy = json!({
+            "name": line.name,
+            "type": "ssr",
+            "server": line.server,
+            "port": line.port,
+            "cipher": line.option("method").ok_or("缺少加密方式")?,
+            "password": line.option("password").ok_or("缺少密码")?,
+            "protocol": line.option("ssr-protocol").unwrap_or("origin"),
+            "obfs": line.option("obfs").unwrap_or("plain"),
+            "udp": line.flag("udp-relay"),
+        });
+
+        if let Some(param) = line.option("ssr-protocol-param") {
+            proxy["protocol-param"] = json!(param);
+        }
+        if let Some(host) = line.option("obfs-host") {
+            proxy["obfs-param"] = json!(host);
+        }
+
+        Ok(proxy)
+    }
+
+    // Quantumult X VMess/VLESS/Trojan（obfs 决定传输层与 TLS）
+    fn build_quanx_v2ray(line: &ProxyLine) -> Result<JsonValue, String> {
+        let password = line.option("password").ok_or("缺少密码")?;
+        let obfs = line.option("obfs").unwrap_or_default();
+        let is_tls = matches!(obfs, "wss" | "over-tls") || line.flag("over-tls");
+        let sni_key = if line.proxy_type == "trojan" {
+            "sni"
+        } else {
+            "servername"
+        };
+
+        let mut proxy = json!({
+            "name": line.name,
+            "type": line.proxy_type,
+            "server": line.server,
+            "port": line.port,
+            "udp": line.flag("udp-relay"),
+        });
+
+        match line.proxy_type.as_str() {
+            "vmess" => {
+                proxy["uuid"] = json!(password);
+                proxy["alterId"] = json!(0);
+                proxy["cipher"] = json!(line.option("method").unwrap_or("auto"));
+                proxy["network"] = json!("tcp");
+            }
+            "vless" => {
+                proxy["uuid"] = json!(password);
+                proxy["network"] = json!("tcp");
+            }
+            _ => proxy["password"] = json!(password),
+        }
+
+        if is_tls && line.proxy_type != "trojan" {
+            proxy["tls"] = json!(true);
+        }
+        if is_tls {
+            let host = line.option("tls-host").or_else(|| line.option("obfs-host"));
+            if let Some(host) = host {
+                proxy[sni_key] = json!(host);
+            }
+            proxy["skip-cert-verify"] = json!(line.option("tls-verification") == Some("false"));
+        }
+
+        match obfs {
+            "ws" | "wss" => {
+                let mut ws_opts = json!({ "path": line.option("obfs-uri").unwrap_or("/") });
+                if let Some(host) = line.option("obfs-host") {
+                    ws_opts["headers"] = json!({ "Host": host });
+                }
+                proxy["network"] = json!("ws");
+                proxy["ws-opts"] = ws_opts;
+            }
+            "http" => {
+                let mut http_opts = json!({ "path": [line.option("obfs-uri").unwrap_or("/")] });
+                if let Some(host) = line.option("obfs-host") {
+                    http_opts["headers"] = json!({ "Host": [host] });
+                }
+                proxy["network"] = json!("http");
+                proxy["http-opts"] = http_opts;
+            }
+            _ => {}
+        }
+
+        Ok(proxy)
+    }
+
+    // Quantumult X HTTP/SOCKS5
+    fn build_quanx_http_socks(line: &ProxyLine) -> Result<JsonValue, String> {
+        let proxy_type = if line.proxy_type == "http" {
+            "http"
+        } else {
+            "socks5"
+        };
+
+        let mut proxy = json!({
+            "name": line.name,
+            "type": proxy_type,
+            "server": line.server,
+            "port": line.port,
+        });
+
+        if let Some(username) = line.option("username") {
+            proxy["username"] = json!(username);
+        }
+        if let Some(password) = line.option("password") {
+            proxy["password"] = json!(password);
+        }
+        if line.flag("over-tls") {
+            proxy["tls"] = json!(true);
+            if let Some(host) = line.option("tls-host") {
+                proxy["sni"] = json!(host);
+            }
+            proxy["skip-cert-verify"] = json!(line.option("tls-verification") == Some("false"));
+        }
+
+        Ok(proxy)
+    }
+
+    // 应用用户名与密码（位置参数或键值参数）
+    fn apply_credentials(line: &ProxyLine, proxy: &mut JsonValue) {
+        if let Some(username) = line.option_or_positional("username", 0)
+            && !username.is_empty()
+        {
+            proxy["username"] = json!(username);
+        }
+        if let Some(password) = line.option_or_positional("password", 1)
+            && !password.is_empty()
+        {
+            proxy["password"] = json!(password);
+        }
+    }
+
+    // 应用 TLS 参数（Surge: tls/sni；Loon: over-tls/tls-name）
+    fn apply_tls_options(line: &ProxyLine, proxy: &mut JsonValue, sni_key: &str) {
+        if line.flag("tls") || line.flag("over-tls") {
+            proxy["tls"] = json!(true);
+        }
+        if let Some(sni) = line.option("sni").or_else(|| line.option("tls-name")) {
+            proxy[sni_key] = json!(sni);
+        }
+        if line.option("skip-cert-verify").is_some() {
+            proxy["skip-cert-verify"] = json!(line.flag("skip-cert-verify"));
+        }
+        if let Some(fingerprint) = line.option("client-fingerprint") {
+            proxy["client-fingerprint"] = json!(fingerprint);
+        }
+    }
+
+    // 应用传输层参数（Surge: ws/ws-path/ws-headers；Loon: transport/path/host）
+    fn apply_transport_options(line: &ProxyLine, proxy: &mut JsonValue) {
+        let transport = if line.flag("ws") {
+            "ws"
+        } else {
+            line.option("transport").unwrap_or("tcp")
+        };
+
+        match transport {
+            "ws" => {
+                let path = line
+                    .option("ws-path")
+                    .or_else(|| line.option("path"))
+                    .unwrap_or("/");
+                let mut ws_opts = json!({ "path": path });
+
+                if let Some(headers) = line.option("ws-headers") {
+                    // Surge：Host:example.com|User-Agent:xxx
+                    let mut map = serde_json::Map::new();
+                    for header in headers.split('|') {
+                        if let Some((key, value)) = header.split_once(':') {
+                            map.insert(key.trim().to_string(), json!(value.trim()));
+                        }
+                    }
+                    ws_opts["headers"] = JsonValue::Object(map);
+                } else if let Some(host) = line.option("host") {
+                    ws_opts["headers"] = json!({ "Host": host });
+                }
+
+                proxy["network"] = json!("ws");
+                proxy["ws-opts"] = ws_opts;
+            }
+            "http" => {
+                let mut http_opts = json!({ "path": [line.option("path").unwrap_or("/")] });
+                if let Some(host) = line.option("host") {
+                    http_opts["headers"] = json!({ "Host": [host] });
+                }
+                proxy["network"] = json!("http");
+                proxy["http-opts"] = http_opts;
+            }
+            "grpc" => {
+                proxy["network"] = json!("grpc");
+                proxy["grpc-opts"] = json!({
+                    "grpc-service-name": line.option("grpc-service-name").unwrap_or_default(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    // 去除值两端的引号
+    fn unquote(value: &str) -> String {
+        value.trim().trim_matches('"').to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surge_lines() -> Result<(), String> {
+        let ss = IosFormatParser::parse_line(
+            "🇯🇵 JP = ss, jp.example.com, 8388, encrypt-method=aes-128-gcm, password=pass, obfs=http, obfs-host=bing.com, udp-relay=true",
+        )?;
+        assert_eq!(
+            ss,
+            json!({
+                "name": "🇯🇵 JP", "type": "ss", "server": "jp.example.com", "port": 8388,
+                "cipher": "aes-128-gcm", "password": "pass", "udp": true,
+                "plugin": "obfs", "plugin-opts": {"mode": "http", "host": "bing.com"},
+            })
+        );
+
+        let vmess = IosFormatParser::parse_line(
+            "HK = vmess, hk.example.com, 443, username=b831381d-6324-4d53-ad4f-8cda48b30811, ws=true, ws-path=/v2, ws-headers=Host:cdn.example.com, tls=true, sni=hk.example.com",
+        )?;
+        assert_eq!(vmess["uuid"], "b831381d-6324-4d53-ad4f-8cda48b30811");
+        assert_eq!(vmess["network"], "ws");
+        assert_eq!(vmess["ws-opts"]["headers"]["Host"], "cdn.example.com");
+        assert_eq!(vmess["tls"], true);
+        assert_eq!(vmess["servername"], "hk.example.com");
+
+        let http = IosFormatParser::parse_line("Corp = https, proxy.example.com, 443, user, pass")?;
+        assert_eq!(http["username"], "user");
+        assert_eq!(http["password"], "pass");
+        assert_eq!(http["tls"], true);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_loon_lines() -> Result<(), String> {
+        let ss = IosFormatParser::parse_line(
+            r#"SG = Shadowsocks, sg.example.com, 443, chacha20-ietf-poly1305, "pass", obfs-name=tls, obfs-host=bing.com"#,
+        )?;
+        assert_eq!(ss["cipher"], "chacha20-ietf-poly1305");
+        assert_eq!(ss["password"], "pass");
+        assert_eq!(ss["plugin-opts"]["mode"], "tls");
+
+        let trojan = IosFormatParser::parse_line(
+            r#"US = trojan, us.example.com, 443, "secret", over-tls=true, tls-name=us.example.com, skip-cert-verify=true, transport=ws, path=/tj, host=us.example.com"#,
+        )?;
+        assert_eq!(
+            trojan,
+            json!({
+                "name": "US", "type": "trojan", "server": "us.example.com", "port": 443,
+                "password": "secret", "udp": true, "skip-cert-verify": true,
+                "sni": "us.example.com", "network": "ws",
+                "ws-opts": {"path": "/tj", "headers": {"Host": "us.example.com"}},
+            })
+        );
+
+        let vmess = IosFormatParser::parse_line(
+            r#"TW = vmess, tw.example.com, 80, aes-128-gcm, "b831381d-6324-4d53-ad4f-8cda48b30811", transport=tcp"#,
+        )?;
+        assert_eq!(vmess["cipher"], "aes-128-gcm");
+        assert_eq!(vmess["uuid"], "b831381d-6324-4d53-ad4f-8cda48b30811");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quanx_lines() -> Result<(), String> {
+        let ss = IosFormatParser::parse_line(
+            "shadowsocks=ss.example.com:443, method=chacha20-ietf-poly1305, password=pass, obfs=wss, obfs-host=cdn.example.com, obfs-uri=/ws, udp-relay=true, tag=QX SS",
+        )?;
+        assert_eq!(ss["name"], "QX SS");
+        assert_eq!(ss["plugin"], "v2ray-plugin");
+        assert_eq!(ss["plugin-opts"]["tls"], true);
+
+        let vmess = IosFormatParser::parse_line(
+            "vmess=vm.example.com:443, method=chacha20-poly1305, password=b831381d-6324-4d53-ad4f-8cda48b30811, obfs=wss, obfs-host=vm.example.com, obfs-uri=/ws, tls-verification=false, tag=QX VMess",
+        )?;
+        assert_eq!(
+            vmess,
+            json!({
+                "name": "QX VMess", "type": "vmess", "server": "vm.example.com", "port": 443,
+                "udp": false, "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811", "alterId": 0,
+                "cipher": "chacha20-poly1305", "network": "ws", "tls": true,
+                "servername": "vm.example.com", "skip-cert-verify": true,
+                "ws-opts": {"path": "/ws", "headers": {"Host": "vm.example.com"}},
+            })
+        );
+
+        assert!(IosFormatParser::is_proxy_line(
+            "trojan=tj.example.com:443, password=pass, over-tls=true, tag=TJ"
+        ));
+        assert!(!IosFormatParser::is_proxy_line("vless://uuid@host:443"));
+
+        Ok(())
+    }
+}
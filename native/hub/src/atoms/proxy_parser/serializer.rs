@@ -0,0 +1,565 @@
+// 代理节点序列化器：将 Clash 代理节点还原为标准分享链接。
+// 与解析器互为逆过程，输出可被 ProxyParser 重新解析。
+
+use super::shadowsocks::ShadowsocksParser;
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD as BASE64_URL},
+};
+use serde_json::{Value as JsonValue, json};
+
+// 代理节点序列化器
+pub struct ProxySerializer;
+
+impl ProxySerializer {
+    // 将单个 Clash 代理节点转换为分享链接。
+    pub fn to_link(proxy: &JsonValue) -> Result<String, String> {
+        let proxy_type = proxy["type"].as_str().ok_or("缺少 type 字段")?;
+
+        match proxy_type {
+            "vless" => Self::serialize_vless(proxy),
+            "vmess" => Self::serialize_vmess(proxy),
+            "ss" => Self::serialize_shadowsocks(proxy),
+            "trojan" => Self::serialize_trojan(proxy),
+            "hysteria2" => Self::serialize_hysteria2(proxy),
+            "tuic" => Self::serialize_tuic(proxy),
+            "socks5" => Self::serialize_socks(proxy),
+            other => Err(format!("不支持导出的协议：{}", other)),
+        }
+    }
+
+    // 批量转换代理节点，返回成功的链接与跳过的节点（名称、原因）。
+    pub fn to_links(proxies: &[JsonValue]) -> (Vec<String>, Vec<(String, String)>) {
+        let mut links = Vec::new();
+        let mut skipped = Vec::new();
+
+        for proxy in proxies {
+            match Self::to_link(proxy) {
+                Ok(link) => links.push(link),
+                Err(e) => {
+                    let name = proxy["name"].as_str().unwrap_or_default().to_string();
+                    log::warn!("跳过无法导出的节点：{} - {}", name, e);
+                    skipped.push((name, e));
+                }
+            }
+        }
+
+        (links, skipped)
+    }
+
+    // 将链接列表打包为 Base64 订阅内容（每行一个链接）。
+    pub fn to_base64_bundle(links: &[String]) -> String {
+        BASE64.encode(links.join("\n"))
+    }
+
+    // 序列化 VLESS 节点
+    fn serialize_vless(proxy: &JsonValue) -> Result<String, String> {
+        let uuid = Self::required_str(proxy, "uuid")?;
+        let params = Self::stream_params(proxy, "servername");
+
+        Ok(format!(
+            "vless://{}@{}?{}#{}",
+            Self::encode(uuid),
+            Self::authority(proxy)?,
+            Self::build_query(&params),
+            Self::encode(Self::name(proxy))
+        ))
+    }
+
+    // 序列化 VMess 节点（v2rayN JSON 格式）
+    fn serialize_vmess(proxy: &JsonValue) -> Result<String, String> {
+        let mut data = json!({
+            "v": "2",
+            "ps": Self::name(proxy),
+            "add": Self::required_str(proxy, "server")?,
+            "port": Self::port(proxy)?.to_string(),
+            "id": Self::required_str(proxy, "uuid")?,
+            "aid": proxy["alterId"].as_i64().unwrap_or(0).to_string(),
+            "scy": proxy["cipher"].as_str().unwrap_or("auto"),
+            "net": "tcp",
+            "type": "none",
+            "tls": "",
+        });
+
+        // 分享链接查询参数映射为 v2rayN 字段
+        for (key, value) in Self::stream_params(proxy, "servername") {
+            let field = match key {
+                "type" => "net",
+                "security" => "tls",
+                "headerType" => "type",
+                "serviceName" => "path",
+                other => other,
+            };
+            data[field] = json!(value);
+        }
+
+        let json_str =
+            serde_json::to_string(&data).map_err(|e| format!("JSON 序列化失败：{}", e))?;
+
+        Ok(format!("vmess://{}", BASE64.encode(json_str)))
+    }
+
+    // 序列化 Shadowsocks 节点（SIP002，认证部分 URL 安全 Base64 编码、无填充）
+    fn serialize_shadowsocks(proxy: &JsonValue) -> Result<String, String> {
+        let cipher = Self::required_str(proxy, "cipher")?;
+        let password = Self::required_str(proxy, "password")?;
+        let user_info = BASE64_URL.encode(format!("{}:{}", cipher, password));
+
+        // SIP002：携带插件时以 /?plugin= 追加
+        let mut params = Vec::new();
+        if let Some(plugin) = ShadowsocksParser::plugin_string(proxy)? {
+            params.push(("plugin", plugin));
+        }
+        let path = if params.is_empty() { "" } else { "/" };
+
+        Ok(format!(
+            "ss://{}@{}{}{}#{}",
+            user_info,
+            Self::authority(proxy)?,
+            path,
+            Self::query_suffix(&params),
+            Self::encode(Self::name(proxy))
+        ))
+    }
+
+    // 序列化 Trojan 节点
+    fn serialize_trojan(proxy: &JsonValue) -> Result<String, String> {
+        let password = Self::required_str(proxy, "password")?;
+        let params = Self::stream_params(proxy, "sni");
+
+        Ok(format!(
+            "trojan://{}@{}{}#{}",
+            Self::encode(password),
+            Self::authority(proxy)?,
+            Self::query_suffix(&params),
+            Self::encode(Self::name(proxy))
+        ))
+    }
+
+    // 序列化 Hysteria2 节点
+    fn serialize_hysteria2(proxy: &JsonValue) -> Result<String, String> {
+        let password = Self::required_str(proxy, "password")?;
+
+        let mut params = Vec::new();
+        Self::push_str(&mut params, "sni", &proxy["sni"]);
+        if proxy["skip-cert-verify"].as_bool() == Some(true) {
+            params.push(("insecure", "1".to_string()));
+        }
+        Self::push_str(&mut params, "obfs", &proxy["obfs"]);
+        Self::push_str(&mut params, "obfs-password", &proxy["obfs-password"]);
+
+        Ok(format!(
+            "hysteria2://{}@{}{}#{}",
+            Self::encode(password),
+            Self::authority(proxy)?,
+            Self::query_suffix(&params),
+            Self::encode(Self::name(proxy))
+        ))
+    }
+
+    // 序列化 TUIC 节点
+    fn serialize_tuic(proxy: &JsonValue) -> Result<String, String> {
+        let uuid = Self::required_str(proxy, "uuid")?;
+        let password = proxy["password"].as_str().unwrap_or("");
+
+        let mut params = Vec::new();
+        Self::push_str(&mut params, "sni", &proxy["sni"]);
+        if let Some(alpn) = Self::join_strs(&proxy["alpn"]) {
+            params.push(("alpn", alpn));
+        }
+        Self::push_str(
+            &mut params,
+            "congestion_control",
+            &proxy["congestion-control"],
+        );
+        if proxy["skip-cert-verify"].as_bool() == Some(true) {
+            params.push(("insecure", "1".to_string()));
+        }
+
+        Ok(format!(
+            "tuic://{}:{}@{}{}#{}",
+            Self::encode(uuid),
+            Self::encode(password),
+            Self::authority(proxy)?,
+            Self::query_suffix(&params),
+            Self::encode(Self::name(proxy))
+        ))
+    }
+
+    // 序列化 SOCKS5 节点
+    fn serialize_socks(proxy: &JsonValue) -> Result<String, String> {
+        let user_info = match (proxy["username"].as_str(), proxy["password"].as_str()) {
+            (Some(user), Some(pass)) => {
+                format!("{}:{}@", Self::encode(user), Self::encode(pass))
+            }
+            (Some(user), None) => format!("{}@", Self::encode(user)),
+            _ => String::new(),
+        };
+
+        Ok(format!(
+            "socks5://{}{}#{}",
+            user_info,
+            Self::authority(proxy)?,
+            Self::encode(Self::name(proxy))
+        ))
+    }
+
+    // 生成 TLS 与传输层查询参数（VLESS/VMess/Trojan 共用），与解析器参数矩阵对应
+    fn stream_params(proxy: &JsonValue, sni_key: &str) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        let is_trojan = proxy["type"] == "trojan";
+        let network = proxy["network"].as_str().unwrap_or("tcp");
+
+        // 传输层类型（Trojan 默认 tcp 时省略）
+        let link_type = match network {
+            "http" => "tcp",
+            "h2" => "http",
+            "ws" if proxy["ws-opts"]["v2ray-http-upgrade"].as_bool() == Some(true) => "httpupgrade",
+            other => other,
+        };
+        if !(is_trojan && link_type == "tcp" && network != "http") {
+            params.push(("type", link_type.to_string()));
+        }
+
+        // 安全层
+        if let Some(reality) = proxy.get("reality-opts") {
+            params.push(("security", "reality".to_string()));
+            Self::push_str(&mut params, "pbk", &reality["public-key"]);
+            Self::push_str(&mut params, "sid", &reality["short-id"]);
+        } else if !is_trojan && proxy["tls"].as_bool() == Some(true) {
+            params.push(("security", "tls".to_string()));
+        }
+
+        Self::push_str(&mut params, "sni", &proxy[sni_key]);
+        Self::push_str(&mut params, "fp", &proxy["client-fingerprint"]);
+        if let Some(alpn) = Self::join_strs(&proxy["alpn"]) {
+            params.push(("alpn", alpn));
+        }
+        if proxy["skip-cert-verify"].as_bool() == Some(true) {
+            params.push(("allowInsecure", "1".to_string()));
+        }
+        Self::push_str(&mut params, "ech", &proxy["ech-opts"]["config"]);
+        Self::push_str(&mut params, "flow", &proxy["flow"]);
+        Self::push_str(&mut params, "packetEncoding", &proxy["packet-encoding"]);
+
+        // 传输层参数
+        match network {
+            "http" => {
+                let http_opts = &proxy["http-opts"];
+                params.push(("headerType", "http".to_string()));
+                if let Some(path) = Self::join_strs(&http_opts["path"]) {
+                    params.push(("path", path));
+                }
+                if let Some(host) = Self::join_strs(&http_opts["headers"]["Host"]) {
+                    params.push(("host", host));
+                }
+            }
+            "ws" => {
+                let ws_opts = &proxy["ws-opts"];
+                let mut path = ws_opts["path"].as_str().unwrap_or("/").to_string();
+                if let Some(early_data) = ws_opts["max-early-data"].as_i64() {
+                    let separator = if path.contains('?') { '&' } else { '?' };
+                    path = format!("{}{}ed={}", path, separator, early_data);
+                } else if ws_opts["v2ray-http-upgrade-fast-open"].as_bool() == Some(true) {
+                    path = format!("{}?ed=2048", path);
+                }
+                params.push(("path", path));
+                Self::push_str(&mut params, "host", &ws_opts["headers"]["Host"]);
+            }
+            "h2" => {
+                let h2_opts = &proxy["h2-opts"];
+                Self::push_str(&mut params, "path", &h2_opts["path"]);
+                if let Some(host) = Self::join_strs(&h2_opts["host"]) {
+                    params.push(("host", host));
+                }
+            }
+            "grpc" => {
+                Self::push_str(
+                    &mut params,
+                    "serviceName",
+                    &proxy["grpc-opts"]["grpc-service-name"],
+                );
+            }
+            "xhttp" => {
+                let xhttp_opts = &proxy["xhttp-opts"];
+                Self::push_str(&mut params, "path", &xhttp_opts["path"]);
+                Self::push_str(&mut params, "host", &xhttp_opts["host"]);
+                Self::push_str(&mut params, "mode", &xhttp_opts["mode"]);
+            }
+            _ => {}
+        }
+
+        params
+    }
+
+    // 将字符串数组以逗号拼接，数组为空时返回 None
+    fn join_strs(value: &JsonValue) -> Option<String> {
+        let items: Vec<&str> = value
+            .as_array()?
+            .iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        if items.is_empty() {
+            None
+        } else {
+            Some(items.join(","))
+        }
+    }
+
+    // 字段为非空字符串时追加查询参数
+    fn push_str(params: &mut Vec<(&'static str, String)>, key: &'static str, value: &JsonValue) {
+        if let Some(s) = value.as_str()
+            && !s.is_empty()
+        {
+            params.push((key, s.to_string()));
+        }
+    }
+
+    // 构建查询字符串（值进行 URL 编码）
+    fn build_query(params: &[(&str, String)]) -> String {
+        params
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, Self::encode(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    // 构建带 `?` 前缀的查询字符串，参数为空时返回空串
+    fn query_suffix(params: &[(&str, String)]) -> String {
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("?{}", Self::build_query(params))
+        }
+    }
+
+    // 构建 server:port，IPv6 地址加方括号
+    fn authority(proxy: &JsonValue) -> Result<String, String> {
+        let server = Self::required_str(proxy, "server")?;
+        let port = Self::port(proxy)?;
+
+        if server.contains(':') && !server.starts_with('[') {
+            Ok(format!("[{}]:{}", server, port))
+        } else {
+            Ok(format!("{}:{}", server, port))
+        }
+    }
+
+    // 读取端口（兼容数字与字符串）
+    fn port(proxy: &JsonValue) -> Result<u16, String> {
+        match &proxy["port"] {
+            JsonValue::Number(n) => n
+                .as_u64()
+                .and_then(|p| u16::try_from(p).ok())
+                .ok_or_else(|| "端口无效".to_string()),
+            JsonValue::String(s) => s.parse::<u16>().map_err(|_| "端口无效".to_string()),
+            _ => Err("缺少端口".to_string()),
+        }
+    }
+
+    // 读取必需的字符串字段
+    fn required_str<'a>(proxy: &'a JsonValue, key: &str) -> Result<&'a str, String> {
+        proxy[key]
+            .as_str()
+            .ok_or_else(|| format!("缺少 {} 字段", key))
+    }
+
+    // 读取节点名称
+    fn name(proxy: &JsonValue) -> &str {
+        proxy["name"].as_str().unwrap_or_default()
+    }
+
+    // URL 编码
+    fn encode(s: &str) -> String {
+        urlencoding::encode(s).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atoms::proxy_parser::ProxyParser;
+
+    // 序列化后重新解析，结果应与原节点一致
+    fn assert_round_trip(proxy: JsonValue) -> Result<(), String> {
+        let link = ProxySerializer::to_link(&proxy)?;
+        let parsed = ProxyParser::parse_single_proxy(&link)?;
+        assert_eq!(parsed, proxy, "链接：{}", link);
+        Ok(())
+    }
+
+    #[test]
+    fn test_vless_round_trip() -> Result<(), String> {
+        assert_round_trip(json!({
+            "name": "🇭🇰 香港 01",
+            "type": "vless",
+            "server": "hk.example.com",
+            "port": 443,
+            "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811",
+            "network": "ws",
+            "udp": true,
+            "skip-cert-verify": false,
+            "tls": true,
+            "servername": "hk.example.com",
+            "ws-opts": {
+                "path": "/ray",
+                "headers": {"Host": "cdn.example.com"},
+                "max-early-data": 2048,
+                "early-data-header-name": "Sec-WebSocket-Protocol",
+            },
+        }))?;
+
+        assert_round_trip(json!({
+            "name": "Reality",
+            "type": "vless",
+            "server": "1.2.3.4",
+            "port": 8443,
+            "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811",
+            "network": "tcp",
+            "udp": true,
+            "skip-cert-verify": false,
+            "tls": true,
+            "servername": "www.microsoft.com",
+            "flow": "xtls-rprx-vision",
+            "reality-opts": {"public-key": "Z84J2IelR9ch3k8VtlVhhs5ycBUlXA7wHBWcBrjqnAw", "short-id": "6ba85179e30d4fc2"},
+        }))
+    }
+
+    #[test]
+    fn test_vmess_round_trip() -> Result<(), String> {
+        assert_round_trip(json!({
+            "name": "VMess gRPC",
+            "type": "vmess",
+            "server": "vm.example.com",
+            "port": 2053,
+            "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811",
+            "alterId": 0,
+            "cipher": "auto",
+            "udp": true,
+            "network": "grpc",
+            "tls": true,
+            "servername": "vm.example.com",
+            "grpc-opts": {"grpc-service-name": "gun"},
+        }))
+    }
+
+    #[test]
+    fn test_shadowsocks_round_trip() -> Result<(), String> {
+        assert_round_trip(json!({
+            "name": "SS 日本",
+            "type": "ss",
+            "server": "jp.example.com",
+            "port": 8388,
+            "cipher": "2022-blake3-aes-128-gcm",
+            "password": "p@ss:word/+=",
+            "udp": true,
+            "plugin": "v2ray-plugin",
+            "plugin-opts": {"mode": "websocket", "tls": true, "host": "cdn.example.com", "path": "/ws;v2"},
+        }))
+    }
+
+    #[test]
+    fn test_shadowsocks_url_safe_user_info() -> Result<(), String> {
+        // 标准 Base64 分别为 "YWVzLTEyOC1nY206Pj4+Pz8/" 与 "YWVzLTEyOC1nY206Pj4+Pz8="
+        for password in [">>>???", ">>>??"] {
+            let proxy = json!({
+                "name": "SS",
+                "type": "ss",
+                "server": "1.2.3.4",
+                "port": 8388,
+                "cipher": "aes-128-gcm",
+                "password": password,
+                "udp": true,
+            });
+            let link = ProxySerializer::to_link(&proxy)?;
+            let user_info = link
+                .strip_prefix("ss://")
+                .and_then(|rest| rest.split_once('@'))
+                .map(|(user_info, _)| user_info)
+                .ok_or("链接缺少认证部分")?;
+            assert!(!user_info.contains(['+', '/', '=']), "链接：{}", link);
+            assert_round_trip(proxy)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_trojan_round_trip() -> Result<(), String> {
+        assert_round_trip(json!({
+            "name": "Trojan WS",
+            "type": "trojan",
+            "server": "tj.example.com",
+            "port": 443,
+            "password": "pass#word?&",
+            "udp": true,
+            "skip-cert-verify": true,
+            "sni": "tj.example.com",
+            "network": "ws",
+            "ws-opts": {"path": "/trojan", "headers": {"Host": "tj.example.com"}},
+        }))
+    }
+
+    #[test]
+    fn test_hysteria2_round_trip() -> Result<(), String> {
+        assert_round_trip(json!({
+            "name": "HY2",
+            "type": "hysteria2",
+            "server": "hy.example.com",
+            "port": 8443,
+            "password": "secret",
+            "skip-cert-verify": false,
+            "sni": "hy.example.com",
+            "obfs": "salamander",
+            "obfs-password": "obfs pass",
+        }))
+    }
+
+    #[test]
+    fn test_tuic_round_trip() -> Result<(), String> {
+        assert_round_trip(json!({
+            "name": "TUIC",
+            "type": "tuic",
+            "server": "tuic.example.com",
+            "port": 443,
+            "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811",
+            "password": "secret",
+            "skip-cert-verify": true,
+            "sni": "tuic.example.com",
+            "alpn": ["h3", "h2"],
+            "congestion-control": "bbr",
+        }))
+    }
+
+    #[test]
+    fn test_socks_round_trip() -> Result<(), String> {
+        assert_round_trip(json!({
+            "name": "SOCKS",
+            "type": "socks5",
+            "server": "10.0.0.1",
+            "port": 1080,
+            "udp": true,
+            "username": "user",
+            "password": "p:ss",
+        }))
+    }
+
+    #[test]
+    fn test_base64_bundle_round_trip() -> Result<(), String> {
+        let proxies = vec![
+            json!({"name": "A", "type": "ss", "server": "a.example.com", "port": 8388, "cipher": "aes-128-gcm", "password": "pa", "udp": true}),
+            json!({"name": "B", "type": "socks5", "server": "b.example.com", "port": 1080, "udp": true}),
+            json!({"name": "C", "type": "snell", "server": "c.example.com", "port": 443}),
+        ];
+
+        let (links, skipped) = ProxySerializer::to_links(&proxies);
+        assert_eq!(links.len(), 2);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0, "C");
+
+        let bundle = ProxySerializer::to_base64_bundle(&links);
+        let config = ProxyParser::parse_subscription(&bundle)?;
+        let parsed = ProxyParser::parse_yaml_json_proxies(&config)?;
+        assert_eq!(parsed, proxies[..2]);
+        Ok(())
+    }
+}
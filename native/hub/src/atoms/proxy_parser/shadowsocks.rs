@@ -0,0 +1,373 @@
+// Shadowsocks 扩展格式：SIP003 插件参数与 SIP008 JSON 订阅文档。
+// 插件字符串格式为 `插件名;键=值;标志`，值中的 `;`、`=`、`\` 以反斜杠转义。
+
+use super::parser::ProxyParser;
+use crate::atoms::shared_types::RejectedEntry;
+use serde_json::{Map, Value as JsonValue, json};
+
+// Shadowsocks 扩展格式解析器
+pub struct ShadowsocksParser;
+
+impl ShadowsocksParser {
+    // 判断是否为 SIP008 订阅文档（顶层 JSON 对象包含 servers 数组）
+    pub fn is_sip008_document(content: &str) -> bool {
+        let trimmed = content.trim_start();
+        if !trimmed.starts_with('{') {
+            return false;
+        }
+
+        match serde_json::from_str::<JsonValue>(trimmed) {
+            Ok(value) => value["servers"].is_array(),
+            Err(_) => false,
+        }
+    }
+
+    // 解析 SIP008 文档中的所有服务器。
+    // 返回节点、服务器总数与被跳过的服务器（序号从 1 开始）。
+    pub fn parse_sip008(
+        content: &str,
+    ) -> Result<(Vec<JsonValue>, usize, Vec<RejectedEntry>), String> {
+        let value: JsonValue =
+            serde_json::from_str(content).map_err(|e| format!("JSON 解析失败：{}", e))?;
+        let servers = value["servers"]
+            .as_array()
+            .ok_or("SIP008 文档缺少 servers 数组")?;
+
+        let mut proxies = Vec::new();
+        let mut rejected = Vec::new();
+
+        for (index, server) in servers.iter().enumerate() {
+            match Self::convert_sip008_server(server) {
+                Ok(proxy) => proxies.push(proxy),
+                Err(e) => rejected.push(ProxyParser::rejected_entry(
+                    index + 1,
+                    "ss",
+                    &server.to_string(),
+                    e,
+                )),
+            }
+        }
+
+        Ok((proxies, servers.len(), rejected))
+    }
+
+    // 转换单个 SIP008 服务器
+    fn convert_sip008_server(server: &JsonValue) -> Result<JsonValue, String> {
+        let host = server["server"].as_str().ok_or("缺少 server 字段")?;
+        let port = server["server_port"]
+            .as_i64()
+            .or_else(|| server["server_port"].as_str()?.parse().ok())
+            .ok_or("缺少 server_port 字段")?;
+        let method = server["method"].as_str().ok_or("缺少 method 字段")?;
+        let password = server["password"].as_str().ok_or("缺少 password 字段")?;
+
+        let name = server["remarks"]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .unwrap_or(host);
+
+        let mut proxy = json!({
+            "name": name,
+            "type": "ss",
+            "server": host,
+            "port": port,
+            "cipher": method,
+            "password": password,
+            "udp": true,
+        });
+
+        if let Some(plugin) = server["plugin"].as_str().filter(|s| !s.is_empty()) {
+            let opts = server["plugin_opts"].as_str().unwrap_or_default();
+            Self::apply_plugin(&mut proxy, plugin, opts)?;
+        }
+
+        Ok(proxy)
+    }
+
+    // 解析 SIP002 链接中的 plugin 参数（插件名与选项以 `;` 连接）
+    pub fn apply_plugin_param(proxy: &mut JsonValue, value: &str) -> Result<(), String> {
+        let (plugin, opts) = value.split_once(';').unwrap_or((value, ""));
+        if plugin.is_empty() {
+            return Ok(());
+        }
+        Self::apply_plugin(proxy, plugin, opts)
+    }
+
+    // 将 SIP003 插件名与选项映射为 mihomo 的 plugin / plugin-opts
+    pub fn apply_plugin(proxy: &mut JsonValue, plugin: &str, opts: &str) -> Result<(), String> {
+        let options = Self::split_plugin_opts(opts);
+        let get = |key: &str| {
+            options
+                .iter()
+                .find(|(k, _)| k == key)
+                .and_then(|(_, v)| v.clone())
+        };
+        let has_flag = |key: &str| options.iter().any(|(k, v)| k == key && v.is_none());
+
+        let mut plugin_opts = Map::new();
+        let plugin_name = match plugin {
+            "obfs-local" | "simple-obfs" | "obfs" => {
+                plugin_opts.insert(
+                    "mode".to_string(),
+                    json!(get("obfs").unwrap_or_else(|| "http".to_string())),
+                );
+                if let Some(host) = get("obfs-host") {
+                    plugin_opts.insert("host".to_string(), json!(host));
+                }
+                "obfs"
+            }
+            "v2ray-plugin" | "gost-plugin" => {
+                let mode = get("mode").unwrap_or_else(|| "websocket".to_string());
+                if mode != "websocket" {
+                    return Err(format!("不支持的 {} 模式：{}", plugin, mode));
+                }
+                plugin_opts.insert("mode".to_string(), json!(mode));
+                plugin_opts.insert("tls".to_string(), json!(has_flag("tls")));
+                if let Some(host) = get("host") {
+                    plugin_opts.insert("host".to_string(), json!(host));
+                }
+                if let Some(path) = get("path") {
+                    plugin_opts.insert("path".to_string(), json!(path));
+                }
+                if let Some(mux) = get("mux") {
+                    plugin_opts.insert("mux".to_string(), json!(mux != "0" && mux != "false"));
+                } else if has_flag("mux") {
+                    plugin_opts.insert("mux".to_string(), json!(true));
+                }
+                if has_flag("skip-cert-verify") || has_flag("insecure") {
+                    plugin_opts.insert("skip-cert-verify".to_string(), json!(true));
+                }
+                plugin
+            }
+            "shadow-tls" => {
+                let host = get("host").ok_or("shadow-tls 插件缺少 host 参数")?;
+                plugin_opts.insert("host".to_string(), json!(host));
+                if let Some(password) = get("password") {
+                    plugin_opts.insert("password".to_string(), json!(password));
+                }
+                if let Some(version) = get("version").and_then(|v| v.parse::<i64>().ok()) {
+                    plugin_opts.insert("version".to_string(), json!(version));
+                }
+                "shadow-tls"
+            }
+            "restls" => {
+                let host = get("host").ok_or("restls 插件缺少 host 参数")?;
+                let password = get("password").ok_or("restls 插件缺少 password 参数")?;
+                plugin_opts.insert("host".to_string(), json!(host));
+                plugin_opts.insert("password".to_string(), json!(password));
+                plugin_opts.insert(
+                    "version-hint".to_string(),
+                    json!(get("version-hint").unwrap_or_else(|| "tls13".to_string())),
+                );
+                if let Some(script) = get("restls-script") {
+                    plugin_opts.insert("restls-script".to_string(), json!(script));
+                }
+                "restls"
+            }
+            _ => return Err(format!("不支持的 SS 插件：{}", plugin)),
+        };
+
+        proxy["plugin"] = json!(plugin_name);
+        proxy["plugin-opts"] = JsonValue::Object(plugin_opts);
+        Ok(())
+    }
+
+    // 将 mihomo 的 plugin / plugin-opts 还原为 SIP003 插件字符串，无插件时返回 None
+    pub fn plugin_string(proxy: &JsonValue) -> Result<Option<String>, String> {
+        let Some(plugin) = proxy["plugin"].as_str() else {
+            return Ok(None);
+        };
+        let opts = &proxy["plugin-opts"];
+
+        let mut parts: Vec<String> = Vec::new();
+        let opt = |key: &str, opt_key: &str| {
+            let value = match &opts[opt_key] {
+                JsonValue::String(s) => s.clone(),
+                JsonValue::Number(n) => n.to_string(),
+                _ => return None,
+            };
+            Some(format!("{}={}", key, Self::escape_plugin_value(&value)))
+        };
+
+        let plugin_name = match plugin {
+            "obfs" => {
+                parts.extend(opt("obfs", "mode"));
+                parts.extend(opt("obfs-host", "host"));
+                "obfs-local"
+            }
+            "v2ray-plugin" | "gost-plugin" => {
+                parts.extend(opt("mode", "mode"));
+                parts.extend(opt("host", "host"));
+                parts.extend(opt("path", "path"));
+                if opts["tls"].as_bool() == Some(true) {
+                    parts.push("tls".to_string());
+                }
+                if opts["mux"].as_bool() == Some(true) {
+                    parts.push("mux=1".to_string());
+                }
+                if opts["skip-cert-verify"].as_bool() == Some(true) {
+                    parts.push("skip-cert-verify".to_string());
+                }
+                plugin
+            }
+            "shadow-tls" => {
+                parts.extend(opt("host", "host"));
+                parts.extend(opt("password", "password"));
+                parts.extend(opt("version", "version"));
+                plugin
+            }
+            "restls" => {
+                parts.extend(opt("host", "host"));
+                parts.extend(opt("password", "password"));
+                parts.extend(opt("version-hint", "version-hint"));
+                parts.extend(opt("restls-script", "restls-script"));
+                plugin
+            }
+            _ => return Err(format!("不支持导出的 SS 插件：{}", plugin)),
+        };
+
+        parts.insert(0, plugin_name.to_string());
+        Ok(Some(parts.join(";")))
+    }
+
+    // 按 SIP003 规则拆分插件选项，返回 (键, 值) 列表；无值的项为标志
+    fn split_plugin_opts(opts: &str) -> Vec<(String, Option<String>)> {
+        let mut items = Vec::new();
+        let mut current = String::new();
+        let mut chars = opts.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    // 保留转义标记，便于区分键值分隔符
+                    current.push('\\');
+                    if let Some(next) = chars.next() {
+                        current.push(next);
+                    }
+                }
+                ';' => items.push(std::mem::take(&mut current)),
+                _ => current.push(c),
+            }
+        }
+        items.push(current);
+
+        items
+            .into_iter()
+            .filter(|item| !item.is_empty())
+            .map(|item| match Self::find_unescaped(&item, '=') {
+                Some(pos) => (
+                    Self::unescape_plugin_value(&item[..pos]),
+                    Some(Self::unescape_plugin_value(&item[pos + 1..])),
+                ),
+                None => (Self::unescape_plugin_value(&item), None),
+            })
+            .collect()
+    }
+
+    // 查找第一个未被转义的字符位置
+    fn find_unescaped(s: &str, target: char) -> Option<usize> {
+        let mut is_escaped = false;
+        for (pos, c) in s.char_indices() {
+            if is_escaped {
+                is_escaped = false;
+            } else if c == '\\' {
+                is_escaped = true;
+            } else if c == target {
+                return Some(pos);
+            }
+        }
+        None
+    }
+
+    // 去除反斜杠转义
+    fn unescape_plugin_value(s: &str) -> String {
+        let mut result = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                if let Some(next) = chars.next() {
+                    result.push(next);
+                }
+            } else {
+                result.push(c);
+            }
+        }
+        result
+    }
+
+    // 转义插件选项值中的特殊字符
+    fn escape_plugin_value(s: &str) -> String {
+        let mut result = String::with_capacity(s.len());
+        for c in s.chars() {
+            if matches!(c, '\\' | ';' | '=') {
+                result.push('\\');
+            }
+            result.push(c);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugin_options() -> Result<(), String> {
+        let cases = [
+            (
+                "obfs-local;obfs=tls;obfs-host=www.bing.com",
+                json!({"plugin": "obfs", "plugin-opts": {"mode": "tls", "host": "www.bing.com"}}),
+            ),
+            (
+                "v2ray-plugin;mode=websocket;tls;host=cdn.example.com;path=/ws;mux=0",
+                json!({"plugin": "v2ray-plugin", "plugin-opts": {
+                    "mode": "websocket", "tls": true, "host": "cdn.example.com", "path": "/ws", "mux": false
+                }}),
+            ),
+            (
+                "shadow-tls;host=cloud.tencent.com;password=sh\\;tls;version=3",
+                json!({"plugin": "shadow-tls", "plugin-opts": {
+                    "host": "cloud.tencent.com", "password": "sh;tls", "version": 3
+                }}),
+            ),
+            (
+                "restls;host=www.microsoft.com;password=pw;version-hint=tls12",
+                json!({"plugin": "restls", "plugin-opts": {
+                    "host": "www.microsoft.com", "password": "pw", "version-hint": "tls12"
+                }}),
+            ),
+        ];
+
+        for (value, expected) in cases {
+            let mut proxy = json!({});
+            ShadowsocksParser::apply_plugin_param(&mut proxy, value)?;
+            assert_eq!(proxy, expected, "{}", value);
+        }
+
+        let mut proxy = json!({});
+        assert!(ShadowsocksParser::apply_plugin_param(&mut proxy, "kcptun;mode=fast").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sip008_document() -> Result<(), String> {
+        let content = r#"{
+            "version": 1,
+            "servers": [
+                {"id": "1", "remarks": "🇭🇰 HK", "server": "hk.example.com", "server_port": 8388,
+                 "password": "pw", "method": "chacha20-ietf-poly1305",
+                 "plugin": "obfs-local", "plugin_opts": "obfs=http;obfs-host=bing.com"},
+                {"id": "2", "server": "bad.example.com", "password": "pw"}
+            ],
+            "bytes_used": 1024
+        }"#;
+
+        assert!(ShadowsocksParser::is_sip008_document(content));
+        let (proxies, total, rejected) = ShadowsocksParser::parse_sip008(content)?;
+        assert_eq!((proxies.len(), total, rejected.len()), (1, 2, 1));
+        assert_eq!(proxies[0]["name"], "🇭🇰 HK");
+        assert_eq!(proxies[0]["plugin-opts"]["host"], "bing.com");
+        Ok(())
+    }
+}
@@ -0,0 +1,569 @@
+// sing-box 订阅解析：将 sing-box JSON 出站（outbounds/endpoints）转换为 Clash 代理节点。
+// 仅转换代理类出站，direct/block/dns/selector 等内置出站会被忽略。
+
+use super::parser::ProxyParser;
+use super::shadowsocks::ShadowsocksParser;
+use crate::atoms::shared_types::RejectedEntry;
+use serde_json::{Map, Value as JsonValue, json};
+
+// 不属于代理节点的 sing-box 出站类型
+const NON_PROXY_TYPES: &[&str] = &["direct", "block", "dns", "selector", "urltest"];
+
+// sing-box 配置解析器
+pub struct SingBoxParser;
+
+impl SingBoxParser {
+    // 判断是否为 sing-box 配置（顶层 JSON 对象包含 outbounds 或 endpoints 数组）
+    pub fn is_singbox_config(content: &str) -> bool {
+        let trimmed = content.trim_start();
+        if !trimmed.starts_with('{') {
+            return false;
+        }
+
+        match serde_json::from_str::<JsonValue>(trimmed) {
+            Ok(value) => value["outbounds"].is_array() || value["endpoints"].is_array(),
+            Err(_) => false,
+        }
+    }
+
+    // 解析 sing-box 配置中的所有代理出站。
+    // 返回节点、参与转换的出站数与被跳过的出站（序号从 1 开始）。
+    pub fn parse_outbounds(
+        content: &str,
+    ) -> Result<(Vec<JsonValue>, usize, Vec<RejectedEntry>), String> {
+        let value: JsonValue =
+            serde_json::from_str(content).map_err(|e| format!("JSON 解析失败：{}", e))?;
+
+        let outbounds = value["outbounds"].as_array().into_iter().flatten();
+        let endpoints = value["endpoints"].as_array().into_iter().flatten();
+
+        let mut proxies = Vec::new();
+        let mut rejected = Vec::new();
+        let mut total = 0;
+        for (index, outbound) in outbounds.chain(endpoints).enumerate() {
+            let outbound_type = outbound["type"].as_str().unwrap_or_default();
+            if NON_PROXY_TYPES.contains(&outbound_type) {
+                continue;
+            }
+
+            total += 1;
+            match Self::convert_outbound(outbound) {
+                Ok(proxy) => proxies.push(proxy),
+                Err(e) => {
+                    let tag = outbound["tag"].as_str().unwrap_or_default();
+                    rejected.push(ProxyParser::rejected_entry(
+                        index + 1,
+                        outbound_type,
+                        tag,
+                        e,
+                    ));
+                }
+            }
+        }
+
+        Ok((proxies, total, rejected))
+    }
+
+    // 转换单个出站
+    fn convert_outbound(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let outbound_type = outbound["type"].as_str().ok_or("缺少 type 字段")?;
+
+        match outbound_type {
+            "vless" => Self::convert_vless(outbound),
+            "vmess" => Self::convert_vmess(outbound),
+            "trojan" => Self::convert_trojan(outbound),
+            "shadowsocks" => Self::convert_shadowsocks(outbound),
+            "hysteria2" => Self::convert_hysteria2(outbound),
+            "tuic" => Self::convert_tuic(outbound),
+            "wireguard" => Self::convert_wireguard(outbound),
+            "socks" => Self::convert_socks(outbound),
+            "http" => Self::convert_http(outbound),
+            other => Err(format!("不支持的出站类型：{}", other)),
+        }
+    }
+
+    // 转换 VLESS 出站
+    fn convert_vless(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::base_proxy(outbound, "vless")?;
+        proxy["uuid"] = json!(Self::required_str(outbound, "uuid")?);
+        proxy["udp"] = json!(true);
+
+        Self::copy_str(outbound, "flow", &mut proxy, "flow");
+        Self::copy_str(outbound, "packet_encoding", &mut proxy, "packet-encoding");
+        Self::apply_tls(outbound, &mut proxy, "servername");
+        Self::apply_transport(outbound, &mut proxy);
+
+        Ok(proxy)
+    }
+
+    // 转换 VMess 出站
+    fn convert_vmess(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::base_proxy(outbound, "vmess")?;
+        proxy["uuid"] = json!(Self::required_str(outbound, "uuid")?);
+        proxy["alterId"] = json!(outbound["alter_id"].as_i64().unwrap_or(0));
+        proxy["cipher"] = json!(outbound["security"].as_str().unwrap_or("auto"));
+        proxy["udp"] = json!(true);
+
+        if outbound["global_padding"].as_bool() == Some(true) {
+            proxy["global-padding"] = json!(true);
+        }
+        if outbound["authenticated_length"].as_bool() == Some(true) {
+            proxy["authenticated-length"] = json!(true);
+        }
+        Self::copy_str(outbound, "packet_encoding", &mut proxy, "packet-encoding");
+        Self::apply_tls(outbound, &mut proxy, "servername");
+        Self::apply_transport(outbound, &mut proxy);
+
+        Ok(proxy)
+    }
+
+    // 转换 Trojan 出站
+    fn convert_trojan(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::base_proxy(outbound, "trojan")?;
+        proxy["password"] = json!(Self::required_str(outbound, "password")?);
+        proxy["udp"] = json!(true);
+
+        Self::apply_tls(outbound, &mut proxy, "sni");
+        // Trojan 在 Clash 中默认启用 TLS，无需 tls 字段
+        if let Some(map) = proxy.as_object_mut() {
+            map.remove("tls");
+        }
+        Self::apply_transport(outbound, &mut proxy);
+
+        Ok(proxy)
+    }
+
+    // 转换 Shadowsocks 出站
+    fn convert_shadowsocks(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::base_proxy(outbound, "ss")?;
+        proxy["cipher"] = json!(Self::required_str(outbound, "method")?);
+        proxy["password"] = json!(Self::required_str(outbound, "password")?);
+        proxy["udp"] = json!(outbound["network"].as_str() != Some("tcp"));
+
+        if outbound["udp_over_tcp"].as_bool() == Some(true)
+            || outbound["udp_over_tcp"]["enabled"].as_bool() == Some(true)
+        {
+            proxy["udp-over-tcp"] = json!(true);
+        }
+
+        // SIP003 插件（obfs-local / v2ray-plugin）
+        if let Some(plugin) = outbound["plugin"].as_str().filter(|s| !s.is_empty()) {
+            let opts = outbound["plugin_opts"].as_str().unwrap_or_default();
+            ShadowsocksParser::apply_plugin(&mut proxy, plugin, opts)?;
+        }
+
+        Ok(proxy)
+    }
+
+    // 转换 Hysteria2 出站
+    fn convert_hysteria2(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::base_proxy(outbound, "hysteria2")?;
+        proxy["password"] = json!(outbound["password"].as_str().unwrap_or_default());
+
+        if let Some(ports) = outbound["server_ports"].as_array() {
+            let ports: Vec<String> = ports
+                .iter()
+                .filter_map(|p| p.as_str())
+                .map(|p| p.replace(':', "-"))
+                .collect();
+            if !ports.is_empty() {
+                proxy["ports"] = json!(ports.join(","));
+            }
+        }
+        if let Some(up) = outbound["up_mbps"].as_i64() {
+            proxy["up"] = json!(up);
+        }
+        if let Some(down) = outbound["down_mbps"].as_i64() {
+            proxy["down"] = json!(down);
+        }
+        if let Some(obfs_type) = outbound["obfs"]["type"].as_str() {
+            proxy["obfs"] = json!(obfs_type);
+            Self::copy_str(&outbound["obfs"], "password", &mut proxy, "obfs-password");
+        }
+
+        Self::apply_tls(outbound, &mut proxy, "sni");
+        if let Some(map) = proxy.as_object_mut() {
+            map.remove("tls");
+        }
+
+        Ok(proxy)
+    }
+
+    // 转换 TUIC 出站
+    fn convert_tuic(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::base_proxy(outbound, "tuic")?;
+        proxy["uuid"] = json!(Self::required_str(outbound, "uuid")?);
+        proxy["password"] = json!(outbound["password"].as_str().unwrap_or_default());
+
+        Self::copy_str(
+            outbound,
+            "congestion_control",
+            &mut proxy,
+            "congestion-control",
+        );
+        Self::copy_str(outbound, "udp_relay_mode", &mut proxy, "udp-relay-mode");
+        if outbound["zero_rtt_handshake"].as_bool() == Some(true) {
+            proxy["reduce-rtt"] = json!(true);
+        }
+
+        Self::apply_tls(outbound, &mut proxy, "sni");
+        if let Some(map) = proxy.as_object_mut() {
+            map.remove("tls");
+        }
+
+        Ok(proxy)
+    }
+
+    // 转换 WireGuard 出站（兼容旧版 outbound 与 1.11+ endpoint 格式）
+    fn convert_wireguard(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let name = Self::required_str(outbound, "tag")?;
+        let mut proxy = json!({
+            "name": name,
+            "type": "wireguard",
+            "private-key": Self::required_str(outbound, "private_key")?,
+            "udp": true,
+        });
+
+        // 本地地址：旧版为 local_address，新版为 address
+        let addresses = outbound["address"]
+            .as_array()
+            .or_else(|| outbound["local_address"].as_array());
+        for address in addresses.into_iter().flatten().filter_map(|a| a.as_str()) {
+            let ip = address.split('/').next().unwrap_or(address);
+            let key = if ip.contains(':') { "ipv6" } else { "ip" };
+            if proxy.get(key).is_none() {
+                proxy[key] = json!(ip);
+            }
+        }
+
+        if let Some(mtu) = outbound["mtu"].as_i64() {
+            proxy["mtu"] = json!(mtu);
+        }
+
+        let peers: Vec<JsonValue> = match outbound["peers"].as_array() {
+            Some(peers) => peers.iter().map(Self::convert_wireguard_peer).collect(),
+            None => vec![Self::convert_wireguard_peer(outbound)],
+        };
+
+        match peers.as_slice() {
+            [] => return Err("缺少 WireGuard 对端".to_string()),
+            [peer] => {
+                // 单对端：展开到节点顶层
+                if let (Some(map), Some(peer_map)) = (proxy.as_object_mut(), peer.as_object()) {
+                    for (key, value) in peer_map {
+                        map.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            _ => proxy["peers"] = json!(peers),
+        }
+
+        if proxy["server"].as_str().unwrap_or_default().is_empty() {
+            proxy["server"] = peers[0]["server"].clone();
+            proxy["port"] = peers[0]["port"].clone();
+        }
+        if proxy["server"].is_null() {
+            return Err("缺少服务器地址".to_string());
+        }
+
+        Ok(proxy)
+    }
+
+    // 转换 WireGuard 对端
+    fn convert_wireguard_peer(peer: &JsonValue) -> JsonValue {
+        let mut result = Map::new();
+
+        let server = peer["address"].as_str().or_else(|| peer["server"].as_str());
+        if let Some(server) = server {
+            result.insert("server".to_string(), json!(server));
+        }
+        let port = peer["port"]
+            .as_i64()
+            .or_else(|| peer["server_port"].as_i64());
+        if let Some(port) = port {
+            result.insert("port".to_string(), json!(port));
+        }
+
+        let public_key = peer["public_key"]
+            .as_str()
+            .or_else(|| peer["peer_public_key"].as_str());
+        if let Some(public_key) = public_key {
+            result.insert("public-key".to_string(), json!(public_key));
+        }
+        if let Some(psk) = peer["pre_shared_key"].as_str() {
+            result.insert("pre-shared-key".to_string(), json!(psk));
+        }
+        if let Some(reserved) = peer["reserved"].as_array() {
+            result.insert("reserved".to_string(), json!(reserved));
+        }
+        if let Some(allowed_ips) = peer["allowed_ips"].as_array() {
+            result.insert("allowed-ips".to_string(), json!(allowed_ips));
+        }
+
+        JsonValue::Object(result)
+    }
+
+    // 转换 SOCKS 出站（仅支持 SOCKS5）
+    fn convert_socks(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let version = outbound["version"].as_str().unwrap_or("5");
+        if version != "5" {
+            return Err(format!("不支持的 SOCKS 版本：{}", version));
+        }
+
+        let mut proxy = Self::base_proxy(outbound, "socks5")?;
+        proxy["udp"] = json!(outbound["network"].as_str() != Some("tcp"));
+        Self::copy_str(outbound, "username", &mut proxy, "username");
+        Self::copy_str(outbound, "password", &mut proxy, "password");
+
+        Ok(proxy)
+    }
+
+    // 转换 HTTP 出站
+    fn convert_http(outbound: &JsonValue) -> Result<JsonValue, String> {
+        let mut proxy = Self::base_proxy(outbound, "http")?;
+        Self::copy_str(outbound, "username", &mut proxy, "username");
+        Self::copy_str(outbound, "password", &mut proxy, "password");
+        if let Some(headers) = outbound["headers"].as_object() {
+            proxy["headers"] = JsonValue::Object(headers.clone());
+        }
+        Self::apply_tls(outbound, &mut proxy, "sni");
+
+        Ok(proxy)
+    }
+
+    // 构建通用字段：name、type、server、port
+    fn base_proxy(outbound: &JsonValue, proxy_type: &str) -> Result<JsonValue, String> {
+        let name = Self::required_str(outbound, "tag")?;
+        let server = Self::required_str(outbound, "server")?;
+        let port = outbound["server_port"]
+            .as_i64()
+            .or_else(|| {
+                // 配置了端口跳跃时 server_port 可省略
+                outbound["server_ports"].as_array().map(|_| 443)
+            })
+            .ok_or("缺少 server_port 字段")?;
+
+        Ok(json!({
+            "name": name,
+            "type": proxy_type,
+            "server": server,
+            "port": port,
+        }))
+    }
+
+    // 应用 TLS 配置（含 uTLS 指纹与 Reality）
+    fn apply_tls(outbound: &JsonValue, proxy: &mut JsonValue, sni_key: &str) {
+        let tls = &outbound["tls"];
+        if tls["enabled"].as_bool() != Some(true) {
+            return;
+        }
+
+        proxy["tls"] = json!(true);
+        proxy["skip-cert-verify"] = json!(tls["insecure"].as_bool().unwrap_or(false));
+        Self::copy_str(tls, "server_name", proxy, sni_key);
+
+        if let Some(alpn) = tls["alpn"].as_array() {
+            proxy["alpn"] = json!(alpn);
+        } else if let Some(alpn) = tls["alpn"].as_str() {
+            proxy["alpn"] = json!([alpn]);
+        }
+
+        if tls["utls"]["enabled"].as_bool() == Some(true) {
+            proxy["client-fingerprint"] =
+                json!(tls["utls"]["fingerprint"].as_str().unwrap_or("chrome"));
+        }
+
+        let reality = &tls["reality"];
+        if reality["enabled"].as_bool() == Some(true) {
+            proxy["reality-opts"] = json!({
+                "public-key": reality["public_key"].as_str().unwrap_or_default(),
+                "short-id": reality["short_id"].as_str().unwrap_or_default(),
+            });
+        }
+    }
+
+    // 应用传输层配置（ws / grpc / http / httpupgrade）
+    fn apply_transport(outbound: &JsonValue, proxy: &mut JsonValue) {
+        let transport = &outbound["transport"];
+        let transport_type = transport["type"].as_str().unwrap_or_default();
+
+        match transport_type {
+            "ws" | "httpupgrade" => {
+                let mut ws_opts = json!({
+                    "path": transport["path"].as_str().unwrap_or("/"),
+                });
+                if let Some(headers) = transport["headers"].as_object() {
+                    ws_opts["headers"] = Self::flatten_headers(headers);
+                }
+                if transport_type == "httpupgrade" {
+                    if let Some(host) = transport["host"].as_str() {
+                        ws_opts["headers"]["Host"] = json!(host);
+                    }
+                    ws_opts["v2ray-http-upgrade"] = json!(true);
+                } else {
+                    if let Some(max_early_data) = transport["max_early_data"].as_i64() {
+                        ws_opts["max-early-data"] = json!(max_early_data);
+                    }
+                    Self::copy_str(
+                        transport,
+                        "early_data_header_name",
+                        &mut ws_opts,
+                        "early-data-header-name",
+                    );
+                }
+                proxy["network"] = json!("ws");
+                proxy["ws-opts"] = ws_opts;
+            }
+            "grpc" => {
+                proxy["network"] = json!("grpc");
+                proxy["grpc-opts"] = json!({
+                    "grpc-service-name": transport["service_name"].as_str().unwrap_or_default(),
+                });
+            }
+            "http" => {
+                let hosts = match &transport["host"] {
+                    JsonValue::Array(hosts) => hosts.clone(),
+                    JsonValue::String(host) => vec![json!(host)],
+                    _ => vec![],
+                };
+                let path = transport["path"].as_str().unwrap_or("/");
+
+                // sing-box 的 http 传输在启用 TLS 时即为 HTTP/2
+                if proxy["tls"].as_bool() == Some(true) {
+                    proxy["network"] = json!("h2");
+                    proxy["h2-opts"] = json!({
+                        "host": hosts,
+                        "path": path,
+                    });
+                } else {
+                    let mut http_opts = json!({
+                        "method": transport["method"].as_str().unwrap_or("GET"),
+                        "path": [path],
+                    });
+                    if !hosts.is_empty() {
+                        http_opts["headers"] = json!({"Host": hosts});
+                    }
+                    proxy["network"] = json!("http");
+                    proxy["http-opts"] = http_opts;
+                }
+            }
+            _ => {
+                if proxy["type"] == "vless" || proxy["type"] == "vmess" {
+                    proxy["network"] = json!("tcp");
+                }
+            }
+        }
+    }
+
+    // 将 sing-box 的多值请求头展开为单值（取第一个值）
+    fn flatten_headers(headers: &Map<String, JsonValue>) -> JsonValue {
+        let mut result = Map::new();
+        for (key, value) in headers {
+            let value = match value {
+                JsonValue::Array(values) => values.first().cloned().unwrap_or_default(),
+                other => other.clone(),
+            };
+            result.insert(key.clone(), value);
+        }
+        JsonValue::Object(result)
+    }
+
+    // 复制非空字符串字段
+    fn copy_str(source: &JsonValue, from: &str, target: &mut JsonValue, to: &str) {
+        if let Some(value) = source[from].as_str()
+            && !value.is_empty()
+        {
+            target[to] = json!(value);
+        }
+    }
+
+    // 读取必需的字符串字段
+    fn required_str<'a>(value: &'a JsonValue, key: &str) -> Result<&'a str, String> {
+        value[key]
+            .as_str()
+            .ok_or_else(|| format!("缺少 {} 字段", key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "outbounds": [
+            {"type": "selector", "tag": "proxy", "outbounds": ["hk", "jp"]},
+            {
+                "type": "vless", "tag": "hk", "server": "hk.example.com", "server_port": 443,
+                "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811", "flow": "xtls-rprx-vision",
+                "tls": {
+                    "enabled": true, "server_name": "www.microsoft.com",
+                    "utls": {"enabled": true, "fingerprint": "chrome"},
+                    "reality": {"enabled": true, "public_key": "pbk", "short_id": "abcd"}
+                }
+            },
+            {
+                "type": "vmess", "tag": "jp", "server": "jp.example.com", "server_port": 8080,
+                "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811", "security": "auto",
+                "transport": {"type": "ws", "path": "/ws", "headers": {"Host": "cdn.example.com"}}
+            },
+            {
+                "type": "hysteria2", "tag": "us", "server": "us.example.com", "server_port": 8443,
+                "password": "secret", "obfs": {"type": "salamander", "password": "obfs"},
+                "tls": {"enabled": true, "server_name": "us.example.com", "insecure": true}
+            },
+            {"type": "direct", "tag": "direct"}
+        ],
+        "endpoints": [
+            {
+                "type": "wireguard", "tag": "wg", "address": ["172.16.0.2/32", "fd01::2/128"],
+                "private_key": "priv", "mtu": 1280,
+                "peers": [{
+                    "address": "162.159.192.1", "port": 2408, "public_key": "pub",
+                    "allowed_ips": ["0.0.0.0/0"], "reserved": [1, 2, 3]
+                }]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_detect_singbox_config() {
+        assert!(SingBoxParser::is_singbox_config(SAMPLE));
+        assert!(!SingBoxParser::is_singbox_config("proxies: []"));
+        assert!(!SingBoxParser::is_singbox_config(r#"{"servers": []}"#));
+    }
+
+    #[test]
+    fn test_parse_outbounds() -> Result<(), String> {
+        let (proxies, total, rejected) = SingBoxParser::parse_outbounds(SAMPLE)?;
+        assert_eq!(proxies.len(), 4);
+        assert_eq!(total, 4);
+        assert!(rejected.is_empty());
+
+        assert_eq!(
+            proxies[0],
+            json!({
+                "name": "hk", "type": "vless", "server": "hk.example.com", "port": 443,
+                "uuid": "b831381d-6324-4d53-ad4f-8cda48b30811", "udp": true,
+                "flow": "xtls-rprx-vision", "network": "tcp",
+                "tls": true, "skip-cert-verify": false, "servername": "www.microsoft.com",
+                "client-fingerprint": "chrome",
+                "reality-opts": {"public-key": "pbk", "short-id": "abcd"},
+            })
+        );
+        assert_eq!(proxies[1]["network"], "ws");
+        assert_eq!(proxies[1]["ws-opts"]["headers"]["Host"], "cdn.example.com");
+        assert_eq!(proxies[2]["obfs-password"], "obfs");
+        assert_eq!(proxies[2]["skip-cert-verify"], true);
+        assert_eq!(proxies[2].get("tls"), None);
+
+        let wg = &proxies[3];
+        assert_eq!(wg["server"], "162.159.192.1");
+        assert_eq!(wg["port"], 2408);
+        assert_eq!(wg["ip"], "172.16.0.2");
+        assert_eq!(wg["ipv6"], "fd01::2");
+        assert_eq!(wg["reserved"], json!([1, 2, 3]));
+        assert_eq!(wg["allowed-ips"], json!(["0.0.0.0/0"]));
+
+        Ok(())
+    }
+}
@@ -9,6 +9,8 @@ use serde::{Deserialize, Serialize};
 pub enum OverrideFormat {
     Yaml = 0,
     Javascript = 1,
+    JsonPatch = 2,  // RFC 6902
+    MergePatch = 3, // RFC 7386
 }
 
 // 覆写配置
@@ -18,4 +20,168 @@ pub struct OverrideConfig {
     pub name: String,
     pub format: OverrideFormat,
     pub content: String,
+    pub arguments: Option<String>, // 脚本参数（JSON 对象），注入为 $arguments 与 main 的第二个参数
+    pub conditions: Option<OverrideConditions>, // 匹配条件，不满足时跳过该覆写
+}
+
+// 覆写匹配条件（各项同时满足时应用覆写，未设置的项不参与判断）
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone, Default)]
+pub struct OverrideConditions {
+    pub subscription_pattern: Option<String>, // 匹配订阅 ID 或订阅链接的正则表达式
+    pub platforms: Vec<String>,               // 操作系统：windows / linux / macos / android
+    pub runtime_flags: Vec<RuntimeFlagCondition>,
+    pub min_core_version: Option<String>, // 最低核心版本，如 v1.19.0
+}
+
+// 运行时开关
+#[derive(Deserialize, Serialize, SignalPiece, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuntimeFlag {
+    TunEnabled = 0,
+    Ipv6Enabled = 1,
+    AllowLanEnabled = 2,
+    DnsOverrideEnabled = 3,
+    KeepAliveEnabled = 4,
+    TcpConcurrentEnabled = 5,
+    UnifiedDelayEnabled = 6,
+}
+
+// 运行时开关条件
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone)]
+pub struct RuntimeFlagCondition {
+    pub flag: RuntimeFlag,
+    pub is_enabled: bool, // 要求的开关状态
+}
+
+// 评估匹配条件所需的上下文
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone, Default)]
+pub struct OverrideMatchContext {
+    pub subscription_id: Option<String>,
+    pub subscription_url: Option<String>,
+    pub core_version: Option<String>,
+    pub runtime_flags: Option<Vec<RuntimeFlag>>, // 已开启的运行时开关，None 表示未知
+}
+
+// 被跳过的覆写
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone)]
+pub struct SkippedOverride {
+    pub override_id: String,
+    pub override_name: String,
+    pub reason: String,
+}
+
+// 订阅解析选项（仅作用于由节点列表生成的配置，完整 Clash YAML 原样保留）
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone, Default)]
+pub struct SubscriptionParseOptions {
+    pub is_region_grouping_enabled: bool, // 按地区自动生成 url-test 代理组
+    pub is_info_node_excluded: bool,      // 排除流量、到期时间等信息节点
+    pub exclude_patterns: Vec<String>,    // 自定义排除规则（正则或关键字）
+}
+
+// 订阅解析报告
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone, Default)]
+pub struct ParseReport {
+    pub source_format: String, // 订阅格式：clash_yaml / yaml_proxies / sip008 / singbox / links
+    pub total_lines: u32,      // 参与解析的行（或出站）总数
+    pub accepted_count: u32,
+    pub excluded_count: u32, // 被排除规则过滤掉的节点数
+    pub protocol_counts: Vec<ProtocolCount>,
+    pub rejected_entries: Vec<RejectedEntry>,
+}
+
+// 各协议的节点数量
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone)]
+pub struct ProtocolCount {
+    pub protocol: String,
+    pub count: u32,
+}
+
+// 被跳过的条目
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone)]
+pub struct RejectedEntry {
+    pub line_number: u32, // 行号（sing-box 为出站序号），从 1 开始
+    pub protocol: String,
+    pub reason: String,
+    pub preview: String, // 内容预览（最多 50 个字符）
+}
+
+// 待聚合的订阅来源
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone)]
+pub struct SubscriptionSource {
+    pub label: String,   // 来源标签，用作节点名前缀与来源代理组名
+    pub content: String, // 订阅内容
+}
+
+// 单个订阅来源的聚合结果
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone)]
+pub struct AggregatedSourceReport {
+    pub label: String, // 去重后的来源标签
+    pub is_successful: bool,
+    pub report: Option<ParseReport>,
+    pub error_message: Option<String>,
+}
+
+// 两个订阅版本之间的差异
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone, Default)]
+pub struct ProfileDiff {
+    pub has_changes: bool,
+    pub added_nodes: Vec<String>,
+    pub removed_nodes: Vec<String>,
+    pub renamed_nodes: Vec<RenamedNode>, // 服务器、端口与凭据相同，仅名称不同
+    pub modified_nodes: Vec<ModifiedNode>, // 同名节点的参数变化
+    pub unchanged_node_count: u32,
+    pub added_groups: Vec<String>,
+    pub removed_groups: Vec<String>,
+    pub modified_groups: Vec<ModifiedGroup>,
+    pub old_rule_count: u32,
+    pub new_rule_count: u32,
+    pub added_rule_count: u32,   // 新版本中新增的规则条数
+    pub removed_rule_count: u32, // 旧版本中被移除的规则条数
+}
+
+// 改名的节点
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone)]
+pub struct RenamedNode {
+    pub old_name: String,
+    pub new_name: String,
+    pub changed_fields: Vec<String>, // 除名称外同时变化的字段
+}
+
+// 参数变化的节点
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone)]
+pub struct ModifiedNode {
+    pub name: String,
+    pub changed_fields: Vec<String>,
+}
+
+// 成员或类型变化的代理组
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone)]
+pub struct ModifiedGroup {
+    pub name: String,
+    pub is_type_changed: bool,
+    pub added_members: Vec<String>,
+    pub removed_members: Vec<String>,
+}
+
+// 配置中节点、代理组与规则的数量
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone, Default)]
+pub struct ConfigCounts {
+    pub proxy_count: u32,
+    pub group_count: u32,
+    pub rule_count: u32,
+}
+
+// 覆写追踪：单个覆写的耗时、结果与配置变化
+#[derive(Debug, Deserialize, Serialize, SignalPiece, Clone, Default)]
+pub struct OverrideTraceStep {
+    pub override_id: String,
+    pub override_name: String,
+    pub elapsed_micros: u64,
+    pub is_successful: bool,
+    pub is_skipped: bool, // 覆写未生效但流程继续（如 JSON Patch 前置条件不满足）
+    pub error_message: Option<String>, // 失败原因或跳过原因
+    pub added_keys: Vec<String>, // 新增的键路径（以“.”连接）
+    pub removed_keys: Vec<String>, // 删除的键路径
+    pub changed_keys: Vec<String>, // 值发生变化的键路径（数组整体比较）
+    pub counts_before: ConfigCounts,
+    pub counts_after: ConfigCounts,
 }
@@ -5,10 +5,14 @@ pub mod clash_network;
 pub mod clash_process;
 pub mod core_update;
 pub mod delay_testing;
+pub mod http_client;
 pub mod overrides;
 pub mod shared_types;
 pub mod subscription;
 pub mod system_operations;
 
 // 导出共享类型，方便其他分子使用
-pub use shared_types::{OverrideConfig, OverrideFormat, ProxyMode};
+pub use shared_types::{
+    DownloadRetryPolicy, HttpRequestOptions, OverrideConfig, OverrideFormat, OverrideIntegrity,
+    ProxyMode,
+};
@@ -2,10 +2,12 @@
 
 pub mod generator;
 pub mod injector;
+pub mod provider_converter;
 pub mod runtime_params;
 
 pub use generator::{GenerateRuntimeConfigRequest, GenerateRuntimeConfigResponse};
 pub use injector::inject_runtime_params;
+pub use provider_converter::{ProviderModeParams, convert_to_provider_mode};
 pub use runtime_params::RuntimeConfigParams;
 
 pub fn init_listeners() {
@@ -4,9 +4,11 @@
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
 
+use super::provider_converter::{ProviderModeParams, RuleProviderFile, convert_to_provider_mode};
 use super::runtime_params::RuntimeConfigParams;
 use crate::atoms::OverrideProcessor;
 use crate::molecules::OverrideConfig;
+use crate::molecules::shared_types::{OverrideMatchContext, OverrideTraceStep, SkippedOverride};
 
 // Dart → Rust：生成运行时配置请求
 #[derive(Debug, Clone, Serialize, Deserialize, DartSignal)]
@@ -19,6 +21,15 @@ pub struct GenerateRuntimeConfigRequest {
 
     // 运行时参数
     pub runtime_params: RuntimeConfigParams,
+
+    // 提供者模式参数（为空时节点内联在配置中）
+    pub provider_mode: Option<ProviderModeParams>,
+
+    // 记录每个覆写的耗时与配置变化
+    pub is_trace_enabled: bool,
+
+    // 覆写匹配条件的上下文（运行时开关由运行时参数填充）
+    pub match_context: Option<OverrideMatchContext>,
 }
 
 // Rust → Dart：生成运行时配置响应
@@ -27,6 +38,9 @@ pub struct GenerateRuntimeConfigResponse {
     pub is_successful: bool,
     pub result_config: String,
     pub error_message: String,
+    pub override_logs: Vec<String>, // 覆写脚本的 console 输出
+    pub override_trace: Vec<OverrideTraceStep>, // 覆写追踪（开启追踪时提供）
+    pub skipped_overrides: Vec<SkippedOverride>, // 被跳过的覆写及原因
 }
 
 impl GenerateRuntimeConfigRequest {
@@ -35,15 +49,46 @@ impl GenerateRuntimeConfigRequest {
         log::debug!("覆写数量：{}", self.overrides.len());
         log::debug!("运行时参数：{:?}", self.runtime_params);
 
-        match generate_runtime_config_internal(
+        let mut processor = match OverrideProcessor::new() {
+            Ok(processor) => processor,
+            Err(e) => {
+                log::error!("初始化覆写处理器失败：{}", e);
+                return GenerateRuntimeConfigResponse {
+                    is_successful: false,
+                    result_config: String::new(),
+                    error_message: format!("初始化覆写处理器失败：{}", e),
+                    override_logs: Vec::new(),
+                    override_trace: Vec::new(),
+                    skipped_overrides: Vec::new(),
+                };
+            }
+        };
+        if self.is_trace_enabled {
+            processor.enable_trace();
+        }
+        let mut match_context = self.match_context.clone().unwrap_or_default();
+        match_context.runtime_flags = Some(self.runtime_params.enabled_flags());
+        processor.set_match_context(match_context);
+
+        let result = generate_runtime_config_internal(
+            &mut processor,
             &self.base_config_content,
             &self.overrides,
             &self.runtime_params,
-        ) {
+            self.provider_mode.as_ref(),
+        );
+        let override_logs = processor.take_logs();
+        let override_trace = processor.take_trace();
+        let skipped_overrides = processor.take_skipped();
+
+        match result {
             Ok(config) => GenerateRuntimeConfigResponse {
                 is_successful: true,
                 result_config: config,
                 error_message: String::new(),
+                override_logs,
+                override_trace,
+                skipped_overrides,
             },
             Err(e) => {
                 log::error!("生成运行时配置失败：{}", e);
@@ -51,40 +96,68 @@ impl GenerateRuntimeConfigRequest {
                     is_successful: false,
                     result_config: String::new(),
                     error_message: e,
+                    override_logs,
+                    override_trace,
+                    skipped_overrides,
                 }
             }
         }
     }
 }
 
-// 内部处理函数：应用覆写 + 注入运行时参数
+// 内部处理函数：应用覆写 + 提供者模式转换 + 注入运行时参数。
+// 覆写脚本的输出与追踪记录保留在 processor 中（失败时同样可取出）。
 fn generate_runtime_config_internal(
+    processor: &mut OverrideProcessor,
     base_content: &str,
     overrides: &[OverrideConfig],
     params: &RuntimeConfigParams,
+    provider_mode: Option<&ProviderModeParams>,
 ) -> Result<String, String> {
     // 1. 应用覆写
     let config_after_override = if overrides.is_empty() {
         base_content.to_string()
     } else {
         log::info!("应用 {} 个覆写…", overrides.len());
-
-        // 创建覆写处理器
-        let mut processor =
-            OverrideProcessor::new().map_err(|e| format!("初始化覆写处理器失败：{}", e))?;
-
         processor.apply_overrides(base_content, overrides.to_vec())?
     };
 
-    // 2. 注入运行时参数
+    // 2. 转换为提供者模式
+    let config_after_override = match provider_mode {
+        Some(provider_params) => {
+            let (config, rule_files) =
+                convert_to_provider_mode(&config_after_override, base_content, provider_params)?;
+            write_rule_provider_files(&provider_params.rule_providers_dir, &rule_files)?;
+            config
+        }
+        None => config_after_override,
+    };
+
+    // 3. 注入运行时参数
     let final_config = super::injector::inject_runtime_params(&config_after_override, params)?;
 
-    // 3. 输出配置摘要（调试用）
+    // 4. 输出配置摘要（调试用）
     log_config_summary(&final_config);
 
     Ok(final_config)
 }
 
+// 写入提取出的 rule-provider 文件
+fn write_rule_provider_files(dir: &str, files: &[RuleProviderFile]) -> Result<(), String> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(dir).map_err(|e| format!("创建规则目录失败：{}", e))?;
+    for file in files {
+        std::fs::write(&file.path, &file.content)
+            .map_err(|e| format!("写入规则文件失败（{}）：{}", file.path, e))?;
+    }
+
+    log::info!("已写入 {} 个 rule-provider 文件", files.len());
+    Ok(())
+}
+
 // 输出配置摘要到日志
 fn log_config_summary(config_yaml: &str) {
     match serde_yaml_ng::from_str::<serde_yaml_ng::Value>(config_yaml) {
@@ -143,7 +216,8 @@ pub fn init() {
         let receiver = GenerateRuntimeConfigRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
             let message = dart_signal.message;
-            tokio::spawn(async move {
+            // 覆写脚本可能长时间运行，放到阻塞线程池中执行，避免阻塞异步运行时
+            tokio::task::spawn_blocking(move || {
                 let response = message.handle();
                 response.send_signal_to_dart();
             });
@@ -0,0 +1,442 @@
+// Clash 配置提供者模式转换器
+// 将与订阅文件一致的内联节点改为引用订阅文件的 proxy-providers，并把大段域名/IP 规则提取为
+// rule-providers，以便通过 /providers/proxies/{name} 刷新节点而无需重载整个配置。
+// 覆写新增或修改过的节点不在订阅文件中，保留为内联节点。
+// 限制：只包含部分订阅节点的代理组使用按节点名生成的 filter，成员固定为转换时的节点名；
+// 通过提供者刷新新增的节点只会出现在包含全部节点的组中，重新生成配置后才会更新。
+
+use regex::escape as regex_escape;
+use rinf::SignalPiece;
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::{Mapping, Value as YamlValue};
+use std::collections::HashSet;
+
+// 可提取到 rule-providers 的规则类型
+const EXTRACTABLE_RULE_TYPES: &[&str] = &[
+    "DOMAIN",
+    "DOMAIN-SUFFIX",
+    "DOMAIN-KEYWORD",
+    "IP-CIDR",
+    "IP-CIDR6",
+];
+
+// 提供者模式参数
+#[derive(Debug, Clone, Serialize, Deserialize, SignalPiece)]
+pub struct ProviderModeParams {
+    pub provider_name: String, // proxy-providers 条目名，即刷新接口中的 {name}
+    pub subscription_path: String, // 已保存的订阅文件路径
+    pub rule_providers_dir: String, // rule-providers 文件的输出目录
+    pub rule_set_min_size: u32, // 连续同策略规则达到该数量时提取，0 表示不提取
+}
+
+// 需要写入磁盘的 rule-provider 文件
+pub struct RuleProviderFile {
+    pub path: String,
+    pub content: String,
+}
+
+// 将配置转换为提供者模式，返回新配置与需要写入的规则文件。
+// subscription_content 为订阅文件内容（应用覆写前），用于判断哪些节点可由提供者加载。
+pub fn convert_to_provider_mode(
+    yaml_content: &str,
+    subscription_content: &str,
+    params: &ProviderModeParams,
+) -> Result<(String, Vec<RuleProviderFile>), String> {
+    let mut config: YamlValue =
+        serde_yaml_ng::from_str(yaml_content).map_err(|e| format!("解析配置失败：{}", e))?;
+    let subscription_proxies = subscription_proxies(subscription_content);
+
+    let config_map = config
+        .as_mapping_mut()
+        .ok_or_else(|| "配置根节点必须是 Map".to_string())?;
+
+    // 1. 与订阅文件一致的内联节点改为 proxy-providers
+    let proxy_names = take_provider_proxies(config_map, &subscription_proxies);
+    log::info!(
+        "提供者模式：{}个内联节点移入 proxy-provider「{}」，{}个覆写后的节点保留内联",
+        proxy_names.len(),
+        params.provider_name,
+        config_map
+            .get(YamlValue::from("proxies"))
+            .and_then(|v| v.as_sequence())
+            .map_or(0, |seq| seq.len())
+    );
+    if !proxy_names.is_empty() {
+        insert_proxy_provider(config_map, params);
+        rewrite_proxy_groups(
+            config_map,
+            &proxy_names,
+            subscription_proxies.len(),
+            &params.provider_name,
+        );
+    }
+
+    // 2. 大段规则提取为 rule-providers
+    let rule_files = if params.rule_set_min_size > 0 {
+        extract_rule_providers(config_map, params)?
+    } else {
+        Vec::new()
+    };
+
+    let result = serde_yaml_ng::to_string(&config).map_err(|e| format!("序列化配置失败：{}", e))?;
+
+    Ok((result, rule_files))
+}
+
+// 订阅文件中的节点，文件无法解析时视为没有节点（全部保留内联）
+fn subscription_proxies(subscription_content: &str) -> Vec<YamlValue> {
+    serde_yaml_ng::from_str::<YamlValue>(subscription_content)
+        .ok()
+        .and_then(|config| config.get("proxies").and_then(|v| v.as_sequence()).cloned())
+        .unwrap_or_default()
+}
+
+// 移除与订阅文件完全一致的内联节点，返回其名称列表（保持原有顺序）；
+// 其余节点（覆写新增或修改的）留在 proxies 中
+fn take_provider_proxies(
+    config_map: &mut Mapping,
+    subscription_proxies: &[YamlValue],
+) -> Vec<String> {
+    let Some(proxies) = config_map
+        .get_mut(YamlValue::from("proxies"))
+        .and_then(|v| v.as_sequence_mut())
+    else {
+        return Vec::new();
+    };
+
+    let (moved, inline): (Vec<YamlValue>, Vec<YamlValue>) = std::mem::take(proxies)
+        .into_iter()
+        .partition(|proxy| subscription_proxies.contains(proxy));
+    *proxies = inline;
+    if proxies.is_empty() {
+        config_map.remove(YamlValue::from("proxies"));
+    }
+
+    moved
+        .iter()
+        .filter_map(|p| p.get("name").and_then(|n| n.as_str()))
+        .map(|s| s.to_string())
+        .collect()
+}
+
+// 添加指向订阅文件的 proxy-provider
+fn insert_proxy_provider(config_map: &mut Mapping, params: &ProviderModeParams) {
+    let mut health_check = Mapping::new();
+    health_check.insert(YamlValue::from("enable"), YamlValue::from(true));
+    health_check.insert(
+        YamlValue::from("url"),
+        YamlValue::from("https://www.gstatic.com/generate_204"),
+    );
+    health_check.insert(YamlValue::from("interval"), YamlValue::from(300));
+
+    let mut provider = Mapping::new();
+    provider.insert(YamlValue::from("type"), YamlValue::from("file"));
+    provider.insert(
+        YamlValue::from("path"),
+        YamlValue::from(params.subscription_path.as_str()),
+    );
+    provider.insert(
+        YamlValue::from("health-check"),
+        YamlValue::Mapping(health_check),
+    );
+
+    let providers = config_map
+        .entry(YamlValue::from("proxy-providers"))
+        .or_insert_with(|| YamlValue::Mapping(Mapping::new()));
+
+    if let Some(providers) = providers.as_mapping_mut() {
+        providers.insert(
+            YamlValue::from(params.provider_name.as_str()),
+            YamlValue::Mapping(provider),
+        );
+    }
+}
+
+// 将代理组中的节点引用替换为 use，未包含订阅文件全部节点的组通过 filter 保持原有成员。
+// filter 由转换时的节点名拼成，提供者刷新后改名或新增的节点不会进入这些组。
+fn rewrite_proxy_groups(
+    config_map: &mut Mapping,
+    proxy_names: &[String],
+    provider_size: usize,
+    provider_name: &str,
+) {
+    let all_nodes: HashSet<&str> = proxy_names.iter().map(|s| s.as_str()).collect();
+
+    let Some(groups) = config_map
+        .get_mut(YamlValue::from("proxy-groups"))
+        .and_then(|v| v.as_sequence_mut())
+    else {
+        return;
+    };
+
+    for group in groups.iter_mut() {
+        let Some(group_map) = group.as_mapping_mut() else {
+            continue;
+        };
+        let Some(members) = group_map
+            .get(YamlValue::from("proxies"))
+            .and_then(|v| v.as_sequence())
+        else {
+            continue;
+        };
+
+        let (nodes, others): (Vec<&YamlValue>, Vec<&YamlValue>) = members
+            .iter()
+            .partition(|m| m.as_str().is_some_and(|name| all_nodes.contains(name)));
+        if nodes.is_empty() {
+            continue;
+        }
+
+        // 仅包含部分节点时按名称精确过滤（覆写删除或修改的节点仍在订阅文件中）
+        let filter = if nodes.len() < provider_size {
+            let pattern = nodes
+                .iter()
+                .filter_map(|n| n.as_str())
+                .map(regex_escape)
+                .collect::<Vec<_>>()
+                .join("|");
+            Some(format!("^({})$", pattern))
+        } else {
+            None
+        };
+        let others: Vec<YamlValue> = others.into_iter().cloned().collect();
+
+        if others.is_empty() {
+            group_map.remove(YamlValue::from("proxies"));
+        } else {
+            group_map.insert(YamlValue::from("proxies"), YamlValue::Sequence(others));
+        }
+
+        let uses = group_map
+            .entry(YamlValue::from("use"))
+            .or_insert_with(|| YamlValue::Sequence(vec![]));
+        if let Some(uses) = uses.as_sequence_mut()
+            && !uses.iter().any(|u| u.as_str() == Some(provider_name))
+        {
+            uses.push(YamlValue::from(provider_name));
+        }
+
+        if let Some(filter) = filter {
+            group_map.insert(YamlValue::from("filter"), YamlValue::from(filter));
+        }
+    }
+}
+
+// 规则段：可提取段的策略（其他规则为 None）与（规则内容, 原规则）列表
+type RuleSegment<'a> = (Option<String>, Vec<(String, &'a YamlValue)>);
+
+// 提取连续的同策略域名/IP 规则为 rule-providers（只合并相邻规则，保持匹配顺序不变）
+fn extract_rule_providers(
+    config_map: &mut Mapping,
+    params: &ProviderModeParams,
+) -> Result<Vec<RuleProviderFile>, String> {
+    let Some(rules) = config_map
+        .get(YamlValue::from("rules"))
+        .and_then(|v| v.as_sequence())
+    else {
+        return Ok(Vec::new());
+    };
+
+    // 按策略切分连续的可提取规则段，其他规则单独成段
+    let mut segments: Vec<RuleSegment> = Vec::new();
+    for rule in rules {
+        match rule.as_str().and_then(split_extractable_rule) {
+            Some((payload, policy)) => match segments.last_mut() {
+                Some((Some(last_policy), items)) if *last_policy == policy => {
+                    items.push((payload, rule));
+                }
+                _ => segments.push((Some(policy), vec![(payload, rule)])),
+            },
+            None => segments.push((None, vec![(String::new(), rule)])),
+        }
+    }
+
+    let min_size = params.rule_set_min_size as usize;
+    let mut new_rules: Vec<YamlValue> = Vec::new();
+    let mut files = Vec::new();
+    let mut providers = Mapping::new();
+
+    for (policy, items) in segments {
+        let policy = match policy {
+            Some(policy) if items.len() >= min_size => policy,
+            _ => {
+                new_rules.extend(items.into_iter().map(|(_, rule)| rule.clone()));
+                continue;
+            }
+        };
+
+        let name = format!("{}-ruleset-{}", params.provider_name, files.len() + 1);
+        let path = std::path::Path::new(&params.rule_providers_dir)
+            .join(format!("{}.yaml", name))
+            .to_string_lossy()
+            .to_string();
+
+        let payload: Vec<YamlValue> = items
+            .iter()
+            .map(|(payload, _)| YamlValue::from(payload.as_str()))
+            .collect();
+        let mut document = Mapping::new();
+        document.insert(YamlValue::from("payload"), YamlValue::Sequence(payload));
+        let content =
+            serde_yaml_ng::to_string(&document).map_err(|e| format!("序列化规则集失败：{}", e))?;
+
+        let mut provider = Mapping::new();
+        provider.insert(YamlValue::from("type"), YamlValue::from("file"));
+        provider.insert(YamlValue::from("behavior"), YamlValue::from("classical"));
+        provider.insert(YamlValue::from("format"), YamlValue::from("yaml"));
+        provider.insert(YamlValue::from("path"), YamlValue::from(path.as_str()));
+        providers.insert(YamlValue::from(name.as_str()), YamlValue::Mapping(provider));
+
+        log::info!("提取{}条规则到 rule-provider「{}」", items.len(), name);
+        new_rules.push(YamlValue::from(format!("RULE-SET,{},{}", name, policy)));
+        files.push(RuleProviderFile { path, content });
+    }
+
+    if providers.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    config_map.insert(YamlValue::from("rules"), YamlValue::Sequence(new_rules));
+    let rule_providers = config_map
+        .entry(YamlValue::from("rule-providers"))
+        .or_insert_with(|| YamlValue::Mapping(Mapping::new()));
+    if let Some(rule_providers) = rule_providers.as_mapping_mut() {
+        rule_providers.extend(providers);
+    }
+
+    Ok(files)
+}
+
+// 拆分可提取的规则：返回（去掉策略的规则内容，策略）
+// 例如 `IP-CIDR,1.1.1.1/32,PROXY,no-resolve` → (`IP-CIDR,1.1.1.1/32,no-resolve`, `PROXY`)
+fn split_extractable_rule(rule: &str) -> Option<(String, String)> {
+    let parts: Vec<&str> = rule.split(',').map(|p| p.trim()).collect();
+    if parts.len() < 3 || !EXTRACTABLE_RULE_TYPES.contains(&parts[0]) {
+        return None;
+    }
+
+    let mut payload = vec![parts[0], parts[1]];
+    payload.extend(&parts[3..]);
+    Some((payload.join(","), parts[2].to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::atoms::OverrideProcessor;
+    use crate::molecules::{OverrideConfig, OverrideFormat};
+
+    #[test]
+    fn test_convert_to_provider_mode() -> Result<(), String> {
+        let config = r#"
+proxies:
+  - {name: HK, type: ss, server: a.example.com, port: 1, cipher: aes-128-gcm, password: p}
+  - {name: JP, type: ss, server: b.example.com, port: 1, cipher: aes-128-gcm, password: p}
+proxy-groups:
+  - {name: PROXY, type: select, proxies: [AUTO, HK, JP]}
+  - {name: AUTO, type: url-test, proxies: [HK, JP]}
+  - {name: 香港, type: select, proxies: [HK]}
+rules:
+  - DOMAIN-SUFFIX,a.com,PROXY
+  - DOMAIN-SUFFIX,b.com,PROXY
+  - IP-CIDR,1.1.1.1/32,PROXY,no-resolve
+  - DOMAIN,c.com,DIRECT
+  - MATCH,PROXY
+"#;
+        let params = ProviderModeParams {
+            provider_name: "sub".to_string(),
+            subscription_path: "./profiles/sub.yaml".to_string(),
+            rule_providers_dir: "rules".to_string(),
+            rule_set_min_size: 2,
+        };
+
+        let (result, files) = convert_to_provider_mode(config, config, &params)?;
+        let result: YamlValue = serde_yaml_ng::from_str(&result).map_err(|e| e.to_string())?;
+
+        assert!(result.get("proxies").is_none());
+        assert_eq!(
+            result["proxy-providers"]["sub"]["path"],
+            "./profiles/sub.yaml"
+        );
+
+        let groups = &result["proxy-groups"];
+        assert_eq!(groups[0]["proxies"][0], "AUTO");
+        assert_eq!(groups[0]["use"][0], "sub");
+        assert!(groups[1].get("proxies").is_none());
+        assert!(groups[1].get("filter").is_none());
+        assert_eq!(groups[2]["filter"], "^(HK)$");
+
+        assert_eq!(result["rules"][0], "RULE-SET,sub-ruleset-1,PROXY");
+        assert_eq!(result["rules"][1], "DOMAIN,c.com,DIRECT");
+        assert_eq!(files.len(), 1);
+        assert!(files[0].content.contains("IP-CIDR,1.1.1.1/32,no-resolve"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_overridden_proxies_stay_inline() -> Result<(), String> {
+        let subscription = r#"
+proxies:
+  - {name: HK, type: ss, server: a.example.com, port: 1, cipher: aes-128-gcm, password: p}
+  - {name: JP, type: ss, server: b.example.com, port: 1, cipher: aes-128-gcm, password: p}
+  - {name: US, type: ss, server: c.example.com, port: 1, cipher: aes-128-gcm, password: p}
+proxy-groups:
+  - {name: PROXY, type: select, proxies: [HK, JP, US]}
+rules:
+  - MATCH,PROXY
+"#;
+        let overrides = vec![OverrideConfig {
+            id: "relay".to_string(),
+            name: "relay".to_string(),
+            format: OverrideFormat::Yaml,
+            content: r#"
+proxies[name=JP]: {port: 2}
+proxies[name=US]-: ~
+proxies+:
+  - {name: Relay, type: socks5, server: 127.0.0.1, port: 1080}
+proxy-groups[name=PROXY]:
+  proxies: [Relay, HK, JP]
+"#
+            .to_string(),
+            arguments: None,
+            conditions: None,
+        }];
+        let overridden = OverrideProcessor::new()?.apply_overrides(subscription, overrides)?;
+
+        let params = ProviderModeParams {
+            provider_name: "sub".to_string(),
+            subscription_path: "./profiles/sub.yaml".to_string(),
+            rule_providers_dir: "rules".to_string(),
+            rule_set_min_size: 0,
+        };
+        let (result, _) = convert_to_provider_mode(&overridden, subscription, &params)?;
+        let result: YamlValue = serde_yaml_ng::from_str(&result).map_err(|e| e.to_string())?;
+
+        // 新增与修改的节点保留内联，未改动的节点由提供者加载
+        let inline: Vec<(&str, i64)> = result["proxies"]
+            .as_sequence()
+            .ok_or("缺少内联节点")?
+            .iter()
+            .filter_map(|p| Some((p["name"].as_str()?, p["port"].as_i64()?)))
+            .collect();
+        assert_eq!(inline, [("JP", 2), ("Relay", 1080)]);
+        assert_eq!(
+            result["proxy-providers"]["sub"]["path"],
+            "./profiles/sub.yaml"
+        );
+
+        // 组成员：内联节点按名称引用，提供者中只保留 HK（US 已被覆写删除）
+        let group = &result["proxy-groups"][0];
+        assert_eq!(group["proxies"][0], "Relay");
+        assert_eq!(group["proxies"][1], "JP");
+        assert_eq!(group["use"][0], "sub");
+        assert_eq!(group["filter"], "^(HK)$");
+
+        // 没有节点与订阅文件一致时不添加提供者
+        let (result, _) = convert_to_provider_mode(&overridden, "proxies: []", &params)?;
+        let result: YamlValue = serde_yaml_ng::from_str(&result).map_err(|e| e.to_string())?;
+        assert!(result.get("proxy-providers").is_none());
+        assert_eq!(result["proxy-groups"][0]["proxies"][0], "Relay");
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 // Clash 运行时配置参数
 
+use crate::molecules::shared_types::RuntimeFlag;
 use rinf::{DartSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
 
@@ -43,3 +44,30 @@ pub struct RuntimeConfigParams {
     pub is_dns_override_enabled: bool,
     pub dns_override_content: Option<String>,
 }
+
+impl RuntimeConfigParams {
+    // 已开启的运行时开关，用于评估覆写匹配条件
+    pub fn enabled_flags(&self) -> Vec<RuntimeFlag> {
+        [
+            (RuntimeFlag::TunEnabled, self.is_tun_enabled),
+            (RuntimeFlag::Ipv6Enabled, self.is_ipv6_enabled),
+            (RuntimeFlag::AllowLanEnabled, self.is_allow_lan_enabled),
+            (
+                RuntimeFlag::DnsOverrideEnabled,
+                self.is_dns_override_enabled,
+            ),
+            (RuntimeFlag::KeepAliveEnabled, self.is_keep_alive_enabled),
+            (
+                RuntimeFlag::TcpConcurrentEnabled,
+                self.is_tcp_concurrent_enabled,
+            ),
+            (
+                RuntimeFlag::UnifiedDelayEnabled,
+                self.is_unified_delay_enabled,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(flag, is_enabled)| is_enabled.then_some(flag))
+        .collect()
+    }
+}
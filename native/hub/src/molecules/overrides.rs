@@ -3,6 +3,7 @@
 pub mod downloader;
 
 // 内部使用
+mod integrity;
 mod processor;
 
 pub use downloader::{DownloadOverrideRequest, DownloadOverrideResponse};
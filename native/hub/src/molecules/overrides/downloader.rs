@@ -1,11 +1,19 @@
 // 覆写文件下载器
-// 处理覆写文件的 HTTP 下载，支持多种代理模式
-
-use crate::molecules::ProxyMode;
-use reqwest::Client;
+// 处理覆写文件的 HTTP 下载，支持多种代理模式、回退重试、自定义请求头与客户端证书；
+// 支持 ETag 条件请求，并在接受内容前校验固定哈希与 minisign 签名
+
+use super::integrity::IntegrityVerifier;
+use crate::atoms::override_processor::JsonPatcher;
+use crate::molecules::http_client::{
+    BoxError, DownloadPlan, HttpStatusError, TlsMaterial, build_get, redact_url,
+};
+use crate::molecules::{
+    DownloadRetryPolicy, HttpRequestOptions, OverrideFormat, OverrideIntegrity, ProxyMode,
+};
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
 
 // Dart → Rust：下载覆写文件请求
 #[derive(Deserialize, DartSignal)]
@@ -16,6 +24,11 @@ pub struct DownloadOverrideRequest {
     pub user_agent: String,
     pub timeout_seconds: u64,
     pub mixed_port: u16,
+    pub retry_policy: Option<DownloadRetryPolicy>, // 代理模式回退与重试，未设置时只尝试一次
+    pub http_options: Option<HttpRequestOptions>,  // 自定义请求头、客户端证书与额外 CA
+    pub format: Option<OverrideFormat>, // 覆写格式，JSON Patch 与 Merge Patch 下载后校验内容
+    pub integrity: Option<OverrideIntegrity>, // 固定哈希或 minisign 签名，校验失败时拒绝内容
+    pub etag: Option<String>,           // 上次下载的 ETag，服务器返回 304 时不重新下载
 }
 
 // Rust → Dart：下载覆写文件响应
@@ -24,33 +37,61 @@ pub struct DownloadOverrideResponse {
     pub request_id: String, // 请求标识符，用于请求匹配
     pub is_successful: bool,
     pub content: String,
+    pub is_not_modified: bool,              // 服务器返回 304，content 为空
+    pub etag: Option<String>,               // 新内容的 ETag
+    pub content_sha256: String,             // 新内容的 SHA-256（十六进制），未修改时为空
+    pub proxy_mode_used: Option<ProxyMode>, // 下载成功时实际使用的代理模式
     pub error_message: Option<String>,
 }
 
+// 覆写下载结果
+pub enum OverrideFetch {
+    Modified {
+        content: String,
+        etag: Option<String>,
+    },
+    NotModified,
+}
+
 impl DownloadOverrideRequest {
     pub async fn handle(self) {
-        log::info!("收到下载覆写文件请求 [{}]：{}", self.request_id, self.url);
+        log::info!(
+            "收到下载覆写文件请求 [{}]：{}",
+            self.request_id,
+            redact_url(&self.url)
+        );
 
-        let result = download_override(
-            &self.url,
-            self.proxy_mode,
-            &self.user_agent,
-            self.timeout_seconds,
-            self.mixed_port,
-        )
-        .await;
+        let result = download_override(&self).await;
 
         let response = match result {
-            Ok(content) => {
+            Ok((OverrideFetch::Modified { content, etag }, proxy_mode_used)) => {
                 log::info!(
-                    "覆写文件下载成功 [{}]，内容长度：{} 字节",
+                    "覆写文件下载成功 [{}]（{:?}），内容长度：{} 字节",
                     self.request_id,
+                    proxy_mode_used,
                     content.len()
                 );
                 DownloadOverrideResponse {
                     request_id: self.request_id,
                     is_successful: true,
+                    content_sha256: IntegrityVerifier::sha256_hex(&content),
                     content,
+                    is_not_modified: false,
+                    etag,
+                    proxy_mode_used: Some(proxy_mode_used),
+                    error_message: None,
+                }
+            }
+            Ok((OverrideFetch::NotModified, proxy_mode_used)) => {
+                log::info!("覆写文件未变化 [{}]（HTTP 304）", self.request_id);
+                DownloadOverrideResponse {
+                    request_id: self.request_id,
+                    is_successful: true,
+                    content: String::new(),
+                    is_not_modified: true,
+                    etag: self.etag,
+                    content_sha256: String::new(),
+                    proxy_mode_used: Some(proxy_mode_used),
                     error_message: None,
                 }
             }
@@ -60,6 +101,10 @@ impl DownloadOverrideRequest {
                     request_id: self.request_id,
                     is_successful: false,
                     content: String::new(),
+                    is_not_modified: false,
+                    etag: None,
+                    content_sha256: String::new(),
+                    proxy_mode_used: None,
                     error_message: Some(e.to_string()),
                 }
             }
@@ -69,84 +114,105 @@ impl DownloadOverrideRequest {
     }
 }
 
-// 下载覆写文件并返回内容。
-// 支持代理模式、超时与自定义 User-Agent。
+// 下载覆写文件，返回下载结果与实际使用的代理模式。
+// 支持代理模式回退、超时、自定义 User-Agent 与请求头、客户端证书。
 pub async fn download_override(
-    url: &str,
-    proxy_mode: ProxyMode,
-    user_agent: &str,
-    timeout_seconds: u64,
-    mixed_port: u16,
-) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
-    log::info!("开始下载覆写文件：{}", url);
-    log::info!("代理模式：{:?}", proxy_mode);
-
-    // 创建 HTTP 客户端
-    let client = create_http_client(proxy_mode, timeout_seconds, mixed_port)?;
-
-    // 发送 HTTP GET 请求
-    let response = client
-        .get(url)
-        .header("User-Agent", user_agent)
-        .send()
-        .await?;
+    request: &DownloadOverrideRequest,
+) -> Result<(OverrideFetch, ProxyMode), BoxError> {
+    log::info!("开始下载覆写文件：{}", redact_url(&request.url));
+    log::info!("代理模式：{:?}", request.proxy_mode);
+
+    let plan = DownloadPlan::new(
+        request.proxy_mode,
+        request.timeout_seconds,
+        request.mixed_port,
+        request.retry_policy.as_ref(),
+        TlsMaterial::load(request.http_options.as_ref())?,
+    );
+    let headers = request
+        .http_options
+        .as_ref()
+        .map(|options| options.headers.as_slice())
+        .unwrap_or_default();
+
+    let signature_url = IntegrityVerifier::signature_url(request.integrity.as_ref(), &request.url);
+
+    let ((content, etag, signature), proxy_mode_used) = plan
+        .run(|client| {
+            let signature_url = signature_url.as_deref();
+            async move {
+                // 发送 HTTP GET 请求，附带上次的 ETag
+                let mut http_request =
+                    build_get(&client, &request.url, &request.user_agent, headers)?;
+                if let Some(etag) = &request.etag {
+                    http_request = http_request.header(IF_NONE_MATCH, etag);
+                }
+                let response = http_request.send().await?;
 
-    // 检查 HTTP 状态码
-    let status = response.status();
-    if !status.is_success() {
-        return Err(format!(
-            "HTTP {}: {}",
-            status.as_u16(),
-            status.canonical_reason().unwrap_or("Unknown")
-        )
-        .into());
-    }
+                // 检查 HTTP 状态码
+                let status = response.status();
+                if status == StatusCode::NOT_MODIFIED {
+                    return Ok((None, None, None));
+                }
+                if !status.is_success() {
+                    return Err(HttpStatusError(status).into());
+                }
 
-    // 读取响应体
-    let content = response.text().await?;
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|v| v.to_str().ok())
+                    .map(|s| s.to_string());
 
-    if content.is_empty() {
-        return Err("覆写文件内容为空".into());
-    }
+                // 读取响应体
+                let content = response.text().await?;
 
-    log::info!("覆写文件下载成功，内容长度：{} 字节", content.len());
+                if content.is_empty() {
+                    return Err("覆写文件内容为空".into());
+                }
 
-    Ok(content)
-}
+                log::info!("覆写文件下载成功，内容长度：{} 字节", content.len());
+
+                // 配置了 minisign 公钥时下载签名文件
+                let signature = match signature_url {
+                    Some(url) => {
+                        let response = build_get(&client, url, &request.user_agent, headers)?
+                            .send()
+                            .await?;
+                        let status = response.status();
+                        if !status.is_success() {
+                            return Err(format!("下载签名文件失败：{}", status).into());
+                        }
+                        Some(response.text().await?)
+                    }
+                    None => None,
+                };
+
+                Ok((Some(content), etag, signature))
+            }
+        })
+        .await?;
 
-// 创建 HTTP 客户端（复用订阅下载的逻辑）
-fn create_http_client(
-    proxy_mode: ProxyMode,
-    timeout_seconds: u64,
-    mixed_port: u16,
-) -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
-    use reqwest::Proxy;
-
-    let mut builder = Client::builder()
-        .timeout(Duration::from_secs(timeout_seconds))
-        .connect_timeout(Duration::from_secs(10)) // 连接超时
-        .danger_accept_invalid_certs(false); // 验证 SSL 证书
-
-    // 根据代理模式配置客户端
-    match proxy_mode {
-        ProxyMode::Direct => {
-            log::debug!("使用直连模式");
-            // 不设置代理
-        }
-        ProxyMode::System => {
-            log::debug!("使用系统代理模式");
-            // reqwest 默认会读取系统环境变量（HTTP_PROXY, HTTPS_PROXY）
-            // 无需额外配置
-        }
-        ProxyMode::Core => {
-            log::debug!("使用核心代理模式：127.0.0.1:{}", mixed_port);
-            let proxy_url = format!("http://127.0.0.1:{}", mixed_port);
-            let proxy = Proxy::all(&proxy_url)?;
-            builder = builder.proxy(proxy);
-        }
+    let Some(content) = content else {
+        return Ok((OverrideFetch::NotModified, proxy_mode_used));
+    };
+
+    // 校验固定哈希与签名，避免接受被篡改的覆写
+    if let Some(integrity) = &request.integrity {
+        IntegrityVerifier::verify(&content, integrity, signature.as_deref())
+            .map_err(|e| format!("覆写完整性校验失败：{}", e))?;
+        log::info!("覆写完整性校验通过");
     }
 
-    Ok(builder.build()?)
+    // 校验补丁格式，避免保存无法应用的覆写
+    let validation = match request.format {
+        Some(OverrideFormat::JsonPatch) => JsonPatcher::validate_json_patch(&content),
+        Some(OverrideFormat::MergePatch) => JsonPatcher::validate_merge_patch(&content),
+        _ => Ok(()),
+    };
+    validation.map_err(|e| format!("覆写内容无效：{}", e))?;
+
+    Ok((OverrideFetch::Modified { content, etag }, proxy_mode_used))
 }
 
 pub fn init() {
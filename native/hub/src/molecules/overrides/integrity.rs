@@ -0,0 +1,115 @@
+// 远程覆写完整性校验：固定 SHA-256 与 minisign 签名。
+// 下载内容在被接受前校验，任一项不通过即拒绝本次下载。
+
+use crate::molecules::OverrideIntegrity;
+use minisign_verify::{PublicKey, Signature};
+use sha2::{Digest, Sha256};
+
+// 完整性校验器
+pub struct IntegrityVerifier;
+
+impl IntegrityVerifier {
+    // 内容的 SHA-256（小写十六进制）
+    pub fn sha256_hex(content: &str) -> String {
+        Sha256::digest(content.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    // 需要下载的签名文件地址，未配置公钥时为 None
+    pub fn signature_url(integrity: Option<&OverrideIntegrity>, url: &str) -> Option<String> {
+        let integrity = integrity?;
+        integrity.minisign_public_key.as_ref()?;
+        Some(
+            integrity
+                .signature_url
+                .clone()
+                .filter(|signature_url| !signature_url.trim().is_empty())
+                .unwrap_or_else(|| format!("{}.minisig", url)),
+        )
+    }
+
+    // 校验内容哈希与签名
+    pub fn verify(
+        content: &str,
+        integrity: &OverrideIntegrity,
+        signature: Option<&str>,
+    ) -> Result<(), String> {
+        if let Some(pinned) = &integrity.sha256 {
+            let pinned = pinned.trim();
+            let pinned = pinned.strip_prefix("sha256:").unwrap_or(pinned);
+            let actual = Self::sha256_hex(content);
+            if !pinned.eq_ignore_ascii_case(&actual) {
+                return Err(format!(
+                    "内容哈希与固定值不符（期望 {}，实际 {}）",
+                    pinned, actual
+                ));
+            }
+        }
+
+        if let Some(public_key) = &integrity.minisign_public_key {
+            let public_key = public_key.trim();
+            // 完整的公钥文件包含注释行，否则视为 base64 公钥
+            let public_key = if public_key.contains('\n') {
+                PublicKey::decode(public_key)
+            } else {
+                PublicKey::from_base64(public_key)
+            }
+            .map_err(|e| format!("minisign 公钥无效：{}", e))?;
+            let signature = Signature::decode(signature.ok_or("缺少 minisign 签名")?.trim())
+                .map_err(|e| format!("minisign 签名无效：{}", e))?;
+            public_key
+                .verify(content.as_bytes(), &signature, true)
+                .map_err(|e| format!("minisign 签名校验失败：{}", e))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // minisign 官方测试向量：对内容 "test" 的预哈希签名
+    const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==";
+
+    #[test]
+    fn test_verify() -> Result<(), String> {
+        let hash = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        assert_eq!(IntegrityVerifier::sha256_hex("test"), hash);
+
+        let pinned = OverrideIntegrity {
+            sha256: Some(format!("sha256:{}", hash.to_uppercase())),
+            ..Default::default()
+        };
+        IntegrityVerifier::verify("test", &pinned, None)?;
+        assert!(IntegrityVerifier::verify("Test", &pinned, None).is_err());
+
+        let signed = OverrideIntegrity {
+            minisign_public_key: Some(format!(
+                "untrusted comment: minisign public key\n{}",
+                PUBLIC_KEY
+            )),
+            ..Default::default()
+        };
+        IntegrityVerifier::verify("test", &signed, Some(SIGNATURE))?;
+        assert!(IntegrityVerifier::verify("Test", &signed, Some(SIGNATURE)).is_err());
+        assert!(IntegrityVerifier::verify("test", &signed, None).is_err());
+
+        assert_eq!(
+            IntegrityVerifier::signature_url(Some(&signed), "https://a.com/x.js"),
+            Some("https://a.com/x.js.minisig".to_string())
+        );
+        assert_eq!(
+            IntegrityVerifier::signature_url(Some(&pinned), "https://a.com/x.js"),
+            None
+        );
+        Ok(())
+    }
+}
@@ -4,6 +4,9 @@
 use crate::atoms::ProxyParser;
 use crate::atoms::override_processor::OverrideProcessor;
 use crate::molecules::OverrideConfig;
+use crate::molecules::shared_types::{
+    OverrideMatchContext, OverrideTraceStep, ParseReport, SkippedOverride, SubscriptionParseOptions,
+};
 use rinf::{DartSignal, RustSignal};
 use serde::{Deserialize, Serialize};
 
@@ -12,6 +15,8 @@ use serde::{Deserialize, Serialize};
 pub struct ApplyOverridesRequest {
     pub base_config_content: String,
     pub overrides: Vec<OverrideConfig>,
+    pub is_trace_enabled: bool, // 记录每个覆写的耗时与配置变化
+    pub match_context: Option<OverrideMatchContext>, // 覆写匹配条件的上下文
 }
 
 // Rust → Dart：应用覆写响应
@@ -20,7 +25,9 @@ pub struct ApplyOverridesResponse {
     pub is_successful: bool,
     pub result_config: String,
     pub error_message: String,
-    pub logs: Vec<String>,
+    pub logs: Vec<String>,                       // 覆写脚本的 console 输出
+    pub trace: Vec<OverrideTraceStep>,           // 覆写追踪（开启追踪时提供）
+    pub skipped_overrides: Vec<SkippedOverride>, // 被跳过的覆写及原因
 }
 
 // Dart → Rust：解析订阅请求
@@ -28,6 +35,7 @@ pub struct ApplyOverridesResponse {
 pub struct ParseSubscriptionRequest {
     pub request_id: String, // 请求标识符，用于响应匹配
     pub content: String,
+    pub options: SubscriptionParseOptions,
 }
 
 // Rust → Dart：解析订阅响应
@@ -37,6 +45,7 @@ pub struct ParseSubscriptionResponse {
     pub is_successful: bool,
     pub parsed_config: String,
     pub error_message: String,
+    pub report: Option<ParseReport>, // 解析报告（成功时提供）
 }
 
 impl ApplyOverridesRequest {
@@ -52,6 +61,8 @@ impl ApplyOverridesRequest {
                     result_config: String::new(),
                     error_message: format!("初始化处理器失败：{}", e),
                     logs: vec![],
+                    trace: vec![],
+                    skipped_overrides: vec![],
                 };
                 response.send_signal_to_dart();
                 return;
@@ -68,6 +79,8 @@ impl ApplyOverridesRequest {
                     result_config: String::new(),
                     error_message: format!("订阅解析失败：{}", e),
                     logs: vec![],
+                    trace: vec![],
+                    skipped_overrides: vec![],
                 };
                 response.send_signal_to_dart();
                 return;
@@ -76,14 +89,27 @@ impl ApplyOverridesRequest {
 
         log::info!("订阅解析成功，配置长度：{}字节", parsed_config.len());
 
-        match processor.apply_overrides(&parsed_config, self.overrides) {
+        if self.is_trace_enabled {
+            processor.enable_trace();
+        }
+        if let Some(context) = self.match_context {
+            processor.set_match_context(context);
+        }
+        let result = processor.apply_overrides(&parsed_config, self.overrides);
+        let logs = processor.take_logs();
+        let trace = processor.take_trace();
+        let skipped_overrides = processor.take_skipped();
+
+        match result {
             Ok(result) => {
                 log::info!("覆写处理成功");
                 let response = ApplyOverridesResponse {
                     is_successful: true,
                     result_config: result,
                     error_message: String::new(),
-                    logs: vec!["处理成功".to_string()],
+                    logs,
+                    trace,
+                    skipped_overrides,
                 };
                 response.send_signal_to_dart();
             }
@@ -93,7 +119,9 @@ impl ApplyOverridesRequest {
                     is_successful: false,
                     result_config: String::new(),
                     error_message: e,
-                    logs: vec![],
+                    logs,
+                    trace,
+                    skipped_overrides,
                 };
                 response.send_signal_to_dart();
             }
@@ -110,18 +138,22 @@ impl ParseSubscriptionRequest {
             self.content.len()
         );
 
-        match ProxyParser::parse_subscription(&self.content) {
-            Ok(parsed_config) => {
+        match ProxyParser::parse_subscription_with_report(&self.content, &self.options) {
+            Ok((parsed_config, report)) => {
                 log::info!(
-                    "订阅解析成功 [{}]，配置长度：{}字节",
+                    "订阅解析成功 [{}]，配置长度：{}字节，接受{}个节点，排除{}个，跳过{}条",
                     self.request_id,
-                    parsed_config.len()
+                    parsed_config.len(),
+                    report.accepted_count,
+                    report.excluded_count,
+                    report.rejected_entries.len()
                 );
                 let response = ParseSubscriptionResponse {
                     request_id: self.request_id,
                     is_successful: true,
                     parsed_config,
                     error_message: String::new(),
+                    report: Some(report),
                 };
                 response.send_signal_to_dart();
             }
@@ -132,6 +164,7 @@ impl ParseSubscriptionRequest {
                     is_successful: false,
                     parsed_config: String::new(),
                     error_message: e,
+                    report: None,
                 };
                 response.send_signal_to_dart();
             }
@@ -146,7 +179,12 @@ pub fn init() {
     spawn(async {
         let receiver = ApplyOverridesRequest::get_dart_signal_receiver();
         while let Some(dart_signal) = receiver.recv().await {
-            dart_signal.message.handle();
+            // 覆写脚本可能长时间运行，放到阻塞线程池中执行，避免阻塞异步运行时；
+            // 响应不带请求标识，等待完成后再处理下一个请求以保持顺序
+            let task = tokio::task::spawn_blocking(move || dart_signal.message.handle());
+            if let Err(e) = task.await {
+                log::error!("应用覆写任务异常退出：{}", e);
+            }
         }
     });
 
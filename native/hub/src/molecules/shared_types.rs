@@ -5,12 +5,65 @@ use rinf::SignalPiece;
 use serde::{Deserialize, Serialize};
 
 // 从 atoms 层重新导出
-pub use crate::atoms::shared_types::{OverrideConfig, OverrideFormat};
+pub use crate::atoms::shared_types::{
+    AggregatedSourceReport, ConfigCounts, ModifiedGroup, ModifiedNode, OverrideConditions,
+    OverrideConfig, OverrideFormat, OverrideMatchContext, OverrideTraceStep, ParseReport,
+    ProfileDiff, ProtocolCount, RejectedEntry, RenamedNode, RuntimeFlag, RuntimeFlagCondition,
+    SkippedOverride, SubscriptionParseOptions, SubscriptionSource,
+};
 
 // 代理模式（分子层特有）
-#[derive(Deserialize, Serialize, Clone, Copy, Debug, SignalPiece)]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, SignalPiece)]
 pub enum ProxyMode {
     Direct = 0, // 直连
     System = 1, // 系统代理
     Core = 2,   // Clash 核心代理
 }
+
+// 下载重试策略（分子层特有）：请求的代理模式失败后按顺序尝试回退模式
+#[derive(Deserialize, Serialize, Clone, Debug, SignalPiece)]
+pub struct DownloadRetryPolicy {
+    pub fallback_modes: Vec<ProxyMode>, // 回退链，例如 Core → System → Direct
+    pub attempt_timeout_seconds: Option<u64>, // 单次尝试超时，未设置时使用请求的超时
+    pub max_retries: u32,               // 每个代理模式的重试次数（不含首次尝试）
+    pub retry_delay_ms: u64,            // 首次重试前的等待时间，之后逐次翻倍
+}
+
+// HTTP 请求选项（分子层特有）：自定义请求头、客户端证书与额外信任的 CA
+#[derive(Deserialize, Serialize, Clone, Debug, Default, SignalPiece)]
+pub struct HttpRequestOptions {
+    pub headers: Vec<HttpHeader>, // 自定义请求头，例如 Authorization、Cookie
+    pub client_certificate: Option<ClientCertificate>, // 双向 TLS 客户端证书
+    pub ca_bundle_path: Option<String>, // 额外信任的 CA 证书（PEM，可包含多个）
+}
+
+// 远程覆写完整性校验（分子层特有）：校验通过后才接受下载的内容
+#[derive(Deserialize, Serialize, Clone, Debug, Default, SignalPiece)]
+pub struct OverrideIntegrity {
+    pub sha256: Option<String>, // 固定的内容 SHA-256（十六进制，可带 sha256: 前缀）
+    pub minisign_public_key: Option<String>, // minisign 公钥（base64 或完整的公钥文件内容）
+    pub signature_url: Option<String>, // 签名文件地址，未设置时为覆写地址加 .minisig
+}
+
+// 自定义请求头
+#[derive(Deserialize, Serialize, Clone, Debug, SignalPiece)]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+// 客户端证书
+#[derive(Deserialize, Serialize, Clone, Debug, SignalPiece)]
+pub struct ClientCertificate {
+    pub format: ClientCertificateFormat,
+    pub certificate_path: String, // PEM 证书链（可同时包含私钥）或 PKCS#12 文件
+    pub key_path: Option<String>, // PEM 私钥文件（与证书分开存放时）
+    pub password: Option<String>, // PKCS#12 密码
+}
+
+// 客户端证书格式
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq, SignalPiece)]
+pub enum ClientCertificateFormat {
+    Pem = 0,
+    Pkcs12 = 1,
+}
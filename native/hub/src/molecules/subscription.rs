@@ -1,13 +1,33 @@
 // 订阅管理分子模块
 
+pub mod aggregator;
+pub mod cache;
+pub mod diff;
 pub mod downloader;
-pub mod parser;
+pub mod exporter;
+pub mod scheduler;
 
+pub use aggregator::{AggregateSubscriptionsRequest, AggregateSubscriptionsResponse};
+pub use cache::RemoveSubscriptionCacheRequest;
+pub use diff::{DiffSubscriptionRequest, DiffSubscriptionResponse};
 pub use downloader::{
-    DownloadSubscriptionRequest, DownloadSubscriptionResponse, SubscriptionInfoData,
+    DownloadOutcome, DownloadSubscriptionProgress, DownloadSubscriptionRequest,
+    DownloadSubscriptionResponse, SubscriptionInfoData,
+};
+pub use exporter::{ExportProxyLinksRequest, ExportProxyLinksResponse};
+
+// 从 atoms 层重新导出 ProxyParser（供其他分子使用）
+pub use crate::atoms::ProxyParser;
+pub use scheduler::{
+    ConfigureSubscriptionScheduleRequest, ScheduledSubscription, SetSubscriptionSchedulePaused,
+    SubscriptionRefreshProgress, SubscriptionRefreshResult,
 };
-pub use parser::ProxyParser;
 
 pub fn init_listeners() {
+    aggregator::init();
+    cache::init();
+    diff::init();
     downloader::init();
+    exporter::init();
+    scheduler::init();
 }
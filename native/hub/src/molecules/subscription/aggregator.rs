@@ -0,0 +1,84 @@
+// 多订阅聚合
+// 将多个订阅合并为一份配置，每个来源一个代理组，并保留基准订阅的规则
+
+use crate::atoms::SubscriptionAggregator;
+use crate::molecules::shared_types::{
+    AggregatedSourceReport, SubscriptionParseOptions, SubscriptionSource,
+};
+use rinf::{DartSignal, RustSignal};
+use serde::{Deserialize, Serialize};
+
+// Dart → Rust：聚合订阅请求
+#[derive(Deserialize, DartSignal)]
+pub struct AggregateSubscriptionsRequest {
+    pub request_id: String, // 请求标识符，用于响应匹配
+    pub sources: Vec<SubscriptionSource>,
+    pub rule_source_index: u32, // 提供规则的来源序号（从 0 开始）
+    pub options: SubscriptionParseOptions,
+}
+
+// Rust → Dart：聚合订阅响应
+#[derive(Serialize, RustSignal)]
+pub struct AggregateSubscriptionsResponse {
+    pub request_id: String, // 请求标识符，用于请求匹配
+    pub is_successful: bool,
+    pub result_config: String,
+    pub source_reports: Vec<AggregatedSourceReport>,
+    pub error_message: Option<String>,
+}
+
+impl AggregateSubscriptionsRequest {
+    pub fn handle(self) {
+        log::info!(
+            "收到订阅聚合请求 [{}]，来源数：{}",
+            self.request_id,
+            self.sources.len()
+        );
+
+        let response = match SubscriptionAggregator::aggregate(
+            &self.sources,
+            self.rule_source_index as usize,
+            &self.options,
+        ) {
+            Ok((result_config, source_reports)) => {
+                log::info!(
+                    "订阅聚合成功 [{}]，成功来源：{}/{}",
+                    self.request_id,
+                    source_reports.iter().filter(|r| r.is_successful).count(),
+                    source_reports.len()
+                );
+                AggregateSubscriptionsResponse {
+                    request_id: self.request_id,
+                    is_successful: true,
+                    result_config,
+                    source_reports,
+                    error_message: None,
+                }
+            }
+            Err(e) => {
+                log::error!("订阅聚合失败 [{}]：{}", self.request_id, e);
+                AggregateSubscriptionsResponse {
+                    request_id: self.request_id,
+                    is_successful: false,
+                    result_config: String::new(),
+                    source_reports: vec![],
+                    error_message: Some(e),
+                }
+            }
+        };
+
+        response.send_signal_to_dart();
+    }
+}
+
+pub fn init() {
+    use tokio::spawn;
+
+    // 聚合订阅请求监听器
+    spawn(async {
+        let receiver = AggregateSubscriptionsRequest::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
@@ -0,0 +1,181 @@
+// 订阅缓存
+// 按订阅 URL 的 SHA-256 摘要持久化最近一次成功下载的内容与 ETag/Last-Modified 校验信息。
+// 文件名与条目中只有摘要，不保存 URL 本身；但缓存的订阅内容仍是明文。
+
+use super::downloader::SubscriptionInfoData;
+use rinf::DartSignal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// 临时文件序号，保证并发写入时各自使用不同的临时文件
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// Dart → Rust：删除订阅时清除其缓存
+#[derive(Deserialize, DartSignal)]
+pub struct RemoveSubscriptionCacheRequest {
+    pub url: String,
+}
+
+// 缓存条目
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CachedSubscription {
+    pub url_digest: String, // 订阅 URL 的 SHA-256 摘要（小写十六进制）
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fetched_at: i64, // 最近一次确认内容有效的时间（Unix 秒）
+    pub content: String,
+    pub subscription_info: Option<SubscriptionInfoData>,
+}
+
+impl CachedSubscription {
+    // 缓存内容距今的秒数
+    pub fn age_seconds(&self) -> u64 {
+        (chrono::Utc::now().timestamp() - self.fetched_at).max(0) as u64
+    }
+}
+
+// 订阅缓存目录
+pub struct SubscriptionCache {
+    dir: PathBuf,
+}
+
+impl SubscriptionCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    // 使用应用数据目录下的默认缓存目录
+    pub fn open_default() -> Self {
+        Self::new(crate::atoms::path_service::subscription_cache_dir())
+    }
+
+    // URL 的 SHA-256 摘要，作为缓存键
+    pub fn url_digest(url: &str) -> String {
+        Sha256::digest(url.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    // 读取 URL 对应的缓存，不存在或损坏时返回 None
+    pub fn load(&self, url: &str) -> Option<CachedSubscription> {
+        let digest = Self::url_digest(url);
+        let data = std::fs::read_to_string(self.entry_path(&digest)).ok()?;
+        match serde_json::from_str::<CachedSubscription>(&data) {
+            Ok(entry) if entry.url_digest == digest => Some(entry),
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("订阅缓存已损坏，忽略：{}", e);
+                None
+            }
+        }
+    }
+
+    // 写入缓存（先写独占的临时文件再替换，避免中断或并发写入时留下半个文件）
+    pub fn store(&self, entry: &CachedSubscription) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("创建缓存目录失败：{}", e))?;
+
+        let data =
+            serde_json::to_string(entry).map_err(|e| format!("序列化订阅缓存失败：{}", e))?;
+        let path = self.entry_path(&entry.url_digest);
+        let temp_path = self.dir.join(format!(
+            "{}.{}-{}.tmp",
+            entry.url_digest,
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        if let Err(e) = std::fs::write(&temp_path, data) {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(format!("写入订阅缓存失败：{}", e));
+        }
+        if let Err(e) = std::fs::rename(&temp_path, &path) {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(format!("替换订阅缓存失败：{}", e));
+        }
+
+        log::debug!("订阅缓存已更新：{}", path.display());
+        Ok(())
+    }
+
+    // 删除 URL 对应的缓存，缓存不存在时视为成功
+    pub fn remove(&self, url: &str) -> Result<(), String> {
+        match std::fs::remove_file(self.entry_path(&Self::url_digest(url))) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("删除订阅缓存失败：{}", e)),
+        }
+    }
+
+    // 缓存文件路径：以 URL 摘要命名
+    fn entry_path(&self, digest: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", digest))
+    }
+}
+
+impl RemoveSubscriptionCacheRequest {
+    pub fn handle(self) {
+        match SubscriptionCache::open_default().remove(&self.url) {
+            Ok(()) => log::info!("已清除订阅缓存"),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+}
+
+pub fn init() {
+    tokio::spawn(async {
+        let receiver = RemoveSubscriptionCacheRequest::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_round_trip() -> Result<(), String> {
+        let dir = std::env::temp_dir().join(format!("subscription_cache_{}", std::process::id()));
+        let cache = SubscriptionCache::new(dir.clone());
+        let url = "https://example.com/sub?token=secret";
+
+        assert!(cache.load(url).is_none());
+
+        let entry = CachedSubscription {
+            url_digest: SubscriptionCache::url_digest(url),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            fetched_at: chrono::Utc::now().timestamp() - 60,
+            content: "proxies: []".to_string(),
+            subscription_info: None,
+        };
+        cache.store(&entry)?;
+
+        let loaded = cache.load(url).ok_or("缓存未命中")?;
+        assert_eq!(loaded.etag.as_deref(), Some("\"abc\""));
+        assert!(loaded.age_seconds() >= 60);
+        assert!(cache.load("https://example.com/other").is_none());
+
+        // 缓存文件中不含 URL 与令牌，也没有残留的临时文件
+        let data = std::fs::read_to_string(
+            dir.join(format!("{}.json", SubscriptionCache::url_digest(url))),
+        )
+        .map_err(|e| e.to_string())?;
+        assert!(!data.contains("secret"));
+        assert_eq!(
+            std::fs::read_dir(&dir).map_err(|e| e.to_string())?.count(),
+            1
+        );
+
+        cache.remove(url)?;
+        assert!(cache.load(url).is_none());
+        cache.remove(url)?;
+
+        std::fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+// 订阅版本差异
+// 比较订阅更新前后的两份配置，返回节点、代理组与规则的变化摘要
+
+use crate::atoms::ProfileDiffer;
+use crate::molecules::shared_types::ProfileDiff;
+use rinf::{DartSignal, RustSignal};
+use serde::{Deserialize, Serialize};
+
+// Dart → Rust：比较订阅版本请求
+#[derive(Deserialize, DartSignal)]
+pub struct DiffSubscriptionRequest {
+    pub request_id: String,  // 请求标识符，用于响应匹配
+    pub old_content: String, // 更新前的配置 YAML
+    pub new_content: String, // 更新后的配置 YAML
+}
+
+// Rust → Dart：比较订阅版本响应
+#[derive(Serialize, RustSignal)]
+pub struct DiffSubscriptionResponse {
+    pub request_id: String, // 请求标识符，用于请求匹配
+    pub is_successful: bool,
+    pub diff: Option<ProfileDiff>,
+    pub error_message: Option<String>,
+}
+
+impl DiffSubscriptionRequest {
+    pub fn handle(self) {
+        log::info!("收到订阅差异比较请求 [{}]", self.request_id);
+
+        let response = match ProfileDiffer::diff(&self.old_content, &self.new_content) {
+            Ok(diff) => {
+                log::info!(
+                    "订阅差异 [{}]：新增 {}，移除 {}，改名 {}，修改 {}",
+                    self.request_id,
+                    diff.added_nodes.len(),
+                    diff.removed_nodes.len(),
+                    diff.renamed_nodes.len(),
+                    diff.modified_nodes.len()
+                );
+                DiffSubscriptionResponse {
+                    request_id: self.request_id,
+                    is_successful: true,
+                    diff: Some(diff),
+                    error_message: None,
+                }
+            }
+            Err(e) => {
+                log::error!("订阅差异比较失败 [{}]：{}", self.request_id, e);
+                DiffSubscriptionResponse {
+                    request_id: self.request_id,
+                    is_successful: false,
+                    diff: None,
+                    error_message: Some(e),
+                }
+            }
+        };
+
+        response.send_signal_to_dart();
+    }
+}
+
+pub fn init() {
+    use tokio::spawn;
+
+    // 订阅差异比较请求监听器
+    spawn(async {
+        let receiver = DiffSubscriptionRequest::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
@@ -1,21 +1,49 @@
 // 订阅下载器
-// 处理订阅配置的 HTTP 下载，支持多种代理模式
+// 处理订阅配置的 HTTP 下载，支持多种代理模式、回退重试与条件请求缓存；
+// 响应体流式读取并限制大小，支持 gzip/deflate/br 压缩与字符集检测
 
-use crate::molecules::ProxyMode;
-use reqwest::{Client, Proxy};
-use rinf::{DartSignal, RustSignal};
+use super::cache::{CachedSubscription, SubscriptionCache};
+use crate::molecules::http_client::{
+    BoxError, DownloadPlan, HttpStatusError, TlsMaterial, build_get, decode_text, decompress_body,
+    read_body_limited, redact_url,
+};
+use crate::molecules::{DownloadRetryPolicy, HttpRequestOptions, ProxyMode};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use reqwest::StatusCode;
+use reqwest::header::{
+    ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+    LAST_MODIFIED,
+};
+use rinf::{DartSignal, RustSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
 
-// Dart → Rust：下载订阅请求
-#[derive(Deserialize, DartSignal)]
+// Dart → Rust：下载订阅请求（也作为自动更新计划中的下载参数）
+#[derive(Deserialize, Serialize, Clone, Debug, DartSignal, SignalPiece)]
 pub struct DownloadSubscriptionRequest {
     pub request_id: String, // 请求标识符，用于响应匹配
     pub url: String,
     pub proxy_mode: ProxyMode,
     pub user_agent: String,
     pub timeout_seconds: u64,
-    pub mixed_port: u16, // Clash 混合端口
+    pub mixed_port: u16,                           // Clash 混合端口
+    pub retry_policy: Option<DownloadRetryPolicy>, // 代理模式回退与重试，未设置时只尝试一次
+    pub max_size_bytes: Option<u64>,               // 内容大小上限（解压后），未设置时使用默认上限
+    pub http_options: Option<HttpRequestOptions>,  // 自定义请求头、客户端证书与额外 CA
+}
+
+// 默认订阅大小上限：32 MiB
+const DEFAULT_MAX_SIZE_BYTES: u64 = 32 * 1024 * 1024;
+
+// 进度通知的最小间隔字节数，避免每个数据块都发送信号
+const PROGRESS_STEP_BYTES: u64 = 64 * 1024;
+
+// Rust → Dart：订阅下载进度通知
+#[derive(Serialize, RustSignal)]
+pub struct DownloadSubscriptionProgress {
+    pub request_id: String,
+    pub progress: f64,   // 0.0 - 1.0，服务器未提供总长度时为 0
+    pub downloaded: u64, // 已接收字节数（压缩传输时为压缩后的字节数）
+    pub total: u64,      // 总字节数（Content-Length），未知时为 0
 }
 
 // Rust → Dart：下载订阅响应
@@ -23,185 +51,418 @@ pub struct DownloadSubscriptionRequest {
 pub struct DownloadSubscriptionResponse {
     pub request_id: String, // 请求标识符，用于请求匹配
     pub is_successful: bool,
+    pub outcome: DownloadOutcome,
     pub content: String,
     pub subscription_info: Option<SubscriptionInfoData>,
+    pub cache_age_seconds: Option<u64>, // 内容来自缓存时，距上次确认有效的秒数
+    pub proxy_mode_used: Option<ProxyMode>, // 请求成功时实际使用的代理模式
     pub error_message: Option<String>,
 }
 
+// 下载结果
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, SignalPiece)]
+pub enum DownloadOutcome {
+    Downloaded = 0,     // 下载到新内容
+    NotModified = 1,    // 服务器返回 304，内容取自缓存
+    CachedFallback = 2, // 下载失败，回退到上次成功的内容
+    Failed = 3,         // 下载失败且没有可用缓存
+}
+
 // 订阅信息
-#[derive(Serialize, Deserialize, Clone, Debug, rinf::SignalPiece)]
+#[derive(Serialize, Deserialize, Clone, Debug, SignalPiece)]
 pub struct SubscriptionInfoData {
     pub upload: Option<u64>,
     pub download: Option<u64>,
     pub total: Option<u64>,
     pub expire: Option<i64>,
+    pub update_interval_hours: Option<u32>, // profile-update-interval（小时）
+    pub profile_name: Option<String>,       // content-disposition 中的文件名
+    pub web_page_url: Option<String>,       // profile-web-page-url
+    pub support_url: Option<String>,        // support-url
+    pub announcement: Option<String>,       // announce（已解码）
+}
+
+// 单次 HTTP 请求的结果
+pub enum FetchResult {
+    Modified {
+        content: String,
+        subscription_info: Option<SubscriptionInfoData>,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+    NotModified {
+        subscription_info: Option<SubscriptionInfoData>,
+    },
+}
+
+// 带缓存的下载结果
+pub struct CachedDownload {
+    pub outcome: DownloadOutcome,
+    pub content: String,
+    pub subscription_info: Option<SubscriptionInfoData>,
+    pub cache_age_seconds: Option<u64>,
+    pub proxy_mode_used: Option<ProxyMode>,
+    pub error_message: Option<String>,
+}
+
+impl CachedDownload {
+    // 是否拿到了可用内容（包括回退到缓存）
+    pub fn is_successful(&self) -> bool {
+        self.outcome != DownloadOutcome::Failed
+    }
+
+    // 构建失败结果
+    fn failure(error: String) -> Self {
+        Self {
+            outcome: DownloadOutcome::Failed,
+            content: String::new(),
+            subscription_info: None,
+            cache_age_seconds: None,
+            proxy_mode_used: None,
+            error_message: Some(error),
+        }
+    }
 }
 
 impl DownloadSubscriptionRequest {
     pub async fn handle(self) {
-        log::info!("收到下载订阅请求 [{}]：{}", self.request_id, self.url);
-
-        let result = download_subscription(
-            &self.url,
-            self.proxy_mode,
-            &self.user_agent,
-            self.timeout_seconds,
-            self.mixed_port,
-        )
-        .await;
-
-        let response = match result {
-            Ok((content, info)) => {
-                log::info!(
-                    "订阅下载成功 [{}]，内容长度：{} 字节",
-                    self.request_id,
-                    content.len()
-                );
-                DownloadSubscriptionResponse {
-                    request_id: self.request_id,
-                    is_successful: true,
+        log::info!(
+            "收到下载订阅请求 [{}]：{}",
+            self.request_id,
+            redact_url(&self.url)
+        );
+
+        let result = self.download_with_cache().await;
+        match result.outcome {
+            DownloadOutcome::Failed => log::error!(
+                "订阅下载失败 [{}]：{}",
+                self.request_id,
+                result.error_message.as_deref().unwrap_or_default()
+            ),
+            outcome => log::info!(
+                "订阅下载完成 [{}]：{:?}，内容长度：{} 字节",
+                self.request_id,
+                outcome,
+                result.content.len()
+            ),
+        }
+
+        DownloadSubscriptionResponse {
+            request_id: self.request_id,
+            is_successful: result.is_successful(),
+            outcome: result.outcome,
+            content: result.content,
+            subscription_info: result.subscription_info,
+            cache_age_seconds: result.cache_age_seconds,
+            proxy_mode_used: result.proxy_mode_used,
+            error_message: result.error_message,
+        }
+        .send_signal_to_dart();
+    }
+
+    // 下载订阅并维护本地缓存：
+    // 有缓存时发送条件请求，304 时返回缓存内容，下载失败时回退到上次成功的内容。
+    pub async fn download_with_cache(&self) -> CachedDownload {
+        let cache = SubscriptionCache::open_default();
+        let cached = cache.load(&self.url);
+
+        let result = download_subscription(self, cached.as_ref()).await;
+
+        match (result, cached) {
+            (
+                Ok((
+                    FetchResult::Modified {
+                        content,
+                        subscription_info,
+                        etag,
+                        last_modified,
+                    },
+                    proxy_mode_used,
+                )),
+                _,
+            ) => {
+                let entry = CachedSubscription {
+                    url_digest: SubscriptionCache::url_digest(&self.url),
+                    etag,
+                    last_modified,
+                    fetched_at: chrono::Utc::now().timestamp(),
+                    content: content.clone(),
+                    subscription_info: subscription_info.clone(),
+                };
+                if let Err(e) = cache.store(&entry) {
+                    log::warn!("保存订阅缓存失败：{}", e);
+                }
+                CachedDownload {
+                    outcome: DownloadOutcome::Downloaded,
                     content,
-                    subscription_info: info,
+                    subscription_info,
+                    cache_age_seconds: None,
+                    proxy_mode_used: Some(proxy_mode_used),
+                    error_message: None,
+                }
+            }
+            (
+                Ok((FetchResult::NotModified { subscription_info }, proxy_mode_used)),
+                Some(mut entry),
+            ) => {
+                log::info!("订阅未变化，使用缓存内容：{}", redact_url(&self.url));
+                entry.fetched_at = chrono::Utc::now().timestamp();
+                if subscription_info.is_some() {
+                    entry.subscription_info = subscription_info;
+                }
+                if let Err(e) = cache.store(&entry) {
+                    log::warn!("更新订阅缓存失败：{}", e);
+                }
+                CachedDownload {
+                    outcome: DownloadOutcome::NotModified,
+                    content: entry.content,
+                    subscription_info: entry.subscription_info,
+                    cache_age_seconds: Some(0),
+                    proxy_mode_used: Some(proxy_mode_used),
                     error_message: None,
                 }
             }
-            Err(e) => {
-                log::error!("订阅下载失败 [{}]：{}", self.request_id, e);
-                DownloadSubscriptionResponse {
-                    request_id: self.request_id,
-                    is_successful: false,
-                    content: String::new(),
-                    subscription_info: None,
+            (Ok((FetchResult::NotModified { .. }, _)), None) => {
+                CachedDownload::failure("服务器返回 304，但本地没有缓存".to_string())
+            }
+            (Err(e), Some(entry)) => {
+                let age = entry.age_seconds();
+                log::warn!("订阅下载失败：{}，回退到 {} 秒前的缓存", e, age);
+                CachedDownload {
+                    outcome: DownloadOutcome::CachedFallback,
+                    content: entry.content,
+                    subscription_info: entry.subscription_info,
+                    cache_age_seconds: Some(age),
+                    proxy_mode_used: None,
                     error_message: Some(e.to_string()),
                 }
             }
-        };
-
-        response.send_signal_to_dart();
+            (Err(e), None) => CachedDownload::failure(e.to_string()),
+        }
     }
 }
 
-// 下载订阅配置并返回内容与订阅信息。
-// 支持代理模式、超时与自定义 User-Agent。
+// 下载订阅配置，返回请求结果与实际使用的代理模式。
+// 支持代理模式回退、超时与自定义 User-Agent；提供缓存时发送条件请求头。
 pub async fn download_subscription(
-    url: &str,
-    proxy_mode: ProxyMode,
-    user_agent: &str,
-    timeout_seconds: u64,
-    mixed_port: u16,
-) -> Result<(String, Option<SubscriptionInfoData>), Box<dyn std::error::Error + Send + Sync>> {
-    log::info!("开始下载订阅：{}", url);
-    log::info!("代理模式：{:?}", proxy_mode);
-
-    // 创建 HTTP 客户端
-    let client = create_http_client(proxy_mode, timeout_seconds, mixed_port)?;
-
-    // 发送 HTTP GET 请求
-    let response = client
-        .get(url)
-        .header("User-Agent", user_agent)
-        .send()
-        .await?;
+    request: &DownloadSubscriptionRequest,
+    cached: Option<&CachedSubscription>,
+) -> Result<(FetchResult, ProxyMode), BoxError> {
+    log::info!("开始下载订阅：{}", redact_url(&request.url));
+    log::info!("代理模式：{:?}", request.proxy_mode);
 
-    // 检查 HTTP 状态码
-    let status = response.status();
-    if !status.is_success() {
-        return Err(format!(
-            "HTTP {}: {}",
-            status.as_u16(),
-            status.canonical_reason().unwrap_or("Unknown")
-        )
-        .into());
-    }
+    let max_bytes = request
+        .max_size_bytes
+        .filter(|bytes| *bytes > 0)
+        .unwrap_or(DEFAULT_MAX_SIZE_BYTES);
+    let plan = DownloadPlan::new(
+        request.proxy_mode,
+        request.timeout_seconds,
+        request.mixed_port,
+        request.retry_policy.as_ref(),
+        TlsMaterial::load(request.http_options.as_ref())?,
+    );
+    let headers = request
+        .http_options
+        .as_ref()
+        .map(|options| options.headers.as_slice())
+        .unwrap_or_default();
 
-    // 解析订阅信息头
-    let subscription_info = parse_subscription_info(response.headers());
+    plan.run(|client| async move {
+        // 构建 HTTP GET 请求，附带缓存校验信息
+        let mut http_request = build_get(&client, &request.url, &request.user_agent, headers)?
+            .header(ACCEPT_ENCODING, "gzip, deflate, br");
+        if let Some(cached) = cached {
+            if let Some(etag) = &cached.etag {
+                http_request = http_request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                http_request = http_request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
 
-    // 读取响应体
-    let content = response.text().await?;
+        let response = http_request.send().await?;
 
-    if content.is_empty() {
-        return Err("订阅内容为空".into());
-    }
+        // 检查 HTTP 状态码
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            log::info!("订阅未变化（HTTP 304）");
+            return Ok(FetchResult::NotModified {
+                subscription_info: parse_subscription_info(response.headers()),
+            });
+        }
+        if !status.is_success() {
+            return Err(HttpStatusError(status).into());
+        }
 
-    log::info!("订阅下载成功，内容长度：{} 字节", content.len());
+        // 解析订阅信息头与缓存校验头
+        let headers = response.headers();
+        let subscription_info = parse_subscription_info(headers);
+        let header_string = |name| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|s| s.to_string())
+        };
+        let etag = header_string(ETAG);
+        let last_modified = header_string(LAST_MODIFIED);
+        let content_encoding = header_string(CONTENT_ENCODING);
+        let content_type = header_string(CONTENT_TYPE);
 
-    Ok((content, subscription_info))
+        // 流式读取响应体并通知进度，随后解压与解码
+        let mut last_reported = 0;
+        let body = read_body_limited(response, max_bytes, |downloaded, total| {
+            if downloaded - last_reported >= PROGRESS_STEP_BYTES || Some(downloaded) == total {
+                last_reported = downloaded;
+                send_progress(&request.request_id, downloaded, total);
+            }
+        })
+        .await?;
+        let body = decompress_body(body, content_encoding.as_deref(), max_bytes)?;
+        let content = decode_text(&body, content_type.as_deref());
+
+        if content.is_empty() {
+            return Err("订阅内容为空".into());
+        }
+
+        log::info!("订阅下载成功，内容长度：{} 字节", content.len());
+
+        Ok(FetchResult::Modified {
+            content,
+            subscription_info,
+            etag,
+            last_modified,
+        })
+    })
+    .await
 }
 
-// 创建 HTTP 客户端
-fn create_http_client(
-    proxy_mode: ProxyMode,
-    timeout_seconds: u64,
-    mixed_port: u16,
-) -> Result<Client, Box<dyn std::error::Error + Send + Sync>> {
-    let mut builder = Client::builder()
-        .timeout(Duration::from_secs(timeout_seconds))
-        .connect_timeout(Duration::from_secs(10)) // 连接超时
-        .danger_accept_invalid_certs(false); // 验证 SSL 证书
+// 发送下载进度通知到 Dart
+fn send_progress(request_id: &str, downloaded: u64, total: Option<u64>) {
+    let total = total.unwrap_or(0);
+    let progress = if total > 0 {
+        (downloaded as f64 / total as f64).min(1.0)
+    } else {
+        0.0
+    };
 
-    // 根据代理模式配置客户端
-    match proxy_mode {
-        ProxyMode::Direct => {
-            log::debug!("使用直连模式");
-            // 不设置代理
-        }
-        ProxyMode::System => {
-            log::debug!("使用系统代理模式");
-            // reqwest 默认会读取系统环境变量（HTTP_PROXY, HTTPS_PROXY）
-            // 无需额外配置
-        }
-        ProxyMode::Core => {
-            log::debug!("使用核心代理模式：127.0.0.1:{}", mixed_port);
-            let proxy_url = format!("http://127.0.0.1:{}", mixed_port);
-            let proxy = Proxy::all(&proxy_url)?;
-            builder = builder.proxy(proxy);
-        }
+    DownloadSubscriptionProgress {
+        request_id: request_id.to_string(),
+        progress,
+        downloaded,
+        total,
     }
-
-    Ok(builder.build()?)
+    .send_signal_to_dart();
 }
 
-// 解析订阅信息头（subscription-userinfo）。
-// 示例：upload=0; download=123; total=1073741824; expire=1735689600
+// 解析订阅响应头：流量信息、更新间隔、配置名称、主页与公告。
+// subscription-userinfo 示例：upload=0; download=123; total=1073741824; expire=1735689600
 fn parse_subscription_info(headers: &reqwest::header::HeaderMap) -> Option<SubscriptionInfoData> {
-    let header_value = headers.get("subscription-userinfo")?.to_str().ok()?;
-
-    log::debug!("解析订阅信息头：{}", header_value);
-
-    let mut upload = None;
-    let mut download = None;
-    let mut total = None;
-    let mut expire = None;
-
-    // 解析键值对
-    for pair in header_value.split(';') {
-        let pair = pair.trim();
-        if let Some((key, value)) = pair.split_once('=') {
-            let key = key.trim();
-            let value = value.trim();
-
-            match key {
-                "upload" => upload = value.parse::<u64>().ok(),
-                "download" => download = value.parse::<u64>().ok(),
-                "total" => total = value.parse::<u64>().ok(),
-                "expire" => expire = value.parse::<i64>().ok(),
-                _ => {}
+    // 非 ASCII 的头部值按 UTF-8 宽松解码
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .map(|v| String::from_utf8_lossy(v.as_bytes()).trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let mut info = SubscriptionInfoData {
+        upload: None,
+        download: None,
+        total: None,
+        expire: None,
+        update_interval_hours: None,
+        profile_name: None,
+        web_page_url: None,
+        support_url: None,
+        announcement: None,
+    };
+
+    if let Some(header_value) = header("subscription-userinfo") {
+        log::debug!("解析订阅信息头：{}", header_value);
+
+        // 解析键值对
+        for pair in header_value.split(';') {
+            let pair = pair.trim();
+            if let Some((key, value)) = pair.split_once('=') {
+                let key = key.trim();
+                let value = value.trim();
+
+                match key {
+                    "upload" => info.upload = value.parse::<u64>().ok(),
+                    "download" => info.download = value.parse::<u64>().ok(),
+                    "total" => info.total = value.parse::<u64>().ok(),
+                    "expire" => info.expire = value.parse::<i64>().ok(),
+                    _ => {}
+                }
             }
         }
     }
 
+    info.update_interval_hours = header("profile-update-interval")
+        .and_then(|v| v.parse::<u32>().ok())
+        .filter(|hours| *hours > 0);
+    info.profile_name = header("content-disposition").and_then(|v| parse_profile_name(&v));
+    info.web_page_url = header("profile-web-page-url");
+    info.support_url = header("support-url");
+    info.announcement = header("announce").map(|v| decode_announcement(&v));
+
     // 如果至少有一个字段有值，则返回订阅信息
-    if upload.is_some() || download.is_some() || total.is_some() || expire.is_some() {
-        Some(SubscriptionInfoData {
-            upload,
-            download,
-            total,
-            expire,
-        })
-    } else {
-        None
+    let has_value = info.upload.is_some()
+        || info.download.is_some()
+        || info.total.is_some()
+        || info.expire.is_some()
+        || info.update_interval_hours.is_some()
+        || info.profile_name.is_some()
+        || info.web_page_url.is_some()
+        || info.support_url.is_some()
+        || info.announcement.is_some();
+
+    has_value.then_some(info)
+}
+
+// 从 content-disposition 中提取配置名称，优先使用 RFC 5987 的 filename*
+// 示例：attachment; filename*=UTF-8''%E6%9C%BA%E5%9C%BA.yaml
+fn parse_profile_name(disposition: &str) -> Option<String> {
+    let mut filename = None;
+    let mut extended_filename = None;
+
+    for param in disposition.split(';') {
+        let Some((key, value)) = param.trim().split_once('=') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename*" => {
+                // 格式：字符集'语言'编码值
+                let encoded = value.splitn(3, '\'').nth(2).unwrap_or(value);
+                extended_filename = urlencoding::decode(encoded).ok().map(|s| s.to_string());
+            }
+            "filename" => filename = Some(value.to_string()),
+            _ => {}
+        }
     }
+
+    let name = extended_filename.or(filename)?;
+    let name = [".yaml", ".yml", ".txt", ".conf"]
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext))
+        .unwrap_or(&name)
+        .trim()
+        .to_string();
+
+    (!name.is_empty()).then_some(name)
+}
+
+// 解码公告：仅解码带 base64: 前缀的内容，其余按纯文本原样返回
+// （"Test"、"2024" 等普通文本恰好也是合法 Base64，不能凭字符集猜测）
+fn decode_announcement(value: &str) -> String {
+    value
+        .strip_prefix("base64:")
+        .and_then(|encoded| BASE64.decode(encoded.trim().as_bytes()).ok())
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or_else(|| value.to_string())
 }
 
 // 初始化 Dart 信号监听器
@@ -218,3 +479,46 @@ pub fn init() {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn test_parse_subscription_headers() -> Result<(), String> {
+        let mut headers = HeaderMap::new();
+        let mut insert = |name: &'static str, value: &str| -> Result<(), String> {
+            let value = HeaderValue::from_str(value).map_err(|e| e.to_string())?;
+            headers.insert(name, value);
+            Ok(())
+        };
+        insert(
+            "subscription-userinfo",
+            "upload=10; download=20; total=1073741824; expire=1735689600",
+        )?;
+        insert("profile-update-interval", "12")?;
+        insert(
+            "content-disposition",
+            "attachment; filename=\"sub.yaml\"; filename*=UTF-8''%E6%9C%BA%E5%9C%BA.yaml",
+        )?;
+        insert("profile-web-page-url", "https://example.com")?;
+        insert("announce", "base64:5qyi6L+O5L2/55So")?;
+
+        let info = parse_subscription_info(&headers).ok_or("未解析到订阅信息")?;
+        assert_eq!(info.total, Some(1073741824));
+        assert_eq!(info.update_interval_hours, Some(12));
+        assert_eq!(info.profile_name.as_deref(), Some("机场"));
+        assert_eq!(info.web_page_url.as_deref(), Some("https://example.com"));
+        assert_eq!(info.support_url, None);
+        assert_eq!(info.announcement.as_deref(), Some("欢迎使用"));
+
+        assert_eq!(decode_announcement("Hello world"), "Hello world");
+        for plain in ["Test", "news", "2024", "ABCDEFGH"] {
+            assert_eq!(decode_announcement(plain), plain);
+        }
+        assert_eq!(decode_announcement("base64:!!"), "base64:!!");
+        assert!(parse_subscription_info(&HeaderMap::new()).is_none());
+        Ok(())
+    }
+}
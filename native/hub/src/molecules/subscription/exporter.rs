@@ -0,0 +1,120 @@
+// 订阅节点导出器
+// 将订阅中的代理节点导出为分享链接或 Base64 订阅内容
+
+use crate::atoms::{ProxyParser, ProxySerializer};
+use rinf::{DartSignal, RustSignal};
+use serde::{Deserialize, Serialize};
+
+// Dart → Rust：导出代理链接请求
+#[derive(Deserialize, DartSignal)]
+pub struct ExportProxyLinksRequest {
+    pub request_id: String,       // 请求标识符，用于响应匹配
+    pub content: String,          // 订阅内容（Clash YAML 或链接列表）
+    pub proxy_names: Vec<String>, // 需要导出的节点名称，为空时导出全部
+    pub is_base64_encoded: bool,  // 是否打包为 Base64 订阅内容
+}
+
+// Rust → Dart：导出代理链接响应
+#[derive(Serialize, RustSignal)]
+pub struct ExportProxyLinksResponse {
+    pub request_id: String, // 请求标识符，用于请求匹配
+    pub is_successful: bool,
+    pub links: Vec<String>,
+    pub bundle: String,               // 换行拼接的链接列表，或其 Base64 编码
+    pub skipped_proxies: Vec<String>, // 无法导出的节点（名称：原因）
+    pub error_message: Option<String>,
+}
+
+impl ExportProxyLinksRequest {
+    pub fn handle(self) {
+        log::info!(
+            "收到导出代理链接请求 [{}]，内容长度：{}字节",
+            self.request_id,
+            self.content.len()
+        );
+
+        let response = match export_proxy_links(&self.content, &self.proxy_names) {
+            Ok((links, skipped)) => {
+                log::info!(
+                    "代理链接导出成功 [{}]，成功{}个，跳过{}个",
+                    self.request_id,
+                    links.len(),
+                    skipped.len()
+                );
+                let bundle = if self.is_base64_encoded {
+                    ProxySerializer::to_base64_bundle(&links)
+                } else {
+                    links.join("\n")
+                };
+                ExportProxyLinksResponse {
+                    request_id: self.request_id,
+                    is_successful: true,
+                    links,
+                    bundle,
+                    skipped_proxies: skipped,
+                    error_message: None,
+                }
+            }
+            Err(e) => {
+                log::error!("代理链接导出失败 [{}]：{}", self.request_id, e);
+                ExportProxyLinksResponse {
+                    request_id: self.request_id,
+                    is_successful: false,
+                    links: vec![],
+                    bundle: String::new(),
+                    skipped_proxies: vec![],
+                    error_message: Some(e),
+                }
+            }
+        };
+
+        response.send_signal_to_dart();
+    }
+}
+
+// 解析订阅内容并导出指定节点。
+// 返回成功导出的链接与跳过的节点（名称：原因）。
+pub fn export_proxy_links(
+    content: &str,
+    proxy_names: &[String],
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let config = ProxyParser::parse_subscription(content)?;
+    let proxies = ProxyParser::parse_yaml_json_proxies(&config)?;
+
+    let selected: Vec<_> = if proxy_names.is_empty() {
+        proxies
+    } else {
+        proxies
+            .into_iter()
+            .filter(|p| {
+                p["name"]
+                    .as_str()
+                    .is_some_and(|name| proxy_names.iter().any(|n| n == name))
+            })
+            .collect()
+    };
+
+    if selected.is_empty() {
+        return Err("未找到可导出的代理节点".to_string());
+    }
+
+    let (links, skipped) = ProxySerializer::to_links(&selected);
+    let skipped = skipped
+        .into_iter()
+        .map(|(name, reason)| format!("{}：{}", name, reason))
+        .collect();
+
+    Ok((links, skipped))
+}
+
+pub fn init() {
+    use tokio::spawn;
+
+    // 导出代理链接请求监听器
+    spawn(async {
+        let receiver = ExportProxyLinksRequest::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle();
+        }
+    });
+}
@@ -0,0 +1,536 @@
+// 订阅自动更新调度器
+// 在 Rust 侧维护订阅列表与刷新间隔，后台定时刷新（随机抖动、失败指数退避、并发限制），
+// 系统休眠时暂停、唤醒后恢复，Flutter 界面挂起时刷新也不会中断。
+// 下载到新内容后在 Rust 侧解析并写入订阅配置文件，Dart 只需根据结果更新状态与重载配置。
+// 替换已有配置时附带新旧版本差异，供界面提示节点变化。
+
+use super::cache::SubscriptionCache;
+use super::downloader::{
+    CachedDownload, DownloadOutcome, DownloadSubscriptionRequest, SubscriptionInfoData,
+};
+use crate::atoms::{ProfileDiffer, ProxyParser};
+use crate::molecules::ProxyMode;
+use crate::molecules::shared_types::{ParseReport, ProfileDiff, SubscriptionParseOptions};
+use crate::molecules::system_operations::{PowerEventType, subscribe_power_events};
+use once_cell::sync::Lazy;
+use rand::Rng;
+use rinf::{DartSignal, RustSignal, SignalPiece};
+use serde::{Deserialize, Serialize};
+use serde_yaml_ng::Value as YamlValue;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+
+// 调度检查间隔
+const TICK_INTERVAL: Duration = Duration::from_secs(15);
+// 失败重试的初始退避与上限（秒）
+const BACKOFF_BASE_SECONDS: i64 = 60;
+const BACKOFF_MAX_SECONDS: i64 = 6 * 3600;
+// 唤醒后等待网络恢复的时间（秒）
+const RESUME_DELAY_SECONDS: i64 = 30;
+// 刷新间隔的随机抖动比例
+const JITTER_RATIO: f64 = 0.1;
+
+// 自动更新的订阅
+#[derive(Deserialize, Serialize, Clone, Debug, SignalPiece)]
+pub struct ScheduledSubscription {
+    pub subscription_id: String,
+    pub request: DownloadSubscriptionRequest, // 下载参数
+    pub interval_minutes: u32, // 刷新间隔，订阅响应头提供 profile-update-interval 时以其为准
+    pub last_refreshed_at: Option<i64>, // 上次更新时间（Unix 秒），用于计算首次到期时间
+    pub config_path: String,   // 订阅配置文件路径，刷新到新内容后写入解析结果
+    pub parse_options: SubscriptionParseOptions,
+}
+
+// Dart → Rust：设置自动更新计划（整体替换订阅列表）
+#[derive(Deserialize, DartSignal)]
+pub struct ConfigureSubscriptionScheduleRequest {
+    pub subscriptions: Vec<ScheduledSubscription>,
+    pub max_concurrency: u32,
+}
+
+// Dart → Rust：暂停或恢复自动更新
+#[derive(Deserialize, DartSignal)]
+pub struct SetSubscriptionSchedulePaused {
+    pub is_paused: bool,
+}
+
+// 刷新阶段
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, SignalPiece)]
+pub enum RefreshStage {
+    Queued = 0,      // 已到期，等待并发名额
+    Downloading = 1, // 正在下载
+}
+
+// Rust → Dart：刷新进度
+#[derive(Serialize, RustSignal)]
+pub struct SubscriptionRefreshProgress {
+    pub subscription_id: String,
+    pub stage: RefreshStage,
+    pub attempt: u32, // 第几次尝试（连续失败次数 + 1）
+}
+
+// Rust → Dart：刷新结果
+#[derive(Serialize, RustSignal)]
+pub struct SubscriptionRefreshResult {
+    pub subscription_id: String,
+    pub is_successful: bool,
+    pub outcome: DownloadOutcome,
+    pub is_config_updated: bool,           // 订阅配置文件已写入新内容
+    pub diff: Option<ProfileDiff>,         // 替换已有配置时与旧版本的差异
+    pub parse_report: Option<ParseReport>, // 写入新内容时的解析报告
+    pub subscription_info: Option<SubscriptionInfoData>,
+    pub cache_age_seconds: Option<u64>,
+    pub proxy_mode_used: Option<ProxyMode>,
+    pub error_message: Option<String>,
+    pub consecutive_failures: u32,
+    pub next_refresh_at: i64, // 下次刷新时间（Unix 秒）
+}
+
+// 单个订阅的调度状态
+struct ScheduleEntry {
+    subscription: ScheduledSubscription,
+    next_due: i64, // 下次到期时间（Unix 秒，使用墙上时间以正确跨越休眠）
+    consecutive_failures: u32,
+    header_interval_hours: Option<u32>,
+    is_running: bool,
+}
+
+// 调度器状态
+struct SchedulerState {
+    entries: HashMap<String, ScheduleEntry>,
+    semaphore: Arc<Semaphore>,
+    is_user_paused: bool,
+    is_suspended: bool,
+}
+
+static SCHEDULER: Lazy<Arc<Mutex<SchedulerState>>> = Lazy::new(|| {
+    Arc::new(Mutex::new(SchedulerState {
+        entries: HashMap::new(),
+        semaphore: Arc::new(Semaphore::new(2)),
+        is_user_paused: false,
+        is_suspended: false,
+    }))
+});
+
+impl ConfigureSubscriptionScheduleRequest {
+    pub async fn handle(self) {
+        log::info!(
+            "设置订阅自动更新计划：{}个订阅，并发数：{}",
+            self.subscriptions.len(),
+            self.max_concurrency
+        );
+
+        let now = now_seconds();
+        let mut state = SCHEDULER.lock().await;
+        state.semaphore = Arc::new(Semaphore::new(self.max_concurrency.max(1) as usize));
+
+        let mut entries = HashMap::new();
+        for subscription in self.subscriptions {
+            let id = subscription.subscription_id.clone();
+            // 保留已有订阅的进度，仅更新参数；新订阅在抖动后首次刷新
+            let entry = match state.entries.remove(&id) {
+                Some(mut entry) => {
+                    if entry.subscription.interval_minutes != subscription.interval_minutes {
+                        entry.next_due = entry
+                            .next_due
+                            .min(now + with_jitter(interval_seconds(&subscription, None)));
+                    }
+                    entry.subscription = subscription;
+                    entry
+                }
+                None => ScheduleEntry {
+                    next_due: initial_due(&subscription, now),
+                    subscription,
+                    consecutive_failures: 0,
+                    header_interval_hours: None,
+                    is_running: false,
+                },
+            };
+            entries.insert(id, entry);
+        }
+        state.entries = entries;
+    }
+}
+
+impl SetSubscriptionSchedulePaused {
+    pub async fn handle(self) {
+        log::info!(
+            "订阅自动更新已{}",
+            if self.is_paused { "暂停" } else { "恢复" }
+        );
+        SCHEDULER.lock().await.is_user_paused = self.is_paused;
+    }
+}
+
+// 检查到期订阅并启动刷新任务
+async fn run_due_refreshes() {
+    let now = now_seconds();
+    let mut state = SCHEDULER.lock().await;
+    if state.is_user_paused || state.is_suspended {
+        return;
+    }
+
+    let semaphore = Arc::clone(&state.semaphore);
+    for entry in state.entries.values_mut() {
+        if entry.is_running || entry.next_due > now {
+            continue;
+        }
+
+        entry.is_running = true;
+        let subscription = entry.subscription.clone();
+        let attempt = entry.consecutive_failures + 1;
+        let semaphore = Arc::clone(&semaphore);
+
+        SubscriptionRefreshProgress {
+            subscription_id: subscription.subscription_id.clone(),
+            stage: RefreshStage::Queued,
+            attempt,
+        }
+        .send_signal_to_dart();
+
+        tokio::spawn(async move {
+            let Ok(_permit) = semaphore.acquire_owned().await else {
+                return;
+            };
+
+            SubscriptionRefreshProgress {
+                subscription_id: subscription.subscription_id.clone(),
+                stage: RefreshStage::Downloading,
+                attempt,
+            }
+            .send_signal_to_dart();
+
+            log::info!(
+                "自动更新订阅「{}」（第 {} 次尝试）",
+                subscription.subscription_id,
+                attempt
+            );
+            let mut result = subscription.request.download_with_cache().await;
+            let written = save_refreshed_config(&subscription, &mut result);
+            finish_refresh(subscription, result, written).await;
+        });
+    }
+}
+
+// 已写入的订阅配置：与旧配置的差异及解析报告
+struct WrittenConfig {
+    diff: Option<ProfileDiff>,
+    report: ParseReport,
+}
+
+// 将刷新到的内容解析后写入订阅配置文件，未写入时返回 None。
+// 内容无法解析时清除缓存（避免之后 304 一直返回无效内容），并将本次刷新视为失败。
+fn save_refreshed_config(
+    subscription: &ScheduledSubscription,
+    result: &mut CachedDownload,
+) -> Option<WrittenConfig> {
+    let should_write = match result.outcome {
+        DownloadOutcome::Downloaded => true,
+        DownloadOutcome::NotModified => !Path::new(&subscription.config_path).exists(),
+        DownloadOutcome::CachedFallback | DownloadOutcome::Failed => false,
+    };
+    if !should_write {
+        return None;
+    }
+
+    match write_config(
+        &subscription.config_path,
+        &result.content,
+        &subscription.parse_options,
+    ) {
+        Ok(written) => {
+            log::info!(
+                "订阅「{}」配置已写入：{}",
+                subscription.subscription_id,
+                subscription.config_path
+            );
+            Some(written)
+        }
+        Err(e) => {
+            if let Err(e) = SubscriptionCache::open_default().remove(&subscription.request.url) {
+                log::warn!("{}", e);
+            }
+            result.outcome = DownloadOutcome::Failed;
+            result.error_message = Some(e);
+            None
+        }
+    }
+}
+
+// 解析订阅内容并校验后写入配置文件（先写临时文件再替换），
+// 返回解析报告，原有配置存在时附带新旧版本差异
+fn write_config(
+    path: &str,
+    content: &str,
+    options: &SubscriptionParseOptions,
+) -> Result<WrittenConfig, String> {
+    let (parsed, report) = ProxyParser::parse_subscription_with_report(content, options)?;
+
+    let config: YamlValue =
+        serde_yaml_ng::from_str(&parsed).map_err(|e| format!("订阅配置解析失败：{}", e))?;
+    let has_proxies = ["proxies", "proxy-groups"]
+        .iter()
+        .any(|key| config.get(key).is_some());
+    if !has_proxies {
+        return Err("配置文件格式不正确，缺少 proxies 或 proxy-groups 字段".to_string());
+    }
+
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建订阅目录失败：{}", e))?;
+    }
+    let old_content = std::fs::read_to_string(path).ok();
+    let temp_path = path.with_extension("refresh.tmp");
+    std::fs::write(&temp_path, &parsed).map_err(|e| format!("写入订阅配置失败：{}", e))?;
+    std::fs::rename(&temp_path, path).map_err(|e| format!("替换订阅配置失败：{}", e))?;
+
+    // 差异仅用于提示，比较失败不影响本次写入
+    let diff =
+        old_content.and_then(
+            |old_content| match ProfileDiffer::diff(&old_content, &parsed) {
+                Ok(diff) => Some(diff),
+                Err(e) => {
+                    log::warn!("订阅版本差异比较失败：{}", e);
+                    None
+                }
+            },
+        );
+    Ok(WrittenConfig { diff, report })
+}
+
+// 记录刷新结果并计算下次到期时间
+async fn finish_refresh(
+    subscription: ScheduledSubscription,
+    result: CachedDownload,
+    written: Option<WrittenConfig>,
+) {
+    let id = subscription.subscription_id.clone();
+    let now = now_seconds();
+    // 回退到缓存也视为失败，以便退避重试
+    let is_fresh = matches!(
+        result.outcome,
+        DownloadOutcome::Downloaded | DownloadOutcome::NotModified
+    );
+
+    let mut state = SCHEDULER.lock().await;
+    let Some(entry) = state.entries.get_mut(&id) else {
+        log::debug!("订阅「{}」已从计划中移除，忽略刷新结果", id);
+        return;
+    };
+
+    entry.is_running = false;
+    if is_fresh {
+        entry.consecutive_failures = 0;
+        if let Some(hours) = result
+            .subscription_info
+            .as_ref()
+            .and_then(|info| info.update_interval_hours)
+        {
+            entry.header_interval_hours = Some(hours);
+        }
+    } else {
+        entry.consecutive_failures += 1;
+    }
+
+    let interval = interval_seconds(&entry.subscription, entry.header_interval_hours);
+    let delay = if is_fresh {
+        interval
+    } else {
+        backoff_seconds(entry.consecutive_failures, interval)
+    };
+    entry.next_due = now + with_jitter(delay);
+
+    let consecutive_failures = entry.consecutive_failures;
+    let next_refresh_at = entry.next_due;
+    drop(state);
+
+    if is_fresh {
+        log::info!("订阅「{}」自动更新成功：{:?}", id, result.outcome);
+    } else {
+        log::warn!(
+            "订阅「{}」自动更新失败（连续 {} 次），{} 秒后重试：{}",
+            id,
+            consecutive_failures,
+            next_refresh_at - now,
+            result.error_message.as_deref().unwrap_or_default()
+        );
+    }
+
+    SubscriptionRefreshResult {
+        subscription_id: id,
+        is_successful: result.is_successful(),
+        outcome: result.outcome,
+        is_config_updated: written.is_some(),
+        diff: written.as_ref().and_then(|written| written.diff.clone()),
+        parse_report: written.map(|written| written.report),
+        subscription_info: result.subscription_info,
+        cache_age_seconds: result.cache_age_seconds,
+        proxy_mode_used: result.proxy_mode_used,
+        error_message: result.error_message,
+        consecutive_failures,
+        next_refresh_at,
+    }
+    .send_signal_to_dart();
+}
+
+// 处理电源事件：休眠时暂停，唤醒后延迟恢复并错开到期的订阅
+async fn handle_power_event(event_type: PowerEventType) {
+    let mut state = SCHEDULER.lock().await;
+    match event_type {
+        PowerEventType::Suspend => {
+            log::info!("系统休眠，暂停订阅自动更新");
+            state.is_suspended = true;
+        }
+        PowerEventType::ResumeAutomatic | PowerEventType::ResumeSuspend => {
+            if !state.is_suspended {
+                return;
+            }
+            log::info!("系统唤醒，{} 秒后恢复订阅自动更新", RESUME_DELAY_SECONDS);
+            state.is_suspended = false;
+
+            let resume_at = now_seconds() + RESUME_DELAY_SECONDS;
+            for entry in state.entries.values_mut() {
+                if entry.next_due < resume_at {
+                    entry.next_due =
+                        resume_at + rand::rng().random_range(0..=RESUME_DELAY_SECONDS * 2);
+                }
+            }
+        }
+    }
+}
+
+// 新加入计划的订阅首次到期时间：按上次更新时间计算，已过期的在抖动后尽快刷新
+fn initial_due(subscription: &ScheduledSubscription, now: i64) -> i64 {
+    let earliest = now + with_jitter(TICK_INTERVAL.as_secs() as i64 * 2);
+    match subscription.last_refreshed_at {
+        Some(refreshed_at) => (refreshed_at + interval_seconds(subscription, None)).max(earliest),
+        None => earliest,
+    }
+}
+
+// 刷新间隔（秒）：优先使用订阅响应头提供的间隔
+fn interval_seconds(subscription: &ScheduledSubscription, header_hours: Option<u32>) -> i64 {
+    match header_hours {
+        Some(hours) => hours as i64 * 3600,
+        None => subscription.interval_minutes.max(1) as i64 * 60,
+    }
+}
+
+// 失败退避（秒）：从 1 分钟开始翻倍，不超过刷新间隔与 6 小时
+fn backoff_seconds(consecutive_failures: u32, interval: i64) -> i64 {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    let backoff = BACKOFF_BASE_SECONDS.saturating_mul(1 << exponent);
+    backoff.min(interval).min(BACKOFF_MAX_SECONDS)
+}
+
+// 为时长添加 ±10% 的随机抖动，避免多个订阅同时刷新
+fn with_jitter(seconds: i64) -> i64 {
+    let spread = (seconds as f64 * JITTER_RATIO) as i64;
+    if spread == 0 {
+        return seconds;
+    }
+    seconds + rand::rng().random_range(-spread..=spread)
+}
+
+fn now_seconds() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+pub fn init() {
+    use tokio::spawn;
+
+    // 设置计划请求监听器
+    spawn(async {
+        let receiver = ConfigureSubscriptionScheduleRequest::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle().await;
+        }
+    });
+
+    // 暂停/恢复请求监听器
+    spawn(async {
+        let receiver = SetSubscriptionSchedulePaused::get_dart_signal_receiver();
+        while let Some(dart_signal) = receiver.recv().await {
+            dart_signal.message.handle().await;
+        }
+    });
+
+    // 电源事件监听
+    spawn(async {
+        let mut receiver = subscribe_power_events();
+        loop {
+            match receiver.recv().await {
+                Ok(event_type) => handle_power_event(event_type).await,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+
+    // 调度循环
+    spawn(async {
+        let mut ticker = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            run_due_refreshes().await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_and_interval() {
+        assert_eq!(backoff_seconds(1, 86400), 60);
+        assert_eq!(backoff_seconds(3, 86400), 240);
+        assert_eq!(backoff_seconds(20, 86400), BACKOFF_MAX_SECONDS);
+        assert_eq!(backoff_seconds(10, 1800), 1800);
+
+        let jittered = with_jitter(1000);
+        assert!((900..=1100).contains(&jittered));
+    }
+
+    #[test]
+    fn test_write_config() -> Result<(), String> {
+        let dir = std::env::temp_dir().join(format!("subscription_refresh_{}", std::process::id()));
+        let path = dir.join("sub.yaml");
+        let path_str = path.to_string_lossy().to_string();
+        let options = SubscriptionParseOptions::default();
+
+        // 首次写入没有旧版本可比较
+        let written = write_config(
+            &path_str,
+            "proxies:\n  - {name: HK, type: ss, server: a.com, port: 1, cipher: aes-128-gcm, password: p}\n",
+            &options,
+        )?;
+        assert!(written.diff.is_none());
+        assert_eq!(written.report.accepted_count, 1);
+
+        // 替换已有配置时返回差异
+        let diff = write_config(
+            &path_str,
+            "proxies:\n  - {name: HK, type: ss, server: a.com, port: 1, cipher: aes-128-gcm, password: p}\n  - {name: JP, type: ss, server: b.com, port: 2, cipher: aes-128-gcm, password: p}\n",
+            &options,
+        )?
+        .diff
+        .ok_or("缺少版本差异")?;
+        assert!(diff.has_changes);
+        assert_eq!(diff.added_nodes, vec!["JP".to_string()]);
+        assert_eq!(diff.unchanged_node_count, 1);
+        let saved = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        assert!(saved.contains("JP"));
+
+        // 无效内容不覆盖已有配置
+        assert!(write_config(&path_str, "hello: world", &options).is_err());
+        let unchanged = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        assert_eq!(unchanged, saved);
+
+        std::fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
@@ -21,6 +21,7 @@ pub use loopback::{
 };
 pub use power_event::{
     PowerEventType, SystemPowerEvent, start_power_event_listener, stop_power_event_listener,
+    subscribe_power_events,
 };
 pub use url_launcher::{OpenUrl, OpenUrlResult};
 
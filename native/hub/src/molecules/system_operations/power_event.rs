@@ -23,8 +23,10 @@ use windows::Win32::UI::WindowsAndMessaging::{
 #[cfg(target_os = "windows")]
 use windows::core::GUID;
 
+use once_cell::sync::Lazy;
 use rinf::{RustSignal, SignalPiece};
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SignalPiece)]
 pub enum PowerEventType {
@@ -38,6 +40,23 @@ pub struct SystemPowerEvent {
     pub event_type: PowerEventType,
 }
 
+// Rust 内部的电源事件广播（供订阅自动更新等后台任务在休眠期间暂停）
+static POWER_EVENT_CHANNEL: Lazy<broadcast::Sender<PowerEventType>> =
+    Lazy::new(|| broadcast::channel(16).0);
+
+// 订阅电源事件
+pub fn subscribe_power_events() -> broadcast::Receiver<PowerEventType> {
+    POWER_EVENT_CHANNEL.subscribe()
+}
+
+// 同时上报到 Flutter 与 Rust 内部订阅者
+#[cfg(target_os = "windows")]
+fn publish_power_event(event_type: PowerEventType) {
+    SystemPowerEvent { event_type }.send_signal_to_dart();
+    // 没有订阅者时发送失败，可忽略
+    let _ = POWER_EVENT_CHANNEL.send(event_type);
+}
+
 // GUID_MONITOR_POWER_ON: 监视器电源状态
 #[cfg(target_os = "windows")]
 #[allow(dead_code)]
@@ -89,26 +108,17 @@ unsafe extern "system" fn window_proc(
             match event_type {
                 PBT_APMSUSPEND => {
                     log::info!("系统进入休眠");
-                    SystemPowerEvent {
-                        event_type: PowerEventType::Suspend,
-                    }
-                    .send_signal_to_dart();
+                    publish_power_event(PowerEventType::Suspend);
                 }
 
                 PBT_APMRESUMEAUTOMATIC => {
                     log::info!("系统自动唤醒");
-                    SystemPowerEvent {
-                        event_type: PowerEventType::ResumeAutomatic,
-                    }
-                    .send_signal_to_dart();
+                    publish_power_event(PowerEventType::ResumeAutomatic);
                 }
 
                 PBT_APMRESUMESUSPEND => {
                     log::info!("用户唤醒系统");
-                    SystemPowerEvent {
-                        event_type: PowerEventType::ResumeSuspend,
-                    }
-                    .send_signal_to_dart();
+                    publish_power_event(PowerEventType::ResumeSuspend);
                 }
 
                 PBT_POWERSETTINGCHANGE => {